@@ -0,0 +1,590 @@
+use pinocchio::msg;
+
+use crate::{error::AutoBuyerError, state::PriceQ64};
+
+pub use self::u256::U256;
+
+#[allow(clippy::all)]
+mod u256 {
+    uint::construct_uint! {
+        /// 256-битное беззнаковое целое для промежуточных вычислений
+        pub struct U256(4);
+    }
+}
+
+/// Минимальный индекс тика
+pub const MIN_TICK: i32 = -443636;
+
+/// Максимальный индекс тика
+pub const MAX_TICK: i32 = 443636;
+
+/// Минимальная sqrt цена (Q64.64)
+pub const MIN_SQRT_PRICE_X64: u128 = 4295048016;
+
+/// Максимальная sqrt цена (Q64.64)
+pub const MAX_SQRT_PRICE_X64: u128 = 79226673515401279992447579055;
+
+/// Знаменатель ставки комиссии (сотые доли базисного пункта)
+pub const FEE_RATE_DENOMINATOR: u32 = 1_000_000;
+
+/// Результат одного шага обмена внутри диапазона без пересечения тиков
+#[derive(Debug, Clone, Copy)]
+pub struct SwapStep {
+    /// Списанное количество входного токена без комиссии
+    pub amount_in: u64,
+    /// Полученное количество выходного токена
+    pub amount_out: u64,
+    /// Sqrt цена после шага
+    pub next_sqrt_price: u128,
+    /// Комиссия шага
+    pub fee_amount: u64,
+}
+
+/// Инициализированный тик
+#[derive(Debug, Clone, Copy)]
+pub struct InitializedTick {
+    /// Индекс тика
+    pub index: i32,
+    /// Изменение ликвидности при пересечении слева направо
+    pub liquidity_net: i128,
+}
+
+/// Тики из переданных массивов тиков, покрывающие непрерывный диапазон
+#[derive(Debug, Clone)]
+pub struct TickRange {
+    /// Инициализированные тики в порядке обхода
+    pub ticks: Vec<InitializedTick>,
+    /// Граничный тик диапазона в направлении обмена
+    pub boundary_tick: i32,
+}
+
+/// Sqrt цена (Q64.64) для индекса тика
+pub fn sqrt_price_at_tick(tick: i32) -> Result<u128, AutoBuyerError> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return Err(AutoBuyerError::MathOverflow);
+    }
+
+    if tick >= 0 {
+        Ok(sqrt_price_positive_tick(tick))
+    } else {
+        Ok(sqrt_price_negative_tick(tick))
+    }
+}
+
+fn sqrt_price_positive_tick(tick: i32) -> u128 {
+    // Константы в Q32.96, результат сдвигается к Q64.64
+    const RATIOS: [(i32, u128); 18] = [
+        (0x2, 79236085330515764027303304731),
+        (0x4, 79244008939048815603706035061),
+        (0x8, 79259858533276714757314932305),
+        (0x10, 79291567232598584799939703904),
+        (0x20, 79355022692464371645785046466),
+        (0x40, 79482085999252804386437311141),
+        (0x80, 79736823300114093921829183326),
+        (0x100, 80248749790819932309965073892),
+        (0x200, 81282483887344747381513967011),
+        (0x400, 83390072131320151908154831281),
+        (0x800, 87770609709833776024991924138),
+        (0x1000, 97234110755111693312479820773),
+        (0x2000, 119332217159966728226237229890),
+        (0x4000, 179736315981702064433883588727),
+        (0x8000, 407748233172238350107850275304),
+        (0x10000, 2098478828474011932436660412517),
+        (0x20000, 55581415166113811149459800483533),
+        (0x40000, 38992368544603139932233054999993551),
+    ];
+
+    let mut ratio = if tick & 0x1 != 0 {
+        U256::from(79232123823359799118286999567u128)
+    } else {
+        U256::from(79228162514264337593543950336u128)
+    };
+
+    for (bit, factor) in RATIOS {
+        if tick & bit != 0 {
+            ratio = (ratio * U256::from(factor)) >> 96;
+        }
+    }
+
+    (ratio >> 32).as_u128()
+}
+
+fn sqrt_price_negative_tick(tick: i32) -> u128 {
+    // Константы в Q64.64
+    const RATIOS: [(i32, u128); 18] = [
+        (0x2, 18444899583751176498),
+        (0x4, 18443055278223354162),
+        (0x8, 18439367220385604838),
+        (0x10, 18431993317065449817),
+        (0x20, 18417254355718160513),
+        (0x40, 18387811781193591352),
+        (0x80, 18329067761203520168),
+        (0x100, 18212142134806087854),
+        (0x200, 17980523815641551639),
+        (0x400, 17526086738831147013),
+        (0x800, 16651378430235024244),
+        (0x1000, 15030750278693429944),
+        (0x2000, 12247334978882834399),
+        (0x4000, 8131365268884726200),
+        (0x8000, 3584323654723342297),
+        (0x10000, 696457651847595233),
+        (0x20000, 26294789957452057),
+        (0x40000, 37481735321082),
+    ];
+
+    let abs_tick = tick.abs();
+    let mut ratio: u128 = if abs_tick & 0x1 != 0 {
+        18445821805675392311
+    } else {
+        18446744073709551616
+    };
+
+    for (bit, factor) in RATIOS {
+        if abs_tick & bit != 0 {
+            ratio = (ratio * factor) >> 64;
+        }
+    }
+
+    ratio
+}
+
+/// Изменение количества токена A между двумя sqrt ценами
+///
+/// `L * (upper - lower) * 2^64 / (lower * upper)`. Возвращает `None`, если результат не помещается в u64.
+pub fn amount_delta_a(
+    sqrt_price_0: u128,
+    sqrt_price_1: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Option<u64> {
+    let (lower, upper) = if sqrt_price_0 < sqrt_price_1 {
+        (sqrt_price_0, sqrt_price_1)
+    } else {
+        (sqrt_price_1, sqrt_price_0)
+    };
+
+    let product = U256::from(liquidity) * U256::from(upper - lower);
+    if product.bits() > 256 - 64 {
+        return None;
+    }
+    let numerator = product << 64;
+    let denominator = U256::from(lower) * U256::from(upper);
+    if denominator.is_zero() {
+        return None;
+    }
+
+    let (quotient, remainder) = numerator.div_mod(denominator);
+    let result = if round_up && !remainder.is_zero() {
+        quotient + 1
+    } else {
+        quotient
+    };
+
+    (result <= U256::from(u64::MAX)).then(|| result.as_u64())
+}
+
+/// Изменение количества токена B между двумя sqrt ценами
+///
+/// `L * (upper - lower) / 2^64`. Возвращает `None`, если результат не помещается в u64.
+pub fn amount_delta_b(
+    sqrt_price_0: u128,
+    sqrt_price_1: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Option<u64> {
+    let diff = sqrt_price_0.abs_diff(sqrt_price_1);
+    let product = U256::from(liquidity) * U256::from(diff);
+
+    let quotient = product >> 64;
+    let has_remainder = !(product & U256::from(u64::MAX)).is_zero();
+    let result = if round_up && has_remainder {
+        quotient + 1
+    } else {
+        quotient
+    };
+
+    (result <= U256::from(u64::MAX)).then(|| result.as_u64())
+}
+
+/// Следующая sqrt цена после добавления `amount` входного токена
+pub fn next_sqrt_price_from_input(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount: u64,
+    a_to_b: bool,
+) -> Result<u128, AutoBuyerError> {
+    if amount == 0 {
+        return Ok(sqrt_price);
+    }
+    if liquidity == 0 {
+        return Err(AutoBuyerError::InsufficientLiquidity);
+    }
+
+    if a_to_b {
+        // ceil(L * 2^64 * sqrt_price / (L * 2^64 + amount * sqrt_price)), как в Orca
+        let product = U256::from(liquidity) * U256::from(sqrt_price);
+        if product.bits() > 256 - 64 {
+            return Err(AutoBuyerError::MathOverflow);
+        }
+        let numerator = product << 64;
+        let denominator =
+            (U256::from(liquidity) << 64) + U256::from(sqrt_price) * U256::from(amount);
+        let (quotient, remainder) = numerator.div_mod(denominator);
+        let result = if remainder.is_zero() {
+            quotient
+        } else {
+            quotient + 1
+        };
+        if result < U256::from(MIN_SQRT_PRICE_X64) {
+            return Err(AutoBuyerError::MathOverflow);
+        }
+        Ok(result.as_u128())
+    } else {
+        let delta = ((amount as u128) << 64) / liquidity;
+        sqrt_price
+            .checked_add(delta)
+            .ok_or(AutoBuyerError::MathOverflow)
+    }
+}
+
+/// Рассчитать шаг обмена с фиксированным входом от текущей до целевой sqrt цены
+pub fn compute_swap_step(
+    amount_remaining: u64,
+    fee_rate: u32,
+    liquidity: u128,
+    sqrt_price_current: u128,
+    sqrt_price_target: u128,
+    a_to_b: bool,
+) -> Result<SwapStep, AutoBuyerError> {
+    let amount_remaining_less_fee = ((amount_remaining as u128)
+        * (FEE_RATE_DENOMINATOR - fee_rate) as u128
+        / FEE_RATE_DENOMINATOR as u128) as u64;
+
+    let amount_to_target = if a_to_b {
+        amount_delta_a(sqrt_price_current, sqrt_price_target, liquidity, true)
+    } else {
+        amount_delta_b(sqrt_price_current, sqrt_price_target, liquidity, true)
+    };
+
+    let reaches_target = amount_to_target.is_some_and(|amount| amount_remaining_less_fee >= amount);
+
+    let next_sqrt_price = if reaches_target {
+        sqrt_price_target
+    } else {
+        next_sqrt_price_from_input(
+            sqrt_price_current,
+            liquidity,
+            amount_remaining_less_fee,
+            a_to_b,
+        )?
+    };
+
+    let (amount_in, amount_out) = if a_to_b {
+        (
+            amount_delta_a(sqrt_price_current, next_sqrt_price, liquidity, true),
+            amount_delta_b(sqrt_price_current, next_sqrt_price, liquidity, false),
+        )
+    } else {
+        (
+            amount_delta_b(sqrt_price_current, next_sqrt_price, liquidity, true),
+            amount_delta_a(sqrt_price_current, next_sqrt_price, liquidity, false),
+        )
+    };
+    let amount_in = amount_in.ok_or(AutoBuyerError::MathOverflow)?;
+    let amount_out = amount_out.ok_or(AutoBuyerError::MathOverflow)?;
+
+    let fee_amount = if reaches_target {
+        let numerator = amount_in as u128 * fee_rate as u128;
+        let denominator = (FEE_RATE_DENOMINATOR - fee_rate) as u128;
+        u64::try_from(numerator.div_ceil(denominator)).map_err(|_| AutoBuyerError::MathOverflow)?
+    } else {
+        amount_remaining
+            .checked_sub(amount_in)
+            .ok_or(AutoBuyerError::MathOverflow)?
+    };
+
+    Ok(SwapStep {
+        amount_in,
+        amount_out,
+        next_sqrt_price,
+        fee_amount,
+    })
+}
+
+/// Спотовая цена (выходной токен за единицу входного) из sqrt цены токена B в токене A
+pub fn spot_price_from_sqrt(sqrt_price: u128, a_to_b: bool) -> PriceQ64 {
+    let price_x128 = U256::from(sqrt_price) * U256::from(sqrt_price);
+
+    let price = if a_to_b {
+        price_x128 >> 64
+    } else if price_x128.is_zero() {
+        U256::zero()
+    } else {
+        (U256::one() << 192) / price_x128
+    };
+
+    PriceQ64(if price > U256::from(u128::MAX) {
+        u128::MAX
+    } else {
+        price.as_u128()
+    })
+}
+
+/// Рассчитать обмен с фиксированным входом, проходя по инициализированным тикам
+///
+/// Возвращает полученное количество и суммарную комиссию.
+pub fn swap_exact_in(
+    sqrt_price: u128,
+    liquidity: u128,
+    fee_rate: u32,
+    tick_range: &TickRange,
+    amount_in: u64,
+    a_to_b: bool,
+) -> Result<(u64, u64), AutoBuyerError> {
+    let mut amount_remaining = amount_in;
+    let mut amount_out: u64 = 0;
+    let mut fee_total: u64 = 0;
+    let mut sqrt_price = sqrt_price;
+    let mut liquidity = liquidity;
+    let mut ticks = tick_range.ticks.iter();
+    let mut next_tick = ticks.next();
+
+    while amount_remaining > 0 {
+        let target_tick = next_tick.map_or(tick_range.boundary_tick, |tick| tick.index);
+        let sqrt_price_target = sqrt_price_at_tick(target_tick)?;
+
+        let step = compute_swap_step(
+            amount_remaining,
+            fee_rate,
+            liquidity,
+            sqrt_price,
+            sqrt_price_target,
+            a_to_b,
+        )?;
+
+        amount_remaining = amount_remaining
+            .checked_sub(step.amount_in)
+            .and_then(|x| x.checked_sub(step.fee_amount))
+            .ok_or(AutoBuyerError::MathOverflow)?;
+        amount_out = amount_out
+            .checked_add(step.amount_out)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+        fee_total = fee_total
+            .checked_add(step.fee_amount)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+        sqrt_price = step.next_sqrt_price;
+
+        if step.next_sqrt_price != sqrt_price_target {
+            continue;
+        }
+
+        // Пересечение инициализированного тика или выход за переданные массивы тиков
+        match next_tick {
+            Some(tick) => {
+                let liquidity_net = if a_to_b {
+                    -tick.liquidity_net
+                } else {
+                    tick.liquidity_net
+                };
+                liquidity = liquidity
+                    .checked_add_signed(liquidity_net)
+                    .ok_or(AutoBuyerError::MathOverflow)?;
+                next_tick = ticks.next();
+            }
+            None if amount_remaining > 0 => {
+                msg!("Error: Swap exceeds liquidity in supplied tick arrays");
+                return Err(AutoBuyerError::InsufficientLiquidity);
+            }
+            None => {}
+        }
+    }
+
+    Ok((amount_out, fee_total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sqrt цена 1.0 в Q64.64
+    const ONE_X64: u128 = 1 << 64;
+
+    #[test]
+    fn sqrt_price_at_tick_bounds() {
+        assert_eq!(sqrt_price_at_tick(0).unwrap(), ONE_X64);
+        assert_eq!(sqrt_price_at_tick(MIN_TICK).unwrap(), MIN_SQRT_PRICE_X64);
+        assert_eq!(sqrt_price_at_tick(MAX_TICK).unwrap(), MAX_SQRT_PRICE_X64);
+        assert!(sqrt_price_at_tick(MIN_TICK - 1).is_err());
+        assert!(sqrt_price_at_tick(MAX_TICK + 1).is_err());
+    }
+
+    #[test]
+    fn sqrt_price_at_tick_matches_orca_vectors() {
+        // Результаты битовой аппроксимации Orca `sqrt_price_from_tick_index`
+        let vectors: [(i32, u128); 8] = [
+            (1, 18447666387855959850),
+            (-1, 18445821805675392311),
+            (10, 18455969290605290427),
+            (-10, 18437523468038800957),
+            (1000, 19392480388906836277),
+            (-1000, 17547129613991598777),
+            (100000, 2737055259406582257880),
+            (-100000, 124324258982887573),
+        ];
+
+        for (tick, expected) in vectors {
+            assert_eq!(sqrt_price_at_tick(tick).unwrap(), expected, "tick {}", tick);
+        }
+    }
+
+    #[test]
+    fn sqrt_price_at_tick_is_monotonic() {
+        let mut previous = sqrt_price_at_tick(MIN_TICK).unwrap();
+        for tick in (MIN_TICK + 1..=MAX_TICK).step_by(997) {
+            let current = sqrt_price_at_tick(tick).unwrap();
+            assert!(current > previous, "tick {}", tick);
+            previous = current;
+        }
+    }
+
+    #[test]
+    fn next_sqrt_price_from_input_b_to_a_rounds_down() {
+        // sqrt + floor(amount * 2^64 / L)
+        assert_eq!(
+            next_sqrt_price_from_input(ONE_X64, 1_000_000_000, 1_000_000, false).unwrap(),
+            18465190817783261167
+        );
+    }
+
+    #[test]
+    fn next_sqrt_price_from_input_a_to_b_rounds_up() {
+        // ceil(L * 2^64 * sqrt / (L * 2^64 + amount * sqrt)), Orca `get_next_sqrt_price_from_a_round_up`
+        let vectors: [(u128, u128, u64, u128); 5] = [
+            (ONE_X64, 1_000_000_000, 1_000_000, 18428315757951600016),
+            (
+                17547129613991598777,
+                1_000_000_000,
+                1_000_000,
+                17530454088551727079,
+            ),
+            (
+                23685792191261108451,
+                7_312_000_000_000,
+                250_000_000,
+                23684752412679128415,
+            ),
+            (
+                26408891171800426664149193018,
+                1 << 70,
+                1 << 40,
+                11318217766434452547292074519,
+            ),
+            (
+                4116333652252349704,
+                123_456_789_012,
+                9_876_543,
+                4116260169799367644,
+            ),
+        ];
+
+        for (sqrt_price, liquidity, amount, expected) in vectors {
+            assert_eq!(
+                next_sqrt_price_from_input(sqrt_price, liquidity, amount, true).unwrap(),
+                expected,
+                "sqrt price {}",
+                sqrt_price
+            );
+        }
+    }
+
+    #[test]
+    fn next_sqrt_price_from_input_edge_cases() {
+        assert_eq!(
+            next_sqrt_price_from_input(ONE_X64, 1_000, 0, true).unwrap(),
+            ONE_X64
+        );
+        assert!(next_sqrt_price_from_input(ONE_X64, 0, 1, true).is_err());
+        // Цена ниже минимальной
+        assert!(next_sqrt_price_from_input(MIN_SQRT_PRICE_X64, 1, u64::MAX, true).is_err());
+        // L * sqrt * 2^64 не помещается в 256 бит
+        assert!(next_sqrt_price_from_input(u128::MAX, u128::MAX, 1, true).is_err());
+    }
+
+    #[test]
+    fn compute_swap_step_reaches_target() {
+        let target = 18455969290605290427;
+        let step = compute_swap_step(
+            1_000_000_000_000,
+            3000,
+            1_000_000_000,
+            ONE_X64,
+            target,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(step.next_sqrt_price, target);
+        assert_eq!(step.amount_in, 500101);
+        assert_eq!(step.amount_out, 499850);
+        // ceil(amount_in * fee_rate / (1e6 - fee_rate))
+        assert_eq!(step.fee_amount, 1505);
+    }
+
+    #[test]
+    fn compute_swap_step_b_to_a_within_range() {
+        let target = sqrt_price_at_tick(1000).unwrap();
+        let step =
+            compute_swap_step(1_000_000, 3000, 1_000_000_000, ONE_X64, target, false).unwrap();
+
+        assert_eq!(step.next_sqrt_price, 18465135477551040038);
+        assert_eq!(step.amount_in, 997000);
+        assert_eq!(step.amount_out, 996006);
+        assert_eq!(step.fee_amount, 3000);
+    }
+
+    #[test]
+    fn compute_swap_step_a_to_b_within_range() {
+        let target = sqrt_price_at_tick(-1000).unwrap();
+        let step =
+            compute_swap_step(1_000_000, 3000, 1_000_000_000, ONE_X64, target, true).unwrap();
+
+        assert_eq!(step.next_sqrt_price, 18428370987834680440);
+        assert_eq!(step.amount_in, 997000);
+        assert_eq!(step.amount_out, 996006);
+        assert_eq!(step.fee_amount, 3000);
+
+        // Та же ликвидность при sqrt цене тика 500
+        let step = compute_swap_step(
+            1_000_000,
+            3000,
+            1_000_000_000,
+            sqrt_price_at_tick(500).unwrap(),
+            target,
+            true,
+        )
+        .unwrap();
+        assert_eq!(step.next_sqrt_price, 18894387423778886412);
+        assert_eq!(step.amount_in, 997000);
+        assert_eq!(step.amount_out, 1047044);
+        assert_eq!(step.fee_amount, 3000);
+    }
+
+    #[test]
+    fn swap_exact_in_crosses_initialized_tick() {
+        let tick_range = TickRange {
+            ticks: vec![InitializedTick {
+                index: 10,
+                liquidity_net: -500_000_000,
+            }],
+            boundary_tick: 1000,
+        };
+
+        let (amount_out, fee) =
+            swap_exact_in(ONE_X64, 1_000_000_000, 3000, &tick_range, 1_000_000, false).unwrap();
+
+        // Шаг до тика 10 как в compute_swap_step_reaches_target, затем остаток
+        // исполняется при половинной ликвидности
+        assert_eq!(amount_out, 995758);
+        assert_eq!(fee, 3001);
+    }
+}
@@ -0,0 +1,962 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::cell::OnceCell;
+use spl_token::{solana_program::program_pack::Pack, state::Mint};
+
+use crate::{
+    dex::{
+        get_token_balance, load_anchor_account,
+        saber::Saber,
+        types::{SwapMode, SwapParams},
+        verify_received_amount, DexInterface,
+    },
+    error::AutoBuyerError,
+    state::{constants, PoolConfig, PriceQ64, SwapCalculation, TradingPair},
+};
+
+/// Дискриминатор аккаунта Pool
+const POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+
+/// Дискриминатор аккаунта Vault
+const VAULT_DISCRIMINATOR: [u8; 8] = [211, 8, 232, 43, 2, 152, 117, 119];
+
+/// Дискриминатор инструкции swap
+const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+
+/// Знаменатель скорости разблокировки прибыли хранилища
+const LOCKED_PROFIT_DEGRADATION_DENOMINATOR: u128 = 1_000_000_000_000;
+
+/// Тип активации пула по слоту
+const ACTIVATION_TYPE_SLOT: u8 = 0;
+
+/// Тип активации пула по времени
+const ACTIVATION_TYPE_TIMESTAMP: u8 = 1;
+
+/// Количество аккаунтов, необходимых для обмена через Meteora Dynamic AMM
+const DAMM_ACCOUNTS_LEN: usize = 19;
+
+/// Структура для работы с Meteora Dynamic AMM
+///
+/// Ликвидность пула хранится в долях (LP) динамических хранилищ Meteora, которые
+/// размещают средства в кредитных протоколах. Резервы пула считаются как стоимость
+/// этих долей за вычетом еще не разблокированной прибыли хранилища.
+///
+/// Аккаунты (после общих 0-4):
+/// 5. `[]` Программа Meteora Dynamic AMM
+/// 6. `[writable]` Pool
+/// 7. `[writable]` Хранилище токена A
+/// 8. `[writable]` Хранилище токена B
+/// 9. `[writable]` Токеновый аккаунт хранилища A
+/// 10. `[writable]` Токеновый аккаунт хранилища B
+/// 11. `[writable]` LP минт хранилища A
+/// 12. `[writable]` LP минт хранилища B
+/// 13. `[writable]` LP аккаунт пула в хранилище A
+/// 14. `[writable]` LP аккаунт пула в хранилище B
+/// 15. `[writable]` Аккаунт комиссии протокола токена A
+/// 16. `[writable]` Аккаунт комиссии протокола токена B
+/// 17. `[]` Программа хранилищ Meteora
+/// 18. `[]` Программа токенов
+pub struct MeteoraDamm {
+    /// Состояние пула и его хранилищ, загруженные в рамках текущей инструкции
+    pool: OnceCell<(Pubkey, DammPool, DynamicVault, DynamicVault)>,
+}
+
+/// Ставки комиссий пула
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct PoolFees {
+    pub trade_fee_numerator: u64,
+    pub trade_fee_denominator: u64,
+    pub protocol_trade_fee_numerator: u64,
+    pub protocol_trade_fee_denominator: u64,
+}
+
+/// Тип пула
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolType {
+    Permissioned,
+    Permissionless,
+}
+
+/// Параметры активации пула
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct Bootstrapping {
+    pub activation_point: u64,
+    pub whitelisted_vault: Pubkey,
+    pub pool_creator: Pubkey,
+    pub activation_type: u8,
+}
+
+/// Информация о партнере пула
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct PartnerInfo {
+    pub fee_numerator: u64,
+    pub partner_authority: Pubkey,
+    pub pending_fee_a: u64,
+    pub pending_fee_b: u64,
+}
+
+/// Множители приведения токенов к общей точности
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug, Clone, Copy)]
+pub struct TokenMultiplier {
+    pub token_a_multiplier: u64,
+    pub token_b_multiplier: u64,
+    pub precision_factor: u8,
+}
+
+/// Тип отвязки цены (для пулов с токенами стейкинга)
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepegType {
+    None,
+    Marinade,
+    Lido,
+    SplStake,
+}
+
+/// Параметры отвязки цены
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug, Clone, Copy)]
+pub struct Depeg {
+    pub base_virtual_price: u64,
+    pub base_cache_updated: u64,
+    pub depeg_type: DepegType,
+}
+
+/// Кривая пула
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug, Clone, Copy)]
+pub enum CurveType {
+    ConstantProduct,
+    Stable {
+        amp: u64,
+        token_multiplier: TokenMultiplier,
+        depeg: Depeg,
+        last_amp_updated_timestamp: u64,
+    },
+}
+
+/// Состояние пула Meteora Dynamic AMM
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct DammPool {
+    pub lp_mint: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub a_vault: Pubkey,
+    pub b_vault: Pubkey,
+    pub a_vault_lp: Pubkey,
+    pub b_vault_lp: Pubkey,
+    pub a_vault_lp_bump: u8,
+    pub enabled: bool,
+    pub protocol_token_a_fee: Pubkey,
+    pub protocol_token_b_fee: Pubkey,
+    pub fee_last_updated_at: u64,
+    pub padding0: [u8; 24],
+    pub fees: PoolFees,
+    pub pool_type: PoolType,
+    pub stake: Pubkey,
+    pub total_locked_lp: u64,
+    pub bootstrapping: Bootstrapping,
+    pub partner_info: PartnerInfo,
+    pub padding: [u8; 342],
+    pub curve_type: CurveType,
+}
+
+/// Отслеживание заблокированной прибыли хранилища
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct LockedProfitTracker {
+    pub last_updated_locked_profit: u64,
+    pub last_report: u64,
+    pub locked_profit_degradation: u64,
+}
+
+/// Состояние динамического хранилища Meteora
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct DynamicVault {
+    pub enabled: u8,
+    pub vault_bump: u8,
+    pub token_vault_bump: u8,
+    pub total_amount: u64,
+    pub token_vault: Pubkey,
+    pub fee_vault: Pubkey,
+    pub token_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub strategies: [Pubkey; 30],
+    pub base: Pubkey,
+    pub admin: Pubkey,
+    pub operator: Pubkey,
+    pub locked_profit_tracker: LockedProfitTracker,
+}
+
+impl DynamicVault {
+    /// Прибыль хранилища, еще не разблокированная к моменту `now`
+    fn locked_profit(&self, now: u64) -> Option<u64> {
+        let tracker = &self.locked_profit_tracker;
+        let duration = now.checked_sub(tracker.last_report)? as u128;
+        let locked_fund_ratio = duration.checked_mul(tracker.locked_profit_degradation as u128)?;
+        if locked_fund_ratio > LOCKED_PROFIT_DEGRADATION_DENOMINATOR {
+            return Some(0);
+        }
+
+        let locked_profit = tracker.last_updated_locked_profit as u128
+            * (LOCKED_PROFIT_DEGRADATION_DENOMINATOR - locked_fund_ratio)
+            / LOCKED_PROFIT_DEGRADATION_DENOMINATOR;
+        u64::try_from(locked_profit).ok()
+    }
+
+    /// Разблокированная сумма хранилища, по которой оцениваются доли
+    fn unlocked_amount(&self, now: u64) -> Option<u64> {
+        self.total_amount.checked_sub(self.locked_profit(now)?)
+    }
+
+    /// Стоимость `share` долей при эмиссии `total_supply`
+    fn amount_by_share(unlocked: u64, share: u64, total_supply: u64) -> Option<u64> {
+        if total_supply == 0 {
+            return Some(0);
+        }
+
+        u64::try_from(share as u128 * unlocked as u128 / total_supply as u128).ok()
+    }
+
+    /// Количество долей, соответствующее сумме `amount`
+    fn unmint_amount(unlocked: u64, amount: u64, total_supply: u64) -> Option<u64> {
+        if unlocked == 0 {
+            return None;
+        }
+
+        u64::try_from(amount as u128 * total_supply as u128 / unlocked as u128).ok()
+    }
+}
+
+/// Доли пула в хранилище и их текущая оценка
+#[derive(Debug, Clone, Copy)]
+struct VaultPosition {
+    /// Разблокированная сумма хранилища
+    unlocked: u64,
+    /// Эмиссия LP хранилища
+    lp_supply: u64,
+    /// LP хранилища, принадлежащие пулу
+    pool_lp: u64,
+}
+
+impl VaultPosition {
+    /// Резерв пула в токенах
+    fn reserve(&self) -> Option<u64> {
+        DynamicVault::amount_by_share(self.unlocked, self.pool_lp, self.lp_supply)
+    }
+}
+
+/// Инструкция обмена Meteora Dynamic AMM
+#[derive(BorshSerialize)]
+struct DammSwapInstruction {
+    discriminator: [u8; 8],
+    in_amount: u64,
+    minimum_out_amount: u64,
+}
+
+impl MeteoraDamm {
+    /// Создать новый экземпляр Meteora Dynamic AMM
+    pub fn new() -> Self {
+        Self {
+            pool: OnceCell::new(),
+        }
+    }
+
+    /// Загрузить состояние пула и обоих хранилищ
+    fn load_pool(
+        &self,
+        pool_account: &AccountInfo,
+        a_vault_account: &AccountInfo,
+        b_vault_account: &AccountInfo,
+    ) -> Result<(&DammPool, &DynamicVault, &DynamicVault), AutoBuyerError> {
+        if let Some((pool_address, pool, a_vault, b_vault)) = self.pool.get() {
+            if pool_address != pool_account.key() {
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+            return Ok((pool, a_vault, b_vault));
+        }
+
+        let pool: DammPool = load_anchor_account(
+            pool_account,
+            &constants::METEORA_DAMM_PROGRAM_ID,
+            &POOL_DISCRIMINATOR,
+        )?;
+
+        if a_vault_account.key() != &pool.a_vault || b_vault_account.key() != &pool.b_vault {
+            msg!("Error: Vaults do not match Dynamic AMM pool");
+            return Err(AutoBuyerError::InvalidPoolVault);
+        }
+        let a_vault: DynamicVault = load_anchor_account(
+            a_vault_account,
+            &constants::METEORA_VAULT_PROGRAM_ID,
+            &VAULT_DISCRIMINATOR,
+        )?;
+        let b_vault: DynamicVault = load_anchor_account(
+            b_vault_account,
+            &constants::METEORA_VAULT_PROGRAM_ID,
+            &VAULT_DISCRIMINATOR,
+        )?;
+
+        let (_, pool, a_vault, b_vault) = self
+            .pool
+            .get_or_init(|| (*pool_account.key(), pool, a_vault, b_vault));
+        Ok((pool, a_vault, b_vault))
+    }
+
+    /// Проверить, что переданные аккаунты соответствуют пулу и хранилищам
+    fn validate_pool_accounts(
+        &self,
+        pool: &DammPool,
+        a_vault: &DynamicVault,
+        b_vault: &DynamicVault,
+        accounts: &[AccountInfo],
+    ) -> Result<(), AutoBuyerError> {
+        if accounts[9].key() != &a_vault.token_vault || accounts[10].key() != &b_vault.token_vault {
+            msg!("Error: Token vaults do not match Dynamic AMM vaults");
+            return Err(AutoBuyerError::InvalidPoolVault);
+        }
+
+        if accounts[11].key() != &a_vault.lp_mint
+            || accounts[12].key() != &b_vault.lp_mint
+            || accounts[13].key() != &pool.a_vault_lp
+            || accounts[14].key() != &pool.b_vault_lp
+        {
+            msg!("Error: Vault LP accounts do not match Dynamic AMM pool");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if accounts[15].key() != &pool.protocol_token_a_fee
+            || accounts[16].key() != &pool.protocol_token_b_fee
+        {
+            msg!("Error: Protocol fee accounts do not match Dynamic AMM pool");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if accounts[17].key() != &constants::METEORA_VAULT_PROGRAM_ID
+            || accounts[18].key() != &constants::TOKEN_PROGRAM_ID
+        {
+            msg!("Error: Invalid vault or token program");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if a_vault.token_mint != pool.token_a_mint || b_vault.token_mint != pool.token_b_mint {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let fees = &pool.fees;
+        if fees.trade_fee_denominator == 0
+            || fees.trade_fee_numerator >= fees.trade_fee_denominator
+            || fees.protocol_trade_fee_numerator > fees.protocol_trade_fee_denominator
+        {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        Ok(())
+    }
+
+    /// Проверить, что пул включен, активирован и его кривая поддерживается
+    fn check_pool_status(
+        &self,
+        pool: &DammPool,
+        a_vault: &DynamicVault,
+        b_vault: &DynamicVault,
+    ) -> Result<(), AutoBuyerError> {
+        if !pool.enabled || a_vault.enabled == 0 || b_vault.enabled == 0 {
+            msg!("Error: Dynamic AMM pool or its vaults are disabled");
+            return Err(AutoBuyerError::PoolNotTradable);
+        }
+
+        // Для пулов с токенами стейкинга нужна виртуальная цена из пула стейкинга
+        if let CurveType::Stable { depeg, .. } = pool.curve_type {
+            if depeg.depeg_type != DepegType::None {
+                msg!("Error: Depeg stable pools are not supported");
+                return Err(AutoBuyerError::PoolNotTradable);
+            }
+        }
+
+        let clock = Clock::get().map_err(|_| AutoBuyerError::InvalidParameters)?;
+        let current_point = match pool.bootstrapping.activation_type {
+            ACTIVATION_TYPE_SLOT => clock.slot,
+            ACTIVATION_TYPE_TIMESTAMP => clock.unix_timestamp as u64,
+            _ => return Err(AutoBuyerError::InvalidParameters),
+        };
+
+        if current_point < pool.bootstrapping.activation_point {
+            msg!(
+                "Error: Pool activates at {}, current point {}",
+                pool.bootstrapping.activation_point,
+                current_point
+            );
+            return Err(AutoBuyerError::PoolNotOpen);
+        }
+
+        Ok(())
+    }
+
+    /// Направление обмена: true, если входной токен - токен A пула
+    fn is_a_to_b(pool: &DammPool, trading_pair: &TradingPair) -> bool {
+        trading_pair.quote_mint == pool.token_a_mint
+    }
+
+    /// Эмиссия LP минта хранилища
+    fn get_mint_supply(mint_account: &AccountInfo) -> Result<u64, AutoBuyerError> {
+        let data = mint_account
+            .try_borrow_data()
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+        let mint = Mint::unpack(
+            data.get(..Mint::LEN)
+                .ok_or(AutoBuyerError::InvalidParameters)?,
+        )
+        .map_err(|_| AutoBuyerError::InvalidParameters)?;
+        Ok(mint.supply)
+    }
+
+    /// Доли пула во входном и выходном хранилищах
+    fn vault_positions(
+        a_vault: &DynamicVault,
+        b_vault: &DynamicVault,
+        a_to_b: bool,
+        accounts: &[AccountInfo],
+    ) -> Result<(VaultPosition, VaultPosition), AutoBuyerError> {
+        let clock = Clock::get().map_err(|_| AutoBuyerError::InvalidParameters)?;
+        let now = clock.unix_timestamp as u64;
+
+        let a_position = VaultPosition {
+            unlocked: a_vault
+                .unlocked_amount(now)
+                .ok_or(AutoBuyerError::MathOverflow)?,
+            lp_supply: Self::get_mint_supply(&accounts[11])?,
+            pool_lp: get_token_balance(&accounts[13])?,
+        };
+        let b_position = VaultPosition {
+            unlocked: b_vault
+                .unlocked_amount(now)
+                .ok_or(AutoBuyerError::MathOverflow)?,
+            lp_supply: Self::get_mint_supply(&accounts[12])?,
+            pool_lp: get_token_balance(&accounts[14])?,
+        };
+
+        if a_to_b {
+            Ok((a_position, b_position))
+        } else {
+            Ok((b_position, a_position))
+        }
+    }
+
+    /// Комиссия `numerator / denominator` с округлением вниз, но не меньше 1
+    fn calculate_fee(amount: u64, numerator: u64, denominator: u64) -> Option<u64> {
+        if numerator == 0 || amount == 0 {
+            return Some(0);
+        }
+
+        let fee = (amount as u128).checked_mul(numerator as u128)? / denominator as u128;
+        u64::try_from(fee.max(1)).ok()
+    }
+
+    /// Сумма, фактически зачисляемая в резерв после депозита `amount` в хранилище
+    ///
+    /// Пул зачисляет стоимость выпущенных долей по состоянию хранилища после депозита:
+    /// `floor(minted · (U + amount) / (S + minted))`.
+    fn deposit_into_vault(position: &VaultPosition, amount: u64) -> Option<u64> {
+        let minted_lp = DynamicVault::unmint_amount(position.unlocked, amount, position.lp_supply)?;
+        DynamicVault::amount_by_share(
+            position.unlocked.checked_add(amount)?,
+            minted_lp,
+            position.lp_supply.checked_add(minted_lp)?,
+        )
+    }
+
+    /// Сумма, получаемая при выводе `amount` из хранилища (с округлением долей вниз)
+    fn withdraw_from_vault(position: &VaultPosition, amount: u64) -> Option<u64> {
+        let burned_lp = DynamicVault::unmint_amount(position.unlocked, amount, position.lp_supply)?;
+        DynamicVault::amount_by_share(position.unlocked, burned_lp, position.lp_supply)
+    }
+
+    /// Обмен по кривой пула без комиссий
+    ///
+    /// Возвращает выход и предельную цену в текущей точке кривой.
+    fn swap_curve(
+        curve: &CurveType,
+        a_to_b: bool,
+        amount_in: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+    ) -> Option<(u64, PriceQ64)> {
+        match curve {
+            CurveType::ConstantProduct => {
+                let invariant = (reserve_in as u128).checked_mul(reserve_out as u128)?;
+                let new_reserve_in = (reserve_in as u128).checked_add(amount_in as u128)?;
+                let new_reserve_out = invariant.div_ceil(new_reserve_in);
+                let amount_out =
+                    u64::try_from((reserve_out as u128).checked_sub(new_reserve_out)?).ok()?;
+                Some((amount_out, PriceQ64::from_ratio(reserve_out, reserve_in)?))
+            }
+            CurveType::Stable {
+                amp,
+                token_multiplier,
+                ..
+            } => {
+                // Расчет ведется в приведенных к общей точности единицах
+                let (multiplier_in, multiplier_out) = if a_to_b {
+                    (
+                        token_multiplier.token_a_multiplier,
+                        token_multiplier.token_b_multiplier,
+                    )
+                } else {
+                    (
+                        token_multiplier.token_b_multiplier,
+                        token_multiplier.token_a_multiplier,
+                    )
+                };
+                if multiplier_in == 0 || multiplier_out == 0 {
+                    return None;
+                }
+
+                let upscaled_in = amount_in.checked_mul(multiplier_in)?;
+                let upscaled_reserve_in = reserve_in.checked_mul(multiplier_in)?;
+                let upscaled_reserve_out = reserve_out.checked_mul(multiplier_out)?;
+
+                let d = Saber::compute_d(*amp, upscaled_reserve_in, upscaled_reserve_out)?;
+                let new_reserve_out =
+                    Saber::compute_y(*amp, upscaled_reserve_in.checked_add(upscaled_in)?, d)?;
+                let amount_out =
+                    upscaled_reserve_out.checked_sub(new_reserve_out)? / multiplier_out;
+
+                let spot_price =
+                    Saber::spot_price(*amp, upscaled_reserve_in, upscaled_reserve_out, d)?;
+                let spot_price = PriceQ64(
+                    spot_price.0.checked_mul(multiplier_in as u128)? / multiplier_out as u128,
+                );
+                Some((amount_out, spot_price))
+            }
+        }
+    }
+
+    /// Рассчитать обмен с фиксированным входом
+    ///
+    /// Возвращает количество выходного токена, суммарную комиссию и предельную цену.
+    fn calculate_damm_swap(
+        &self,
+        pool: &DammPool,
+        a_vault: &DynamicVault,
+        b_vault: &DynamicVault,
+        trading_pair: &TradingPair,
+        amount_in: u64,
+        accounts: &[AccountInfo],
+    ) -> Result<(u64, u64, PriceQ64), AutoBuyerError> {
+        let a_to_b = Self::is_a_to_b(pool, trading_pair);
+        let (in_position, out_position) =
+            Self::vault_positions(a_vault, b_vault, a_to_b, accounts)?;
+
+        let reserve_in = in_position.reserve().ok_or(AutoBuyerError::MathOverflow)?;
+        let reserve_out = out_position.reserve().ok_or(AutoBuyerError::MathOverflow)?;
+        if reserve_in == 0 || reserve_out == 0 {
+            return Err(AutoBuyerError::InsufficientLiquidity);
+        }
+
+        // Комиссия протокола выводится из торговой комиссии до депозита в хранилище
+        let fees = &pool.fees;
+        let trade_fee = Self::calculate_fee(
+            amount_in,
+            fees.trade_fee_numerator,
+            fees.trade_fee_denominator,
+        )
+        .ok_or(AutoBuyerError::MathOverflow)?;
+        let protocol_fee = Self::calculate_fee(
+            trade_fee,
+            fees.protocol_trade_fee_numerator,
+            fees.protocol_trade_fee_denominator,
+        )
+        .ok_or(AutoBuyerError::MathOverflow)?;
+        let lp_trade_fee = trade_fee - protocol_fee;
+
+        let deposited = Self::deposit_into_vault(&in_position, amount_in - protocol_fee)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+        let amount_in_after_fee = deposited
+            .checked_sub(lp_trade_fee)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+
+        let (amount_swapped, spot_price) = Self::swap_curve(
+            &pool.curve_type,
+            a_to_b,
+            amount_in_after_fee,
+            reserve_in,
+            reserve_out,
+        )
+        .ok_or(AutoBuyerError::MathOverflow)?;
+
+        let amount_out = Self::withdraw_from_vault(&out_position, amount_swapped)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+
+        if amount_out == 0 {
+            return Err(AutoBuyerError::InsufficientLiquidity);
+        }
+
+        Ok((amount_out, trade_fee, spot_price))
+    }
+
+    /// Выполнить обмен через CPI
+    fn execute_damm_swap(
+        &self,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        let (pool, _, _) = self.load_pool(&accounts[6], &accounts[7], &accounts[8])?;
+        let a_to_b = Self::is_a_to_b(pool, &swap_params.trading_pair);
+
+        let user_account = &accounts[0];
+        let (source_token_account, destination_token_account) =
+            swap_params.direction.user_token_accounts(accounts);
+        let damm_program = &accounts[5];
+        let pool_account = &accounts[6];
+        let a_vault = &accounts[7];
+        let b_vault = &accounts[8];
+        let a_token_vault = &accounts[9];
+        let b_token_vault = &accounts[10];
+        let a_vault_lp_mint = &accounts[11];
+        let b_vault_lp_mint = &accounts[12];
+        let a_vault_lp = &accounts[13];
+        let b_vault_lp = &accounts[14];
+        let vault_program = &accounts[17];
+        let token_program = &accounts[18];
+        // Комиссия протокола удерживается во входном токене
+        let protocol_token_fee = if a_to_b { &accounts[15] } else { &accounts[16] };
+
+        let instruction_data = borsh::to_vec(&DammSwapInstruction {
+            discriminator: SWAP_DISCRIMINATOR,
+            in_amount: swap_params.amount_in,
+            minimum_out_amount: swap_params.min_amount_out,
+        })
+        .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        let instruction = Instruction {
+            program_id: damm_program.key(),
+            accounts: &[
+                AccountMeta::writable(pool_account.key()),
+                AccountMeta::writable(source_token_account.key()),
+                AccountMeta::writable(destination_token_account.key()),
+                AccountMeta::writable(a_vault.key()),
+                AccountMeta::writable(b_vault.key()),
+                AccountMeta::writable(a_token_vault.key()),
+                AccountMeta::writable(b_token_vault.key()),
+                AccountMeta::writable(a_vault_lp_mint.key()),
+                AccountMeta::writable(b_vault_lp_mint.key()),
+                AccountMeta::writable(a_vault_lp.key()),
+                AccountMeta::writable(b_vault_lp.key()),
+                AccountMeta::writable(protocol_token_fee.key()),
+                AccountMeta::readonly_signer(user_account.key()),
+                AccountMeta::readonly(vault_program.key()),
+                AccountMeta::readonly(token_program.key()),
+            ],
+            data: &instruction_data,
+        };
+
+        let account_infos = [
+            pool_account,
+            source_token_account,
+            destination_token_account,
+            a_vault,
+            b_vault,
+            a_token_vault,
+            b_token_vault,
+            a_vault_lp_mint,
+            b_vault_lp_mint,
+            a_vault_lp,
+            b_vault_lp,
+            protocol_token_fee,
+            user_account,
+            vault_program,
+            token_program,
+        ];
+
+        let balance_before = get_token_balance(destination_token_account)?;
+
+        invoke(&instruction, &account_infos).map_err(|_| AutoBuyerError::CpiError)?;
+
+        verify_received_amount(
+            destination_token_account,
+            balance_before,
+            swap_params.min_amount_out,
+        )
+    }
+}
+
+impl DexInterface for MeteoraDamm {
+    fn accounts_len(&self) -> usize {
+        DAMM_ACCOUNTS_LEN
+    }
+
+    fn find_trading_pair(
+        &self,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> Result<Option<TradingPair>, AutoBuyerError> {
+        if accounts.len() < 7 || accounts[5].key() != &constants::METEORA_DAMM_PROGRAM_ID {
+            return Ok(None);
+        }
+
+        if accounts.len() < DAMM_ACCOUNTS_LEN {
+            msg!(
+                "Error: Insufficient accounts for Meteora Dynamic AMM. Expected {}, got {}",
+                DAMM_ACCOUNTS_LEN,
+                accounts.len()
+            );
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let pool_account = &accounts[6];
+        let (pool, a_vault, b_vault) =
+            match self.load_pool(pool_account, &accounts[7], &accounts[8]) {
+                Ok(pool) => pool,
+                Err(_) => return Ok(None),
+            };
+
+        let is_correct_pair = (pool.token_a_mint == *base_mint && pool.token_b_mint == *quote_mint)
+            || (pool.token_a_mint == *quote_mint && pool.token_b_mint == *base_mint);
+
+        if !is_correct_pair {
+            return Ok(None);
+        }
+
+        self.validate_pool_accounts(pool, a_vault, b_vault, accounts)?;
+        self.check_pool_status(pool, a_vault, b_vault)?;
+
+        let pool_config = PoolConfig {
+            pool_address: *pool_account.key(),
+            token_a_account: pool.a_vault_lp,
+            token_b_account: pool.b_vault_lp,
+            token_a_mint: pool.token_a_mint,
+            token_b_mint: pool.token_b_mint,
+            fee_rate: (pool.fees.trade_fee_numerator as u128 * constants::BASIS_POINTS as u128
+                / pool.fees.trade_fee_denominator as u128) as u16,
+        };
+
+        Ok(Some(TradingPair {
+            base_mint: *base_mint,
+            quote_mint: *quote_mint,
+            pool_config,
+        }))
+    }
+
+    fn calculate_swap(
+        &self,
+        trading_pair: &TradingPair,
+        amount_in: u64,
+        accounts: &[AccountInfo],
+    ) -> Result<SwapCalculation, AutoBuyerError> {
+        let (pool, a_vault, b_vault) = self.load_pool(&accounts[6], &accounts[7], &accounts[8])?;
+
+        let (amount_out, fee_amount, spot_price) =
+            self.calculate_damm_swap(pool, a_vault, b_vault, trading_pair, amount_in, accounts)?;
+
+        let price_per_unit = PriceQ64::from_ratio(amount_out, amount_in).unwrap_or_default();
+
+        Ok(SwapCalculation {
+            amount_in,
+            amount_out,
+            fee_amount,
+            price_per_unit,
+            price_impact_bps: price_per_unit.impact_bps(spot_price),
+        })
+    }
+
+    fn execute_swap(
+        &self,
+        _program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        if swap_params.mode != SwapMode::ExactIn {
+            return Err(AutoBuyerError::SwapModeNotSupported);
+        }
+
+        msg!(
+            "Executing Meteora Dynamic AMM swap: {} -> {}",
+            swap_params.amount_in,
+            swap_params.calculation.amount_out
+        );
+
+        let amount_received = self.execute_damm_swap(accounts, swap_params)?;
+
+        msg!(
+            "Meteora Dynamic AMM swap completed successfully, received {}",
+            amount_received
+        );
+        Ok(amount_received)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::zeroed_state;
+
+    /// Скорость разблокировки прибыли за 6 часов (значение по умолчанию хранилищ Meteora)
+    const SIX_HOURS_DEGRADATION: u64 = 46_296_296;
+
+    /// Хранилище с прибылью 1 000 000, зафиксированной в момент 1000
+    fn vault(total_amount: u64) -> DynamicVault {
+        let mut vault: DynamicVault = zeroed_state();
+        vault.total_amount = total_amount;
+        vault.locked_profit_tracker = LockedProfitTracker {
+            last_updated_locked_profit: 1_000_000,
+            last_report: 1_000,
+            locked_profit_degradation: SIX_HOURS_DEGRADATION,
+        };
+        vault
+    }
+
+    /// Позиция пула в хранилище с некратным соотношением суммы и эмиссии
+    const POSITION: VaultPosition = VaultPosition {
+        unlocked: 1_234_567_891,
+        lp_supply: 1_000_000_007,
+        pool_lp: 600_000_000,
+    };
+
+    /// Стабильная кривая для пары 6/9 знаков: токен A приводится множителем 1000
+    fn stable_curve() -> CurveType {
+        CurveType::Stable {
+            amp: 100,
+            token_multiplier: TokenMultiplier {
+                token_a_multiplier: 1_000,
+                token_b_multiplier: 1,
+                precision_factor: 9,
+            },
+            depeg: Depeg {
+                base_virtual_price: 0,
+                base_cache_updated: 0,
+                depeg_type: DepegType::None,
+            },
+            last_amp_updated_timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn locked_profit_degrades_linearly() {
+        let vault = vault(5_000_000);
+        assert_eq!(vault.locked_profit(1_000), Some(1_000_000));
+        assert_eq!(vault.locked_profit(4_600), Some(833_333));
+        assert_eq!(vault.unlocked_amount(4_600), Some(4_166_667));
+        assert_eq!(vault.locked_profit(1_000 + 21_601), Some(0));
+        assert_eq!(vault.unlocked_amount(1_000 + 21_601), Some(5_000_000));
+    }
+
+    #[test]
+    fn locked_profit_rejects_time_before_last_report() {
+        assert_eq!(vault(5_000_000).locked_profit(999), None);
+    }
+
+    #[test]
+    fn shares_round_down() {
+        assert_eq!(POSITION.reserve(), Some(740_740_729));
+        assert_eq!(
+            DynamicVault::unmint_amount(POSITION.unlocked, 10_000_000, POSITION.lp_supply),
+            Some(8_100_000)
+        );
+        assert_eq!(DynamicVault::amount_by_share(1_000, 5, 0), Some(0));
+        assert_eq!(DynamicVault::unmint_amount(0, 5, 1_000), None);
+    }
+
+    #[test]
+    fn deposit_credits_minted_share_value() {
+        // Разница резервов до и после депозита дала бы 10 000 000, 3 и 999 999 937
+        for (amount, expected) in [(10_000_000, 9_999_999), (3, 2), (999_999_937, 999_999_936)] {
+            assert_eq!(
+                MeteoraDamm::deposit_into_vault(&POSITION, amount),
+                Some(expected),
+                "deposit {amount}"
+            );
+        }
+    }
+
+    #[test]
+    fn withdraw_loses_share_rounding() {
+        assert_eq!(
+            MeteoraDamm::withdraw_from_vault(&POSITION, 10_000_000),
+            Some(9_999_999)
+        );
+    }
+
+    #[test]
+    fn fee_is_floored_but_at_least_one() {
+        assert_eq!(
+            MeteoraDamm::calculate_fee(1_000_000, 25, 10_000),
+            Some(2_500)
+        );
+        assert_eq!(MeteoraDamm::calculate_fee(1_000, 25, 10_000), Some(2));
+        assert_eq!(MeteoraDamm::calculate_fee(1, 20, 100), Some(1));
+        assert_eq!(MeteoraDamm::calculate_fee(0, 25, 10_000), Some(0));
+        assert_eq!(MeteoraDamm::calculate_fee(5, 0, 10_000), Some(0));
+    }
+
+    #[test]
+    fn constant_product_rounds_output_down() {
+        let (amount_out, spot_price) = MeteoraDamm::swap_curve(
+            &CurveType::ConstantProduct,
+            true,
+            1_000_000,
+            50_000_000_000,
+            20_000_000_000,
+        )
+        .unwrap();
+        assert_eq!(amount_out, 399_992);
+        assert_eq!(spot_price, PriceQ64(7_378_697_629_483_820_646));
+
+        let (dust_out, _) = MeteoraDamm::swap_curve(
+            &CurveType::ConstantProduct,
+            true,
+            1,
+            50_000_000_000,
+            20_000_000_000,
+        )
+        .unwrap();
+        assert_eq!(dust_out, 0);
+    }
+
+    #[test]
+    fn stable_curve_scales_by_token_multipliers() {
+        let curve = stable_curve();
+        let (a_to_b_out, _) =
+            MeteoraDamm::swap_curve(&curve, true, 1_000_000, 5_000_000_000, 4_990_000_000_000)
+                .unwrap();
+        assert_eq!(a_to_b_out, 999_978_197);
+
+        let (b_to_a_out, _) = MeteoraDamm::swap_curve(
+            &curve,
+            false,
+            1_000_000_000,
+            4_990_000_000_000,
+            5_000_000_000,
+        )
+        .unwrap();
+        assert_eq!(b_to_a_out, 1_000_017);
+    }
+
+    #[test]
+    fn stable_curve_rejects_zero_multiplier() {
+        let mut curve = stable_curve();
+        if let CurveType::Stable {
+            token_multiplier, ..
+        } = &mut curve
+        {
+            token_multiplier.token_b_multiplier = 0;
+        }
+        assert!(MeteoraDamm::swap_curve(&curve, true, 1_000, 1_000_000, 1_000_000).is_none());
+    }
+}
@@ -0,0 +1,902 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::cell::OnceCell;
+
+use crate::{
+    dex::{
+        get_token_balance,
+        math::U256,
+        token_2022::{get_transfer_fee, is_token_program},
+        types::{SwapMode, SwapParams},
+        verify_received_amount, DexInterface,
+    },
+    error::AutoBuyerError,
+    state::{constants, PoolConfig, PriceQ64, SwapCalculation, TradingPair},
+};
+
+/// Дискриминатор аккаунта LbPair
+const LB_PAIR_DISCRIMINATOR: [u8; 8] = [33, 11, 49, 98, 181, 101, 177, 13];
+
+/// Дискриминатор аккаунта BinArray
+const BIN_ARRAY_DISCRIMINATOR: [u8; 8] = [92, 142, 92, 220, 5, 148, 70, 181];
+
+/// Дискриминатор инструкции swap
+const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+
+/// Количество бинов в одном BinArray
+const MAX_BIN_PER_ARRAY: i32 = 70;
+
+/// Размер сериализованного бина
+const BIN_SIZE: usize = 144;
+
+/// Смещение индекса массива в данных BinArray
+const BIN_ARRAY_INDEX_OFFSET: usize = 8;
+
+/// Смещение адреса пары в данных BinArray
+const BIN_ARRAY_LB_PAIR_OFFSET: usize = 24;
+
+/// Смещение первого бина в данных BinArray
+const BINS_OFFSET: usize = 56;
+
+/// Точность ставок комиссий DLMM (1e9 = 100%)
+const FEE_PRECISION: u128 = 1_000_000_000;
+
+/// Максимальная суммарная ставка комиссии (10%)
+const MAX_FEE_RATE: u128 = 100_000_000;
+
+/// Базисные пункты в расчетах волатильности и шага бина
+const BASIS_POINT_MAX: u128 = 10_000;
+
+/// Единица в формате Q64.64
+const ONE_X64: u128 = 1 << 64;
+
+/// Число битов показателя степени цены бина (больший показатель переполняет Q64.64)
+const MAX_EXPONENT_BITS: u32 = 19;
+
+/// Статус пары, разрешающий обмены
+const PAIR_STATUS_ENABLED: u8 = 0;
+
+/// Тип активации по номеру слота
+const ACTIVATION_TYPE_SLOT: u8 = 0;
+
+/// Тип активации по времени
+const ACTIVATION_TYPE_TIMESTAMP: u8 = 1;
+
+/// Количество аккаунтов, необходимых для обмена через DLMM
+const DLMM_ACCOUNTS_LEN: usize = 17;
+
+/// Структура для работы с Meteora DLMM
+///
+/// Аккаунты (после общих 0-4):
+/// 5. `[]` Программа Meteora DLMM
+/// 6. `[writable]` LbPair
+/// 7. `[]` BinArrayBitmapExtension (или программа DLMM, если расширения нет)
+/// 8. `[writable]` Резерв токена X
+/// 9. `[writable]` Резерв токена Y
+/// 10. `[writable]` Oracle
+/// 11. `[]` Программа токена X
+/// 12. `[]` Программа токена Y
+/// 13. `[]` Event authority
+/// 14. `[writable]` BinArray 0 (содержит активный бин)
+/// 15. `[writable]` BinArray 1
+/// 16. `[writable]` BinArray 2
+pub struct MeteoraDlmm {
+    /// Состояние пары, загруженное в рамках текущей инструкции
+    pair: OnceCell<(Pubkey, LbPair)>,
+}
+
+/// Статические параметры комиссий пары
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug, Clone, Copy)]
+pub struct StaticParameters {
+    pub base_factor: u16,
+    pub filter_period: u16,
+    pub decay_period: u16,
+    pub reduction_factor: u16,
+    pub variable_fee_control: u32,
+    pub max_volatility_accumulator: u32,
+    pub min_bin_id: i32,
+    pub max_bin_id: i32,
+    pub protocol_share: u16,
+    pub base_fee_power_factor: u8,
+    pub padding: [u8; 5],
+}
+
+/// Переменные параметры комиссий пары (аккумулятор волатильности)
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug, Clone, Copy)]
+pub struct VariableParameters {
+    pub volatility_accumulator: u32,
+    pub volatility_reference: u32,
+    pub index_reference: i32,
+    pub padding: [u8; 4],
+    pub last_update_timestamp: i64,
+    pub padding1: [u8; 8],
+}
+
+/// Состояние пары DLMM (до адреса создателя)
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct LbPair {
+    pub parameters: StaticParameters,
+    pub v_parameters: VariableParameters,
+    pub bump_seed: [u8; 1],
+    pub bin_step_seed: [u8; 2],
+    pub pair_type: u8,
+    pub active_id: i32,
+    pub bin_step: u16,
+    pub status: u8,
+    pub require_base_factor_seed: u8,
+    pub base_factor_seed: [u8; 2],
+    pub activation_type: u8,
+    pub creator_pool_on_off_control: u8,
+    pub token_x_mint: Pubkey,
+    pub token_y_mint: Pubkey,
+    pub reserve_x: Pubkey,
+    pub reserve_y: Pubkey,
+    pub protocol_fee_amount_x: u64,
+    pub protocol_fee_amount_y: u64,
+    pub padding1: [u8; 32],
+    pub reward_infos: [u8; 288],
+    pub oracle: Pubkey,
+    pub bin_array_bitmap: [u64; 16],
+    pub last_updated_at: i64,
+    pub padding2: [u8; 32],
+    pub pre_activation_swap_address: Pubkey,
+    pub base_key: Pubkey,
+    pub activation_point: u64,
+    pub pre_activation_duration: u64,
+    pub padding3: [u8; 8],
+    pub padding4: u64,
+    pub creator: Pubkey,
+    pub token_mint_x_program_flag: u8,
+    pub token_mint_y_program_flag: u8,
+}
+
+/// Ликвидность одного бина
+#[derive(Debug, Clone, Copy)]
+struct Bin {
+    /// Количество токена X в бине
+    amount_x: u64,
+    /// Количество токена Y в бине
+    amount_y: u64,
+    /// Сохраненная цена бина (0, если еще не записана)
+    price: u128,
+}
+
+/// Бины одного BinArray
+#[derive(Debug, Clone)]
+struct BinArray {
+    /// Индекс массива
+    index: i64,
+    /// Бины массива по порядку идентификаторов
+    bins: Vec<Bin>,
+}
+
+/// Инструкция обмена DLMM
+#[derive(BorshSerialize)]
+struct DlmmSwapInstruction {
+    discriminator: [u8; 8],
+    amount_in: u64,
+    min_amount_out: u64,
+}
+
+impl MeteoraDlmm {
+    /// Создать новый экземпляр Meteora DLMM
+    pub fn new() -> Self {
+        Self {
+            pair: OnceCell::new(),
+        }
+    }
+
+    /// Загрузить состояние пары DLMM
+    fn load_lb_pair(&self, pair_account: &AccountInfo) -> Result<&LbPair, AutoBuyerError> {
+        if let Some((pair_address, pair)) = self.pair.get() {
+            if pair_address != pair_account.key() {
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+            return Ok(pair);
+        }
+
+        if pair_account.owner() != &constants::METEORA_DLMM_PROGRAM_ID {
+            return Err(AutoBuyerError::InvalidAccountOwner);
+        }
+
+        let data = pair_account
+            .try_borrow_data()
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        if data.len() < 8 || data[..8] != LB_PAIR_DISCRIMINATOR {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let pair =
+            LbPair::deserialize(&mut &data[8..]).map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        Ok(&self.pair.get_or_init(|| (*pair_account.key(), pair)).1)
+    }
+
+    /// Программа токенов, соответствующая флагу пары
+    fn token_program_for_flag(flag: u8) -> Option<&'static Pubkey> {
+        match flag {
+            0 => Some(&constants::TOKEN_PROGRAM_ID),
+            1 => Some(&constants::TOKEN_2022_PROGRAM_ID),
+            _ => None,
+        }
+    }
+
+    /// Проверить, что переданные аккаунты соответствуют паре
+    fn validate_pool_accounts(
+        &self,
+        pair: &LbPair,
+        accounts: &[AccountInfo],
+    ) -> Result<(), AutoBuyerError> {
+        let bitmap_extension = &accounts[7];
+        if bitmap_extension.key() != &constants::METEORA_DLMM_PROGRAM_ID
+            && bitmap_extension.owner() != &constants::METEORA_DLMM_PROGRAM_ID
+        {
+            msg!("Error: Invalid bin array bitmap extension");
+            return Err(AutoBuyerError::InvalidAccountOwner);
+        }
+
+        if accounts[8].key() != &pair.reserve_x || accounts[9].key() != &pair.reserve_y {
+            msg!("Error: Pool reserves do not match DLMM pair");
+            return Err(AutoBuyerError::InvalidPoolVault);
+        }
+
+        if accounts[10].key() != &pair.oracle {
+            msg!("Error: Oracle does not match DLMM pair");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let token_x_program = Self::token_program_for_flag(pair.token_mint_x_program_flag);
+        let token_y_program = Self::token_program_for_flag(pair.token_mint_y_program_flag);
+        if token_x_program != Some(accounts[11].key())
+            || token_y_program != Some(accounts[12].key())
+            || !is_token_program(accounts[11].key())
+            || !is_token_program(accounts[12].key())
+        {
+            msg!("Error: Invalid token program for DLMM pair");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if pair.bin_step == 0 || pair.parameters.min_bin_id > pair.parameters.max_bin_id {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        Ok(())
+    }
+
+    /// Проверить, что пара включена и активирована
+    fn check_pool_status(&self, pair: &LbPair) -> Result<(), AutoBuyerError> {
+        if pair.status != PAIR_STATUS_ENABLED {
+            msg!("Error: Pair status {} does not allow swaps", pair.status);
+            return Err(AutoBuyerError::PoolNotTradable);
+        }
+
+        let clock = Clock::get().map_err(|_| AutoBuyerError::InvalidParameters)?;
+        let current_point = match pair.activation_type {
+            ACTIVATION_TYPE_SLOT => clock.slot,
+            ACTIVATION_TYPE_TIMESTAMP => clock.unix_timestamp as u64,
+            _ => return Err(AutoBuyerError::InvalidParameters),
+        };
+
+        if current_point < pair.activation_point {
+            msg!(
+                "Error: Pair activates at {}, current point {}",
+                pair.activation_point,
+                current_point
+            );
+            return Err(AutoBuyerError::PoolNotOpen);
+        }
+
+        Ok(())
+    }
+
+    /// Направление обмена: true, если входной токен - токен X пары
+    fn is_swap_for_y(pair: &LbPair, trading_pair: &TradingPair) -> bool {
+        trading_pair.quote_mint == pair.token_x_mint
+    }
+
+    /// Минты токенов X и Y из общих аккаунтов 3-4
+    fn mint_accounts<'a>(
+        pair: &LbPair,
+        accounts: &'a [AccountInfo],
+    ) -> (&'a AccountInfo, &'a AccountInfo) {
+        if accounts[3].key() == &pair.token_x_mint {
+            (&accounts[3], &accounts[4])
+        } else {
+            (&accounts[4], &accounts[3])
+        }
+    }
+
+    /// Разобрать BinArray пары
+    fn parse_bin_array(
+        bin_array: &AccountInfo,
+        pair_address: &Pubkey,
+    ) -> Result<BinArray, AutoBuyerError> {
+        if bin_array.owner() != &constants::METEORA_DLMM_PROGRAM_ID {
+            return Err(AutoBuyerError::InvalidAccountOwner);
+        }
+
+        let data = bin_array
+            .try_borrow_data()
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        if data.len() < BINS_OFFSET + MAX_BIN_PER_ARRAY as usize * BIN_SIZE
+            || data[..8] != BIN_ARRAY_DISCRIMINATOR
+        {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if &data[BIN_ARRAY_LB_PAIR_OFFSET..BIN_ARRAY_LB_PAIR_OFFSET + 32] != pair_address {
+            msg!("Error: Bin array belongs to another pair");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let index = i64::from_le_bytes(
+            data[BIN_ARRAY_INDEX_OFFSET..BIN_ARRAY_INDEX_OFFSET + 8]
+                .try_into()
+                .unwrap(),
+        );
+
+        let bins = (0..MAX_BIN_PER_ARRAY as usize)
+            .map(|i| {
+                let offset = BINS_OFFSET + i * BIN_SIZE;
+                Bin {
+                    amount_x: u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap()),
+                    amount_y: u64::from_le_bytes(data[offset + 8..offset + 16].try_into().unwrap()),
+                    price: u128::from_le_bytes(data[offset + 16..offset + 32].try_into().unwrap()),
+                }
+            })
+            .collect();
+
+        Ok(BinArray { index, bins })
+    }
+
+    /// Цена бина (токен Y за единицу токена X, Q64.64): `(1 + bin_step / 10000) ^ bin_id`
+    fn price_from_id(bin_id: i32, bin_step: u16) -> Result<u128, AutoBuyerError> {
+        let base = ONE_X64 + ((bin_step as u128) << 64) / BASIS_POINT_MAX;
+        Self::pow(base, bin_id).ok_or(AutoBuyerError::MathOverflow)
+    }
+
+    /// Степень числа Q64.64 по алгоритму `u64x64_math::pow` DLMM
+    ///
+    /// Основание больше единицы заменяется обратным `u128::MAX / base`, чтобы квадраты
+    /// оставались в u128, а результат обращается в конце. Округление в каждом умножении
+    /// совпадает с программой, поэтому цены бинов совпадают бит в бит.
+    fn pow(base: u128, exponent: i32) -> Option<u128> {
+        if exponent == 0 {
+            return Some(ONE_X64);
+        }
+
+        let mut invert = exponent < 0;
+        let exponent = exponent.unsigned_abs();
+        if exponent >= 1 << MAX_EXPONENT_BITS {
+            return None;
+        }
+
+        let mut squared_base = base;
+        let mut result = ONE_X64;
+        if squared_base >= result {
+            squared_base = u128::MAX.checked_div(squared_base)?;
+            invert = !invert;
+        }
+
+        for bit in 0..MAX_EXPONENT_BITS {
+            if exponent & (1 << bit) > 0 {
+                result = result.checked_mul(squared_base)? >> 64;
+            }
+            squared_base = squared_base.checked_mul(squared_base)? >> 64;
+        }
+
+        if result == 0 {
+            return None;
+        }
+
+        if invert {
+            result = u128::MAX.checked_div(result)?;
+        }
+        Some(result)
+    }
+
+    /// Суммарная ставка комиссии (базовая + переменная) при заданном аккумуляторе волатильности
+    fn total_fee_rate(pair: &LbPair, volatility_accumulator: u32) -> u128 {
+        let parameters = &pair.parameters;
+        let base_fee_rate = parameters.base_factor as u128
+            * pair.bin_step as u128
+            * 10
+            * 10u128.pow(parameters.base_fee_power_factor as u32);
+
+        let variable_fee_rate = if parameters.variable_fee_control > 0 {
+            let volatility_bin = volatility_accumulator as u128 * pair.bin_step as u128;
+            (parameters.variable_fee_control as u128 * volatility_bin * volatility_bin)
+                .div_ceil(100_000_000_000)
+        } else {
+            0
+        };
+
+        (base_fee_rate + variable_fee_rate).min(MAX_FEE_RATE)
+    }
+
+    /// Количество выходного токена для входа без комиссии по цене бина
+    fn amount_out_at_price(amount_in: u64, price: u128, swap_for_y: bool) -> Option<u64> {
+        let amount_out = if swap_for_y {
+            (U256::from(amount_in) * U256::from(price)) >> 64
+        } else {
+            (U256::from(amount_in) << 64) / U256::from(price)
+        };
+        (amount_out <= U256::from(u64::MAX)).then(|| amount_out.as_u64())
+    }
+
+    /// Вход без комиссии, необходимый для выкупа `amount_out` по цене бина (округление вверх)
+    fn max_amount_in_at_price(amount_out: u64, price: u128, swap_for_y: bool) -> Option<u64> {
+        let (numerator, denominator) = if swap_for_y {
+            (U256::from(amount_out) << 64, U256::from(price))
+        } else {
+            (
+                U256::from(amount_out) * U256::from(price),
+                U256::one() << 64,
+            )
+        };
+        let (quotient, remainder) = numerator.div_mod(denominator);
+        let amount_in = if remainder.is_zero() {
+            quotient
+        } else {
+            quotient + 1
+        };
+        (amount_in <= U256::from(u64::MAX)).then(|| amount_in.as_u64())
+    }
+
+    /// Смоделировать обмен с фиксированным входом, проходя по бинам
+    ///
+    /// Аккумулятор волатильности обновляется перед каждым бином так же, как в
+    /// программе DLMM. Возвращает полученное количество и суммарную комиссию.
+    fn simulate_swap(
+        &self,
+        pair: &LbPair,
+        bin_arrays: &[BinArray],
+        amount_in: u64,
+        swap_for_y: bool,
+        current_timestamp: i64,
+    ) -> Result<(u64, u64), AutoBuyerError> {
+        let parameters = &pair.parameters;
+        let mut volatility_reference = pair.v_parameters.volatility_reference;
+        let mut index_reference = pair.v_parameters.index_reference;
+
+        // Обновление опорных значений по прошедшему времени
+        let elapsed = current_timestamp.saturating_sub(pair.v_parameters.last_update_timestamp);
+        if elapsed >= parameters.filter_period as i64 {
+            index_reference = pair.active_id;
+            volatility_reference = if elapsed < parameters.decay_period as i64 {
+                (pair.v_parameters.volatility_accumulator as u128
+                    * parameters.reduction_factor as u128
+                    / BASIS_POINT_MAX) as u32
+            } else {
+                0
+            };
+        }
+
+        let mut amount_left = amount_in;
+        let mut amount_out: u64 = 0;
+        let mut fee_total: u64 = 0;
+        let mut active_id = pair.active_id;
+
+        while amount_left > 0 {
+            if !(parameters.min_bin_id..=parameters.max_bin_id).contains(&active_id) {
+                msg!("Error: Swap exceeds pair bin range");
+                return Err(AutoBuyerError::InsufficientLiquidity);
+            }
+
+            let array_index = active_id.div_euclid(MAX_BIN_PER_ARRAY) as i64;
+            let Some(bin_array) = bin_arrays.iter().find(|array| array.index == array_index) else {
+                msg!("Error: Swap exceeds liquidity in supplied bin arrays");
+                return Err(AutoBuyerError::InsufficientLiquidity);
+            };
+            let bin = bin_array.bins[active_id.rem_euclid(MAX_BIN_PER_ARRAY) as usize];
+
+            let delta_id = index_reference.abs_diff(active_id) as u128;
+            let volatility_accumulator = (volatility_reference as u128 + delta_id * BASIS_POINT_MAX)
+                .min(parameters.max_volatility_accumulator as u128)
+                as u32;
+            let fee_rate = Self::total_fee_rate(pair, volatility_accumulator);
+
+            let bin_amount_out = if swap_for_y {
+                bin.amount_y
+            } else {
+                bin.amount_x
+            };
+
+            if bin_amount_out > 0 {
+                let price = if bin.price != 0 {
+                    bin.price
+                } else {
+                    Self::price_from_id(active_id, pair.bin_step)?
+                };
+
+                let max_amount_in = Self::max_amount_in_at_price(bin_amount_out, price, swap_for_y)
+                    .ok_or(AutoBuyerError::MathOverflow)?;
+                let max_fee = (max_amount_in as u128 * fee_rate).div_ceil(FEE_PRECISION - fee_rate);
+                let max_amount_in_with_fee = max_amount_in as u128 + max_fee;
+
+                let (amount_in_with_fee, bin_out, fee) =
+                    if amount_left as u128 >= max_amount_in_with_fee {
+                        (
+                            max_amount_in_with_fee as u64,
+                            bin_amount_out,
+                            max_fee as u64,
+                        )
+                    } else {
+                        let fee = (amount_left as u128 * fee_rate).div_ceil(FEE_PRECISION) as u64;
+                        let out = Self::amount_out_at_price(amount_left - fee, price, swap_for_y)
+                            .ok_or(AutoBuyerError::MathOverflow)?
+                            .min(bin_amount_out);
+                        (amount_left, out, fee)
+                    };
+
+                amount_left -= amount_in_with_fee;
+                amount_out = amount_out
+                    .checked_add(bin_out)
+                    .ok_or(AutoBuyerError::MathOverflow)?;
+                fee_total = fee_total
+                    .checked_add(fee)
+                    .ok_or(AutoBuyerError::MathOverflow)?;
+            }
+
+            if amount_left > 0 {
+                active_id = if swap_for_y {
+                    active_id - 1
+                } else {
+                    active_id + 1
+                };
+            }
+        }
+
+        Ok((amount_out, fee_total))
+    }
+
+    /// Выполнить обмен через CPI
+    fn execute_dlmm_swap(
+        &self,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        let pair = self.load_lb_pair(&accounts[6])?;
+
+        let user_account = &accounts[0];
+        let (source_token_account, destination_token_account) =
+            swap_params.direction.user_token_accounts(accounts);
+        let (token_x_mint, token_y_mint) = Self::mint_accounts(pair, accounts);
+        let dlmm_program = &accounts[5];
+        let lb_pair = &accounts[6];
+        let bitmap_extension = &accounts[7];
+        let reserve_x = &accounts[8];
+        let reserve_y = &accounts[9];
+        let oracle = &accounts[10];
+        let token_x_program = &accounts[11];
+        let token_y_program = &accounts[12];
+        let event_authority = &accounts[13];
+        let bin_array_0 = &accounts[14];
+        let bin_array_1 = &accounts[15];
+        let bin_array_2 = &accounts[16];
+
+        let instruction_data = borsh::to_vec(&DlmmSwapInstruction {
+            discriminator: SWAP_DISCRIMINATOR,
+            amount_in: swap_params.amount_in,
+            min_amount_out: swap_params.min_amount_out,
+        })
+        .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        let instruction = Instruction {
+            program_id: dlmm_program.key(),
+            accounts: &[
+                AccountMeta::writable(lb_pair.key()),
+                AccountMeta::readonly(bitmap_extension.key()),
+                AccountMeta::writable(reserve_x.key()),
+                AccountMeta::writable(reserve_y.key()),
+                AccountMeta::writable(source_token_account.key()),
+                AccountMeta::writable(destination_token_account.key()),
+                AccountMeta::readonly(token_x_mint.key()),
+                AccountMeta::readonly(token_y_mint.key()),
+                AccountMeta::writable(oracle.key()),
+                // host_fee_in не используется: передается программа DLMM
+                AccountMeta::readonly(dlmm_program.key()),
+                AccountMeta::readonly_signer(user_account.key()),
+                AccountMeta::readonly(token_x_program.key()),
+                AccountMeta::readonly(token_y_program.key()),
+                AccountMeta::readonly(event_authority.key()),
+                AccountMeta::readonly(dlmm_program.key()),
+                AccountMeta::writable(bin_array_0.key()),
+                AccountMeta::writable(bin_array_1.key()),
+                AccountMeta::writable(bin_array_2.key()),
+            ],
+            data: &instruction_data,
+        };
+
+        let account_infos = [
+            lb_pair,
+            bitmap_extension,
+            reserve_x,
+            reserve_y,
+            source_token_account,
+            destination_token_account,
+            token_x_mint,
+            token_y_mint,
+            oracle,
+            dlmm_program,
+            user_account,
+            token_x_program,
+            token_y_program,
+            event_authority,
+            dlmm_program,
+            bin_array_0,
+            bin_array_1,
+            bin_array_2,
+        ];
+
+        let balance_before = get_token_balance(destination_token_account)?;
+
+        invoke(&instruction, &account_infos).map_err(|_| AutoBuyerError::CpiError)?;
+
+        verify_received_amount(
+            destination_token_account,
+            balance_before,
+            swap_params.min_amount_out,
+        )
+    }
+}
+
+impl DexInterface for MeteoraDlmm {
+    fn accounts_len(&self) -> usize {
+        DLMM_ACCOUNTS_LEN
+    }
+
+    fn find_trading_pair(
+        &self,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> Result<Option<TradingPair>, AutoBuyerError> {
+        if accounts.len() < 7 || accounts[5].key() != &constants::METEORA_DLMM_PROGRAM_ID {
+            return Ok(None);
+        }
+
+        if accounts.len() < DLMM_ACCOUNTS_LEN {
+            msg!(
+                "Error: Insufficient accounts for Meteora DLMM. Expected {}, got {}",
+                DLMM_ACCOUNTS_LEN,
+                accounts.len()
+            );
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let pair_account = &accounts[6];
+        let pair = match self.load_lb_pair(pair_account) {
+            Ok(pair) => pair,
+            Err(_) => return Ok(None),
+        };
+
+        let is_correct_pair = (pair.token_x_mint == *base_mint && pair.token_y_mint == *quote_mint)
+            || (pair.token_x_mint == *quote_mint && pair.token_y_mint == *base_mint);
+
+        if !is_correct_pair {
+            return Ok(None);
+        }
+
+        self.validate_pool_accounts(pair, accounts)?;
+        self.check_pool_status(pair)?;
+
+        let pool_config = PoolConfig {
+            pool_address: *pair_account.key(),
+            token_a_account: pair.reserve_x,
+            token_b_account: pair.reserve_y,
+            token_a_mint: pair.token_x_mint,
+            token_b_mint: pair.token_y_mint,
+            // Базовая ставка хранится с точностью 1e9
+            fee_rate: (Self::total_fee_rate(pair, 0) / 100_000) as u16,
+        };
+
+        Ok(Some(TradingPair {
+            base_mint: *base_mint,
+            quote_mint: *quote_mint,
+            pool_config,
+        }))
+    }
+
+    fn calculate_swap(
+        &self,
+        trading_pair: &TradingPair,
+        amount_in: u64,
+        accounts: &[AccountInfo],
+    ) -> Result<SwapCalculation, AutoBuyerError> {
+        let pair_account = &accounts[6];
+        let pair = self.load_lb_pair(pair_account)?;
+        let swap_for_y = Self::is_swap_for_y(pair, trading_pair);
+
+        let bin_arrays = accounts[14..17]
+            .iter()
+            .map(|bin_array| Self::parse_bin_array(bin_array, pair_account.key()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (token_x_mint, token_y_mint) = Self::mint_accounts(pair, accounts);
+        let (input_mint, output_mint) = if swap_for_y {
+            (token_x_mint, token_y_mint)
+        } else {
+            (token_y_mint, token_x_mint)
+        };
+
+        // Пара получает сумму за вычетом комиссии перевода Token-2022
+        let transfer_fee_in = get_transfer_fee(input_mint, amount_in)?;
+        let actual_amount_in = amount_in
+            .checked_sub(transfer_fee_in)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+
+        let clock = Clock::get().map_err(|_| AutoBuyerError::InvalidParameters)?;
+        let (amount_out_swapped, fee_amount) = self.simulate_swap(
+            pair,
+            &bin_arrays,
+            actual_amount_in,
+            swap_for_y,
+            clock.unix_timestamp,
+        )?;
+
+        // Пользователь получает сумму за вычетом комиссии перевода Token-2022
+        let transfer_fee_out = get_transfer_fee(output_mint, amount_out_swapped)?;
+        let amount_out = amount_out_swapped - transfer_fee_out;
+
+        let active_price = Self::price_from_id(pair.active_id, pair.bin_step)?;
+        let spot_price = if swap_for_y {
+            PriceQ64(active_price)
+        } else {
+            let inverse = (U256::one() << 128) / U256::from(active_price);
+            PriceQ64(inverse.min(U256::from(u128::MAX)).as_u128())
+        };
+        let price_per_unit = PriceQ64::from_ratio(amount_out, amount_in).unwrap_or_default();
+
+        Ok(SwapCalculation {
+            amount_in,
+            amount_out,
+            fee_amount,
+            price_per_unit,
+            price_impact_bps: price_per_unit.impact_bps(spot_price),
+        })
+    }
+
+    fn execute_swap(
+        &self,
+        _program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        if swap_params.mode != SwapMode::ExactIn {
+            return Err(AutoBuyerError::SwapModeNotSupported);
+        }
+
+        msg!(
+            "Executing Meteora DLMM swap: {} -> {}",
+            swap_params.amount_in,
+            swap_params.calculation.amount_out
+        );
+
+        let amount_received = self.execute_dlmm_swap(accounts, swap_params)?;
+
+        msg!(
+            "Meteora DLMM swap completed successfully, received {}",
+            amount_received
+        );
+        Ok(amount_received)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::zeroed_state;
+
+    /// Пара с шагом бина 10, базовой комиссией 0.1% и активным бином 0
+    fn pair(variable_fee_control: u32) -> LbPair {
+        let mut pair: LbPair = zeroed_state();
+        pair.bin_step = 10;
+        pair.parameters.base_factor = 10_000;
+        pair.parameters.variable_fee_control = variable_fee_control;
+        pair.parameters.max_volatility_accumulator = 350_000;
+        pair.parameters.min_bin_id = -100;
+        pair.parameters.max_bin_id = 100;
+        pair
+    }
+
+    /// Массив бинов, в котором токен Y есть только в заданных бинах
+    fn bin_array(index: i64, amounts_y: &[(i32, u64)]) -> BinArray {
+        let mut bins = vec![
+            Bin {
+                amount_x: 0,
+                amount_y: 0,
+                price: 0,
+            };
+            MAX_BIN_PER_ARRAY as usize
+        ];
+        for &(bin_id, amount_y) in amounts_y {
+            bins[bin_id.rem_euclid(MAX_BIN_PER_ARRAY) as usize].amount_y = amount_y;
+        }
+        BinArray { index, bins }
+    }
+
+    #[test]
+    fn price_from_id_matches_reference_values() {
+        // Выход `get_price_from_id` DLMM
+        let vectors: [(i32, u16, u128); 7] = [
+            (0, 10, ONE_X64),
+            (1, 10, 18_465_190_817_783_261_167),
+            (-1, 10, 18_428_315_757_951_600_016),
+            (100, 10, 20_385_786_447_693_972_794),
+            (-100, 10, 16_692_138_308_916_259_079),
+            (1000, 25, 224_027_336_091_247_007_920),
+            (-1000, 25, 1_518_932_344_856_077_884),
+        ];
+
+        for (bin_id, bin_step, expected) in vectors {
+            assert_eq!(
+                MeteoraDlmm::price_from_id(bin_id, bin_step).unwrap(),
+                expected,
+                "bin {bin_id} step {bin_step}"
+            );
+        }
+    }
+
+    #[test]
+    fn price_from_id_rejects_overflow() {
+        assert!(MeteoraDlmm::price_from_id(5000, 100).is_err());
+    }
+
+    #[test]
+    fn total_fee_rate_adds_variable_fee() {
+        assert_eq!(MeteoraDlmm::total_fee_rate(&pair(0), 10_000), 1_000_000);
+        // 40000 * (10000 * 10)^2 / 1e11 = 4000
+        assert_eq!(
+            MeteoraDlmm::total_fee_rate(&pair(40_000), 10_000),
+            1_004_000
+        );
+        // Переменная часть округляется вверх
+        assert_eq!(MeteoraDlmm::total_fee_rate(&pair(1), 1), 1_000_001);
+    }
+
+    #[test]
+    fn simulate_swap_within_active_bin() {
+        let dlmm = MeteoraDlmm::new();
+        let arrays = [bin_array(0, &[(0, 1_000)])];
+
+        let (amount_out, fee) = dlmm.simulate_swap(&pair(0), &arrays, 500, true, 0).unwrap();
+        assert_eq!(fee, 1);
+        assert_eq!(amount_out, 499);
+    }
+
+    #[test]
+    fn simulate_swap_crosses_into_next_bin() {
+        let dlmm = MeteoraDlmm::new();
+        let arrays = [bin_array(0, &[(0, 100)]), bin_array(-1, &[(-1, 1_000)])];
+
+        // Бин 0: 100 + комиссия 1 по цене 1.0; бин -1: 198 после комиссии по цене 1/1.001
+        let (amount_out, fee) = dlmm.simulate_swap(&pair(0), &arrays, 300, true, 0).unwrap();
+        assert_eq!(fee, 2);
+        assert_eq!(amount_out, 100 + 197);
+    }
+
+    #[test]
+    fn simulate_swap_requires_supplied_bin_arrays() {
+        let dlmm = MeteoraDlmm::new();
+        let arrays = [bin_array(0, &[(0, 100)])];
+
+        assert!(matches!(
+            dlmm.simulate_swap(&pair(0), &arrays, 300, true, 0),
+            Err(AutoBuyerError::InsufficientLiquidity)
+        ));
+    }
+}
@@ -1,17 +1,112 @@
+pub mod math;
+pub mod meteora_damm;
+pub mod meteora_dlmm;
+pub mod openbook_v2;
+#[cfg(feature = "phoenix")]
+pub mod phoenix;
+pub mod pump_fun;
+pub mod pump_swap;
 pub mod raydium;
+#[cfg(feature = "raydium-clmm")]
+pub mod raydium_clmm;
+pub mod raydium_cpmm;
+pub mod saber;
+pub mod token_2022;
 pub mod types;
+pub mod whirlpool;
 
-use pinocchio::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+use borsh::BorshDeserialize;
+use pinocchio::{account_info::AccountInfo, msg, pubkey::Pubkey};
+use spl_token::{solana_program::program_pack::Pack, state::Account as TokenAccount};
 
 use crate::{
     error::AutoBuyerError,
-    state::{SwapCalculation, TradingPair},
+    state::{constants, PriceQ64, SwapCalculation, TradingPair},
 };
 
-use self::types::SwapParams;
+use self::types::{
+    PoolSearchResult, PoolSelection, RouteExecution, RouteHop, SplitExecution, SplitLeg,
+    SwapDirection, SwapExecution, SwapMode, SwapParams,
+};
+
+/// Получить баланс токенов из аккаунта
+///
+/// Поддерживает аккаунты Token-2022: расширения после базовой раскладки игнорируются.
+pub fn get_token_balance(account_info: &AccountInfo) -> Result<u64, AutoBuyerError> {
+    let data = account_info
+        .try_borrow_data()
+        .map_err(|_| AutoBuyerError::CpiError)?;
+    let token_account = TokenAccount::unpack(
+        data.get(..TokenAccount::LEN)
+            .ok_or(AutoBuyerError::CpiError)?,
+    )
+    .map_err(|e| {
+        msg!("Token error: {:?}", e);
+        AutoBuyerError::CpiError
+    })?;
+    Ok(token_account.amount)
+}
+
+/// Прочитать Anchor-аккаунт после проверки владельца и дискриминатора
+pub(crate) fn load_anchor_account<T: BorshDeserialize>(
+    account: &AccountInfo,
+    owner: &Pubkey,
+    discriminator: &[u8; 8],
+) -> Result<T, AutoBuyerError> {
+    if account.owner() != owner {
+        return Err(AutoBuyerError::InvalidAccountOwner);
+    }
+
+    let data = account
+        .try_borrow_data()
+        .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+    if data.len() < 8 || data[..8] != *discriminator {
+        return Err(AutoBuyerError::InvalidParameters);
+    }
+
+    T::deserialize(&mut &data[8..]).map_err(|_| AutoBuyerError::InvalidParameters)
+}
+
+/// Состояние Anchor-аккаунта с нулевыми полями для тестов провайдеров
+#[cfg(test)]
+pub(crate) fn zeroed_state<T: BorshDeserialize>() -> T {
+    T::deserialize(&mut &[0u8; 4096][..]).unwrap()
+}
+
+/// Проверить фактически полученное количество по изменению баланса аккаунта назначения
+pub fn verify_received_amount(
+    destination_account: &AccountInfo,
+    balance_before: u64,
+    min_amount_out: u64,
+) -> Result<u64, AutoBuyerError> {
+    let balance_after = get_token_balance(destination_account)?;
+    let amount_received = balance_after
+        .checked_sub(balance_before)
+        .ok_or(AutoBuyerError::MathOverflow)?;
+
+    if amount_received < min_amount_out {
+        msg!(
+            "Error: Received {} is below minimum {}",
+            amount_received,
+            min_amount_out
+        );
+        return Err(AutoBuyerError::ReceivedAmountTooLow);
+    }
+
+    Ok(amount_received)
+}
 
 /// Трейт для взаимодействия с DEX
 pub trait DexInterface {
+    /// Количество аккаунтов инструкции, необходимых провайдеру (включая общие 0-6)
+    fn accounts_len(&self) -> usize;
+
+    /// Оплачивается ли обмен лампортами пользователя (аккаунт 0), а не аккаунтом источника
+    fn spends_lamports(&self) -> bool {
+        false
+    }
+
     /// Найти торговую пару
     fn find_trading_pair(
         &self,
@@ -28,80 +123,907 @@ pub trait DexInterface {
         accounts: &[AccountInfo],
     ) -> Result<SwapCalculation, AutoBuyerError>;
 
+    /// Рассчитать обмен с фиксированным количеством выходного токена
+    fn calculate_swap_exact_out(
+        &self,
+        _trading_pair: &TradingPair,
+        _amount_out: u64,
+        _accounts: &[AccountInfo],
+    ) -> Result<SwapCalculation, AutoBuyerError> {
+        Err(AutoBuyerError::SwapModeNotSupported)
+    }
+
     /// Выполнить обмен
+    ///
+    /// Возвращает фактически полученное количество выходного токена.
     fn execute_swap(
         &self,
         program_id: &Pubkey,
         accounts: &[AccountInfo],
         swap_params: &SwapParams,
-    ) -> ProgramResult;
+    ) -> Result<u64, AutoBuyerError>;
 }
 
-/// Менеджер DEX для выбора подходящего провайдера
-pub struct DexManager {
-    providers: Vec<Box<dyn DexInterface>>,
+/// Количество общих аккаунтов перед блоками пулов-кандидатов
+const COMMON_ACCOUNTS_LEN: usize = 5;
+
+/// Количество частей, на которые делится вход при распределении между пулами
+const SPLIT_PARTS: u64 = 10;
+
+/// Пул-кандидат с собственным провайдером
+struct PoolCandidate {
+    /// Провайдер DEX пула
+    provider: Box<dyn DexInterface>,
+    /// Аккаунты в раскладке провайдера: общие 0-4 и блок пула начиная с программы DEX
+    accounts: Vec<AccountInfo>,
 }
 
+/// Менеджер DEX для выбора лучшего пула
+///
+/// После общих аккаунтов 0-4 передаются блоки пулов-кандидатов подряд, каждый начинается
+/// с программы DEX и имеет раскладку своего провайдера. Обмен выполняется на пуле с лучшей
+/// котировкой.
+pub struct DexManager;
+
 impl DexManager {
     /// Создать новый менеджер DEX
     pub fn new() -> Self {
-        let providers: Vec<Box<dyn DexInterface>> = vec![Box::new(raydium::RaydiumV4::new())];
+        Self
+    }
+
+    /// Создать провайдера для программы DEX
+    ///
+    /// Каждому кандидату нужен отдельный экземпляр: провайдер кэширует состояние одного пула.
+    fn create_provider(dex_program: &Pubkey) -> Option<Box<dyn DexInterface>> {
+        let provider: Box<dyn DexInterface> = match *dex_program {
+            constants::RAYDIUM_V4_PROGRAM_ID => Box::new(raydium::RaydiumV4::new()),
+            constants::RAYDIUM_CPMM_PROGRAM_ID => Box::new(raydium_cpmm::RaydiumCpmm::new()),
+            constants::WHIRLPOOL_PROGRAM_ID => Box::new(whirlpool::Whirlpool::new()),
+            constants::METEORA_DLMM_PROGRAM_ID => Box::new(meteora_dlmm::MeteoraDlmm::new()),
+            constants::METEORA_DAMM_PROGRAM_ID => Box::new(meteora_damm::MeteoraDamm::new()),
+            constants::PUMP_FUN_PROGRAM_ID => Box::new(pump_fun::PumpFun::new()),
+            constants::PUMP_SWAP_PROGRAM_ID => Box::new(pump_swap::PumpSwap::new()),
+            constants::OPENBOOK_V2_PROGRAM_ID => Box::new(openbook_v2::OpenBookV2::new()),
+            constants::SABER_PROGRAM_ID => Box::new(saber::Saber::new()),
+            #[cfg(feature = "raydium-clmm")]
+            constants::RAYDIUM_CLMM_PROGRAM_ID => Box::new(raydium_clmm::RaydiumClmm::new()),
+            #[cfg(feature = "phoenix")]
+            constants::PHOENIX_PROGRAM_ID => Box::new(phoenix::Phoenix::new()),
+            _ => return None,
+        };
+
+        Some(provider)
+    }
+
+    /// Прочитать блок пула, начинающийся с программы DEX
+    ///
+    /// Возвращает провайдера и конец блока. Неполный блок возвращается как есть: провайдер
+    /// сообщит о нехватке аккаунтов.
+    fn pool_block(
+        accounts: &[AccountInfo],
+        offset: usize,
+    ) -> Result<(Box<dyn DexInterface>, usize), AutoBuyerError> {
+        let dex_program = accounts.get(offset).ok_or_else(|| {
+            msg!("Error: Missing DEX program at account {}", offset);
+            AutoBuyerError::InvalidParameters
+        })?;
+
+        let provider = Self::create_provider(dex_program.key()).ok_or_else(|| {
+            msg!("Error: Unsupported DEX program at account {}", offset);
+            AutoBuyerError::PoolNotFound
+        })?;
+
+        let end = (offset + provider.accounts_len() - COMMON_ACCOUNTS_LEN).min(accounts.len());
+
+        Ok((provider, end))
+    }
+
+    /// Разбить аккаунты инструкции на пулы-кандидаты
+    fn candidate_pools(accounts: &[AccountInfo]) -> Result<Vec<PoolCandidate>, AutoBuyerError> {
+        let mut candidates = Vec::new();
+        let mut offset = COMMON_ACCOUNTS_LEN;
 
-        Self { providers }
+        while offset < accounts.len() {
+            let (provider, end) = Self::pool_block(accounts, offset)?;
+
+            let mut candidate_accounts = accounts[..COMMON_ACCOUNTS_LEN].to_vec();
+            candidate_accounts.extend_from_slice(&accounts[offset..end]);
+            candidates.push(PoolCandidate {
+                provider,
+                accounts: candidate_accounts,
+            });
+
+            offset = end;
+        }
+
+        Ok(candidates)
     }
 
-    /// Найти лучшую торговую пару среди всех DEX
-    pub fn find_best_trading_pair(
+    /// Разбить аккаунты инструкции на пулы шагов маршрута
+    ///
+    /// После общих аккаунтов 0-4 идут пары (аккаунт пользователя, минт) промежуточных
+    /// токенов, затем блоки пулов шагов по порядку. Аккаунты каждого шага собираются в
+    /// раскладке покупки: вход шага - токен-котировка, выход - целевой токен.
+    fn route_pools(
+        accounts: &[AccountInfo],
+        hops: usize,
+    ) -> Result<Vec<PoolCandidate>, AutoBuyerError> {
+        // Аккаунт пользователя и минт промежуточного токена после шага `hop`
+        let intermediate = |hop: usize| COMMON_ACCOUNTS_LEN + 2 * hop;
+
+        let mut route = Vec::with_capacity(hops);
+        let mut offset = intermediate(hops - 1);
+
+        if accounts.len() <= offset {
+            msg!(
+                "Error: Insufficient accounts for route. Expected at least {}, got {}",
+                offset + 1,
+                accounts.len()
+            );
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        for hop in 0..hops {
+            let (source, input_mint) = match hop {
+                0 => (&accounts[1], &accounts[4]),
+                _ => (
+                    &accounts[intermediate(hop - 1)],
+                    &accounts[intermediate(hop - 1) + 1],
+                ),
+            };
+            let (destination, output_mint) = if hop == hops - 1 {
+                (&accounts[2], &accounts[3])
+            } else {
+                (
+                    &accounts[intermediate(hop)],
+                    &accounts[intermediate(hop) + 1],
+                )
+            };
+
+            let (provider, end) = Self::pool_block(accounts, offset)?;
+
+            // Промежуточный токен приходит на токеновый аккаунт, а не в лампорты пользователя
+            if hop > 0 && provider.spends_lamports() {
+                msg!(
+                    "Error: Pool of hop {} is paid with lamports and can only be the first hop",
+                    hop
+                );
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+
+            let mut hop_accounts = vec![
+                accounts[0].clone(),
+                source.clone(),
+                destination.clone(),
+                output_mint.clone(),
+                input_mint.clone(),
+            ];
+            hop_accounts.extend_from_slice(&accounts[offset..end]);
+            route.push(PoolCandidate {
+                provider,
+                accounts: hop_accounts,
+            });
+
+            offset = end;
+        }
+
+        if offset < accounts.len() {
+            msg!(
+                "Error: Unexpected accounts after route pools. Expected {}, got {}",
+                offset,
+                accounts.len()
+            );
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        Ok(route)
+    }
+
+    /// Оплачиваются ли все пулы-кандидаты лампортами пользователя
+    ///
+    /// В этом случае токен-котировка не списывается с аккаунта источника. Если хотя бы один
+    /// кандидат списывает аккаунт источника, его баланс должен покрывать весь вход.
+    pub fn funded_by_lamports(&self, accounts: &[AccountInfo]) -> Result<bool, AutoBuyerError> {
+        let candidates = Self::candidate_pools(accounts)?;
+        Ok(candidates
+            .iter()
+            .all(|candidate| candidate.provider.spends_lamports()))
+    }
+
+    /// Оплачивается ли первый шаг маршрута лампортами пользователя
+    ///
+    /// В этом случае вход маршрута не списывается с аккаунта источника.
+    pub fn route_funded_by_lamports(
         &self,
-        base_mint: &Pubkey,
-        quote_mint: &Pubkey,
         accounts: &[AccountInfo],
-    ) -> Result<(TradingPair, &dyn DexInterface), AutoBuyerError> {
-        for provider in &self.providers {
-            if let Some(trading_pair) =
-                provider.find_trading_pair(base_mint, quote_mint, accounts)?
-            {
-                return Ok((trading_pair, provider.as_ref()));
+        hops: usize,
+    ) -> Result<bool, AutoBuyerError> {
+        let route = Self::route_pools(accounts, hops)?;
+        Ok(route[0].provider.spends_lamports())
+    }
+
+    /// Рассчитать котировку на пуле-кандидате
+    fn quote_candidate(
+        candidate: &PoolCandidate,
+        output_mint: &Pubkey,
+        input_mint: &Pubkey,
+        mode: SwapMode,
+        amount: u64,
+    ) -> Result<Option<(TradingPair, SwapCalculation)>, AutoBuyerError> {
+        let trading_pair = match candidate.provider.find_trading_pair(
+            output_mint,
+            input_mint,
+            &candidate.accounts,
+        )? {
+            Some(trading_pair) => trading_pair,
+            None => return Ok(None),
+        };
+
+        let calculation = match mode {
+            SwapMode::ExactIn => {
+                candidate
+                    .provider
+                    .calculate_swap(&trading_pair, amount, &candidate.accounts)?
+            }
+            SwapMode::ExactOut => candidate.provider.calculate_swap_exact_out(
+                &trading_pair,
+                amount,
+                &candidate.accounts,
+            )?,
+        };
+
+        Ok(Some((trading_pair, calculation)))
+    }
+
+    /// Котировать всех кандидатов и выбрать лучший пул
+    ///
+    /// Кандидаты, которые не удалось котировать, пропускаются. Если не подошел ни один,
+    /// возвращается последняя ошибка котировки.
+    fn select_pool(
+        candidates: &[PoolCandidate],
+        output_mint: &Pubkey,
+        input_mint: &Pubkey,
+        mode: SwapMode,
+        amount: u64,
+    ) -> Result<PoolSelection, AutoBuyerError> {
+        let mut results = Vec::with_capacity(candidates.len());
+        let mut last_error = AutoBuyerError::PoolNotFound;
+
+        for (index, candidate) in candidates.iter().enumerate() {
+            match Self::quote_candidate(candidate, output_mint, input_mint, mode, amount) {
+                Ok(Some((trading_pair, calculation))) => {
+                    let mut result = PoolSearchResult {
+                        candidate_index: index,
+                        dex_program: *candidate.accounts[COMMON_ACCOUNTS_LEN].key(),
+                        trading_pair,
+                        calculation,
+                        score: 0,
+                    };
+                    result.calculate_score(mode);
+                    results.push(result);
+                }
+                Ok(None) => msg!("Candidate {} skipped: pair not found", index),
+                Err(e) => {
+                    msg!("Candidate {} skipped: {:?}", index, e);
+                    last_error = e;
+                }
             }
         }
 
-        Err(AutoBuyerError::PoolNotFound)
+        // Стабильная сортировка: при равном рейтинге выигрывает кандидат, переданный раньше
+        results.sort_by_key(|result| core::cmp::Reverse(result.score));
+
+        let mut ranked = results.into_iter();
+        let best = ranked.next().ok_or(last_error)?;
+
+        msg!(
+            "Best pool: candidate {} ({} -> {})",
+            best.candidate_index,
+            best.calculation.amount_in,
+            best.calculation.amount_out
+        );
+
+        Ok(PoolSelection {
+            best,
+            runner_up: ranked.next(),
+        })
     }
 
-    /// Выполнить автоматический обмен
+    /// Рассчитать котировку покупки на лучшем пуле без выполнения обмена
+    pub fn quote_swap(
+        &self,
+        accounts: &[AccountInfo],
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        amount_in: u64,
+    ) -> Result<SwapCalculation, AutoBuyerError> {
+        let candidates = Self::candidate_pools(accounts)?;
+        let selection = Self::select_pool(
+            &candidates,
+            base_mint,
+            quote_mint,
+            SwapMode::ExactIn,
+            amount_in,
+        )?;
+
+        Ok(selection.best.calculation)
+    }
+
+    /// Выполнить автоматический обмен на лучшем пуле
+    ///
+    /// В возвращаемом расчете `amount_out` - фактически полученное количество.
+    /// При покупке токен-котировка обменивается на целевой токен, при продаже - наоборот.
+    #[allow(clippy::too_many_arguments)]
     pub fn execute_auto_swap(
         &self,
         program_id: &Pubkey,
         accounts: &[AccountInfo],
         base_mint: &Pubkey,
         quote_mint: &Pubkey,
+        direction: SwapDirection,
         amount_in: u64,
         min_amount_out: u64,
-    ) -> Result<SwapCalculation, AutoBuyerError> {
-        // Найти лучшую торговую пару
-        let (trading_pair, provider) =
-            self.find_best_trading_pair(base_mint, quote_mint, accounts)?;
+    ) -> Result<SwapExecution, AutoBuyerError> {
+        // Для продажи входным становится целевой токен
+        let (output_mint, input_mint) = match direction {
+            SwapDirection::Buy => (base_mint, quote_mint),
+            SwapDirection::Sell => (quote_mint, base_mint),
+        };
 
-        // Рассчитать обмен
-        let calculation = provider.calculate_swap(&trading_pair, amount_in, accounts)?;
+        // Котировать кандидатов и выбрать лучший пул
+        let candidates = Self::candidate_pools(accounts)?;
+        let selection = Self::select_pool(
+            &candidates,
+            output_mint,
+            input_mint,
+            SwapMode::ExactIn,
+            amount_in,
+        )?;
+        let best = &selection.best;
 
         // Проверить проскальзывание
-        if calculation.amount_out < min_amount_out {
+        if best.calculation.amount_out < min_amount_out {
             return Err(AutoBuyerError::SlippageTooHigh);
         }
 
         // Выполнить обмен
+        let candidate = &candidates[best.candidate_index];
         let swap_params = SwapParams {
-            trading_pair,
+            trading_pair: best.trading_pair.clone(),
+            direction,
+            mode: SwapMode::ExactIn,
             amount_in,
             min_amount_out,
-            calculation: calculation.clone(),
+            calculation: best.calculation.clone(),
+        };
+
+        let amount_received =
+            candidate
+                .provider
+                .execute_swap(program_id, &candidate.accounts, &swap_params)?;
+
+        Ok(SwapExecution {
+            calculation: SwapCalculation {
+                amount_out: amount_received,
+                ..best.calculation.clone()
+            },
+            selection,
+        })
+    }
+
+    /// Выполнить покупку точного количества целевого токена на лучшем пуле
+    ///
+    /// В возвращаемом расчете `amount_out` - фактически полученное количество.
+    pub fn execute_auto_swap_exact_out(
+        &self,
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        amount_out: u64,
+        max_amount_in: u64,
+    ) -> Result<SwapExecution, AutoBuyerError> {
+        // Котировать кандидатов и выбрать пул с наименьшим необходимым входом
+        let candidates = Self::candidate_pools(accounts)?;
+        let selection = Self::select_pool(
+            &candidates,
+            base_mint,
+            quote_mint,
+            SwapMode::ExactOut,
+            amount_out,
+        )?;
+        let best = &selection.best;
+
+        // Проверить проскальзывание
+        if best.calculation.amount_in > max_amount_in {
+            return Err(AutoBuyerError::SlippageTooHigh);
+        }
+
+        // Выполнить обмен
+        let candidate = &candidates[best.candidate_index];
+        let swap_params = SwapParams {
+            trading_pair: best.trading_pair.clone(),
+            direction: SwapDirection::Buy,
+            mode: SwapMode::ExactOut,
+            amount_in: max_amount_in,
+            min_amount_out: amount_out,
+            calculation: best.calculation.clone(),
         };
 
-        provider
-            .execute_swap(program_id, accounts, &swap_params)
-            .map_err(|_| AutoBuyerError::CpiError)?;
+        let amount_received =
+            candidate
+                .provider
+                .execute_swap(program_id, &candidate.accounts, &swap_params)?;
+
+        Ok(SwapExecution {
+            calculation: SwapCalculation {
+                amount_out: amount_received,
+                ..best.calculation.clone()
+            },
+            selection,
+        })
+    }
+
+    /// Распределить вход между пулами
+    ///
+    /// Вход делится на `SPLIT_PARTS` частей, каждая отдается пулу с наибольшим приростом
+    /// выхода с учетом уже выделенного ему объема (см. `allocate_parts`). Возвращает пулы
+    /// с ненулевой долей.
+    fn allocate_split(
+        candidates: &[PoolCandidate],
+        output_mint: &Pubkey,
+        input_mint: &Pubkey,
+        amount_in: u64,
+    ) -> Result<Vec<SplitLeg>, AutoBuyerError> {
+        let mut routes: Vec<(usize, TradingPair)> = Vec::with_capacity(candidates.len());
+        let mut last_error = AutoBuyerError::PoolNotFound;
+
+        for (index, candidate) in candidates.iter().enumerate() {
+            match candidate
+                .provider
+                .find_trading_pair(output_mint, input_mint, &candidate.accounts)
+            {
+                Ok(Some(trading_pair)) => {
+                    // Котировки повторного пула не учитывали бы первую часть обмена
+                    let pool_address = trading_pair.pool_config.pool_address;
+                    if routes
+                        .iter()
+                        .any(|(_, pair)| pair.pool_config.pool_address == pool_address)
+                    {
+                        msg!("Error: Pool of candidate {} is supplied twice", index);
+                        return Err(AutoBuyerError::InvalidParameters);
+                    }
+                    routes.push((index, trading_pair));
+                }
+                Ok(None) => msg!("Candidate {} skipped: pair not found", index),
+                Err(e) => {
+                    msg!("Candidate {} skipped: {:?}", index, e);
+                    last_error = e;
+                }
+            }
+        }
+
+        let allocations = Self::allocate_parts(routes.len(), amount_in, |route, amount| {
+            let (index, trading_pair) = &routes[route];
+            let candidate = &candidates[*index];
+            candidate
+                .provider
+                .calculate_swap(trading_pair, amount, &candidate.accounts)
+                .map_err(|e| last_error = e)
+                .ok()
+        })
+        .ok_or(last_error)?;
+
+        let legs = routes
+            .into_iter()
+            .zip(allocations)
+            .filter_map(|((index, trading_pair), (amount_in, quote))| {
+                let mut result = PoolSearchResult {
+                    candidate_index: index,
+                    dex_program: *candidates[index].accounts[COMMON_ACCOUNTS_LEN].key(),
+                    trading_pair,
+                    calculation: quote?,
+                    score: 0,
+                };
+                result.calculate_score(SwapMode::ExactIn);
+
+                Some(SplitLeg {
+                    amount_in,
+                    quote: result,
+                    amount_received: 0,
+                })
+            })
+            .collect();
+
+        Ok(legs)
+    }
+
+    /// Жадно распределить `amount_in` между `routes` маршрутами по `SPLIT_PARTS` частям
+    ///
+    /// `quote` котирует маршрут на полный объем и возвращает `None`, если маршрут его не
+    /// принимает. Объем маршрута меняется только при получении части, поэтому каждый
+    /// маршрут котируется один раз на каждом достигнутом объеме: всего не больше
+    /// `2·routes + SPLIT_PARTS` котировок. Остаток от деления на части достается маршруту
+    /// с наибольшим приростом от него. Возвращает объем и котировку каждого маршрута
+    /// или `None`, если распределить вход не удалось.
+    fn allocate_parts(
+        routes: usize,
+        amount_in: u64,
+        mut quote: impl FnMut(usize, u64) -> Option<SwapCalculation>,
+    ) -> Option<Vec<(u64, Option<SwapCalculation>)>> {
+        let mut allocations: Vec<(u64, Option<SwapCalculation>)> = vec![(0, None); routes];
+        let part = amount_in / SPLIT_PARTS;
+
+        // Котировка маршрута на объем после получения еще одной части
+        let mut next_quotes: Vec<Option<Option<SwapCalculation>>> = vec![None; routes];
+
+        for _ in 0..SPLIT_PARTS {
+            if part == 0 {
+                break;
+            }
+
+            let mut best: Option<(usize, u64)> = None;
+            for (route, (allocated, current)) in allocations.iter().enumerate() {
+                let next = next_quotes[route].get_or_insert_with(|| quote(route, allocated + part));
+                let Some(next) = next else {
+                    continue;
+                };
+
+                let gain = next
+                    .amount_out
+                    .saturating_sub(current.as_ref().map_or(0, |q| q.amount_out));
+                let improves = match best {
+                    Some((_, best_gain)) => gain > best_gain,
+                    None => true,
+                };
+                if improves {
+                    best = Some((route, gain));
+                }
+            }
+
+            let (route, _) = best?;
+            allocations[route].0 += part;
+            allocations[route].1 = next_quotes[route].take().flatten();
+        }
+
+        let remainder = amount_in - part * SPLIT_PARTS;
+        if remainder > 0 {
+            let mut best: Option<(usize, u64, SwapCalculation)> = None;
+            for (route, (allocated, current)) in allocations.iter().enumerate() {
+                let Some(calculation) = quote(route, allocated + remainder) else {
+                    continue;
+                };
+
+                let gain = calculation
+                    .amount_out
+                    .saturating_sub(current.as_ref().map_or(0, |q| q.amount_out));
+                let improves = match &best {
+                    Some((_, best_gain, _)) => gain > *best_gain,
+                    None => true,
+                };
+                if improves {
+                    best = Some((route, gain, calculation));
+                }
+            }
+
+            let (route, _, calculation) = best?;
+            allocations[route].0 += remainder;
+            allocations[route].1 = Some(calculation);
+        }
+
+        Some(allocations)
+    }
+
+    /// Минимум части - ее котировка с тем же допуском проскальзывания, что и у всей сделки
+    fn leg_min_amount_out(leg_quoted_out: u64, min_amount_out: u64, quoted_out: u64) -> u64 {
+        (leg_quoted_out as u128 * min_amount_out as u128)
+            .checked_div(quoted_out as u128)
+            .unwrap_or(0) as u64
+    }
+
+    /// Выполнить покупку, разделенную между несколькими пулами
+    ///
+    /// Вход распределяется между переданными пулами-кандидатами так, чтобы максимизировать
+    /// суммарный выход, затем каждая часть исполняется на своем пуле. Каждая часть
+    /// получает минимум пропорционально своей котировке, `min_amount_out` дополнительно
+    /// проверяется по сумме всех частей.
+    pub fn execute_split_swap(
+        &self,
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<SplitExecution, AutoBuyerError> {
+        let candidates = Self::candidate_pools(accounts)?;
+        let mut legs = Self::allocate_split(&candidates, base_mint, quote_mint, amount_in)?;
+
+        // Проверить проскальзывание по суммарной котировке
+        let quoted_out = legs.iter().try_fold(0u64, |total, leg| {
+            total
+                .checked_add(leg.quote.calculation.amount_out)
+                .ok_or(AutoBuyerError::MathOverflow)
+        })?;
+        if quoted_out < min_amount_out {
+            return Err(AutoBuyerError::SlippageTooHigh);
+        }
+
+        let mut amount_received = 0u64;
+        let mut fee_amount = 0u64;
+        let mut weighted_impact = 0u128;
+
+        for leg in &mut legs {
+            let candidate = &candidates[leg.quote.candidate_index];
+            msg!(
+                "Split leg: candidate {} ({} -> {})",
+                leg.quote.candidate_index,
+                leg.amount_in,
+                leg.quote.calculation.amount_out
+            );
+
+            let leg_min_amount_out = Self::leg_min_amount_out(
+                leg.quote.calculation.amount_out,
+                min_amount_out,
+                quoted_out,
+            );
+
+            let swap_params = SwapParams {
+                trading_pair: leg.quote.trading_pair.clone(),
+                direction: SwapDirection::Buy,
+                mode: SwapMode::ExactIn,
+                amount_in: leg.amount_in,
+                min_amount_out: leg_min_amount_out,
+                calculation: leg.quote.calculation.clone(),
+            };
+
+            leg.amount_received =
+                candidate
+                    .provider
+                    .execute_swap(program_id, &candidate.accounts, &swap_params)?;
+
+            amount_received = amount_received
+                .checked_add(leg.amount_received)
+                .ok_or(AutoBuyerError::MathOverflow)?;
+            fee_amount = fee_amount
+                .checked_add(leg.quote.calculation.fee_amount)
+                .ok_or(AutoBuyerError::MathOverflow)?;
+            weighted_impact +=
+                leg.quote.calculation.price_impact_bps as u128 * leg.amount_in as u128;
+        }
+
+        if amount_received < min_amount_out {
+            msg!(
+                "Error: Received {} is below minimum {}",
+                amount_received,
+                min_amount_out
+            );
+            return Err(AutoBuyerError::ReceivedAmountTooLow);
+        }
+
+        Ok(SplitExecution {
+            calculation: SwapCalculation {
+                amount_in,
+                amount_out: amount_received,
+                fee_amount,
+                price_per_unit: PriceQ64::from_ratio(amount_received, amount_in)
+                    .ok_or(AutoBuyerError::MathOverflow)?,
+                price_impact_bps: (weighted_impact / amount_in as u128) as u16,
+            },
+            legs,
+        })
+    }
+
+    /// Выполнить покупку по многошаговому маршруту через промежуточные токены
+    ///
+    /// Фактический выход каждого шага становится входом следующего. `min_amount_out`
+    /// проверяется по итоговому количеству целевого токена на последнем шаге.
+    pub fn execute_multi_hop_swap(
+        &self,
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        hops: usize,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<RouteExecution, AutoBuyerError> {
+        let route = Self::route_pools(accounts, hops)?;
+
+        // Котировать маршрут целиком до выполнения первого шага
+        let mut trading_pairs = Vec::with_capacity(route.len());
+        let mut quoted_out = amount_in;
+        for (hop, pool) in route.iter().enumerate() {
+            let trading_pair = pool
+                .provider
+                .find_trading_pair(
+                    pool.accounts[3].key(),
+                    pool.accounts[4].key(),
+                    &pool.accounts,
+                )?
+                .ok_or_else(|| {
+                    msg!("Error: Pool of hop {} does not trade the route mints", hop);
+                    AutoBuyerError::PoolNotFound
+                })?;
+
+            quoted_out = pool
+                .provider
+                .calculate_swap(&trading_pair, quoted_out, &pool.accounts)?
+                .amount_out;
+            trading_pairs.push(trading_pair);
+        }
+
+        msg!("Route quote: {} -> {}", amount_in, quoted_out);
+
+        // Проверить проскальзывание по котировке маршрута
+        if quoted_out < min_amount_out {
+            return Err(AutoBuyerError::SlippageTooHigh);
+        }
+
+        let last_hop = route.len() - 1;
+        let mut executed = Vec::with_capacity(route.len());
+        let mut hop_amount_in = amount_in;
+
+        for (hop, (pool, trading_pair)) in route.iter().zip(trading_pairs).enumerate() {
+            // Котировка пересчитывается под фактический выход предыдущего шага
+            let calculation =
+                pool.provider
+                    .calculate_swap(&trading_pair, hop_amount_in, &pool.accounts)?;
+
+            let swap_params = SwapParams {
+                trading_pair: trading_pair.clone(),
+                direction: SwapDirection::Buy,
+                mode: SwapMode::ExactIn,
+                amount_in: hop_amount_in,
+                // Проскальзывание маршрута проверяется на последнем шаге
+                min_amount_out: if hop == last_hop { min_amount_out } else { 0 },
+                calculation: calculation.clone(),
+            };
+
+            let amount_received =
+                pool.provider
+                    .execute_swap(program_id, &pool.accounts, &swap_params)?;
+
+            msg!("Hop {}: {} -> {}", hop, hop_amount_in, amount_received);
+
+            let mut quote = PoolSearchResult {
+                candidate_index: hop,
+                dex_program: *pool.accounts[COMMON_ACCOUNTS_LEN].key(),
+                trading_pair,
+                calculation,
+                score: 0,
+            };
+            quote.calculate_score(SwapMode::ExactIn);
+            executed.push(RouteHop {
+                quote,
+                amount_received,
+            });
+
+            hop_amount_in = amount_received;
+        }
+
+        Ok(RouteExecution {
+            amount_in,
+            amount_out: hop_amount_in,
+            hops: executed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Котировка пула с постоянным произведением резервов `reserve_in`/`reserve_out`
+    fn constant_product(reserve_in: u64, reserve_out: u64, amount_in: u64) -> SwapCalculation {
+        let amount_out =
+            (amount_in as u128 * reserve_out as u128 / (reserve_in + amount_in) as u128) as u64;
+        SwapCalculation {
+            amount_in,
+            amount_out,
+            fee_amount: 0,
+            price_per_unit: PriceQ64::default(),
+            price_impact_bps: 0,
+        }
+    }
+
+    /// Распределение между пулами с резервами `pools` и число запрошенных котировок
+    fn allocate(pools: &[(u64, u64)], amount_in: u64) -> (Vec<(u64, Option<u64>)>, usize) {
+        let mut quotes = 0;
+        let allocations = DexManager::allocate_parts(pools.len(), amount_in, |route, amount| {
+            quotes += 1;
+            let (reserve_in, reserve_out) = pools[route];
+            Some(constant_product(reserve_in, reserve_out, amount))
+        });
+
+        let allocations = allocations
+            .unwrap()
+            .into_iter()
+            .map(|(amount, quote)| (amount, quote.map(|q| q.amount_out)))
+            .collect();
+        (allocations, quotes)
+    }
+
+    const POOLS: [(u64, u64); 3] = [
+        (1_000_000_000, 2_000_000_000),
+        (3_000_000_000, 6_000_000_000),
+        (500_000_000, 900_000_000),
+    ];
+
+    #[test]
+    fn split_gives_each_part_to_largest_gain() {
+        let (allocations, quotes) = allocate(&POOLS, 1_000_000_000);
+        assert_eq!(
+            allocations,
+            [
+                (200_000_000, Some(333_333_333)),
+                (700_000_000, Some(1_135_135_135)),
+                (100_000_000, Some(150_000_000)),
+            ]
+        );
+        // Первая часть котирует все пулы, каждая следующая - только пул предыдущей
+        assert_eq!(quotes, POOLS.len() + SPLIT_PARTS as usize - 1);
+    }
+
+    #[test]
+    fn split_remainder_goes_to_largest_gain() {
+        let (allocations, quotes) = allocate(&POOLS, 1_000_000_003);
+        assert_eq!(
+            allocations,
+            [
+                (200_000_003, Some(333_333_337)),
+                (700_000_000, Some(1_135_135_135)),
+                (100_000_000, Some(150_000_000)),
+            ]
+        );
+        assert_eq!(quotes, 2 * POOLS.len() + SPLIT_PARTS as usize - 1);
+    }
+
+    #[test]
+    fn split_below_part_count_goes_to_one_pool() {
+        let pools = [
+            (1_000_000_000, 2_000_000_000),
+            (1_000_000_000, 3_000_000_000),
+        ];
+        let (allocations, quotes) = allocate(&pools, 7);
+        assert_eq!(allocations, [(0, None), (7, Some(20))]);
+        assert_eq!(quotes, pools.len());
+    }
+
+    #[test]
+    fn split_stops_quoting_pool_that_rejects_amount() {
+        let pools = [
+            (1_000_000_000, 2_000_000_000),
+            (10_000_000_000, 20_000_000_000),
+        ];
+        let allocations =
+            DexManager::allocate_parts(pools.len(), 1_000_000_000, |route, amount| {
+                // Второй пул не принимает больше 300 000 000
+                if route == 1 && amount > 300_000_000 {
+                    return None;
+                }
+                let (reserve_in, reserve_out) = pools[route];
+                Some(constant_product(reserve_in, reserve_out, amount))
+            })
+            .unwrap();
+
+        let amounts: Vec<u64> = allocations.iter().map(|(amount, _)| *amount).collect();
+        assert_eq!(amounts, [700_000_000, 300_000_000]);
+    }
+
+    #[test]
+    fn split_without_quotes_fails() {
+        assert!(DexManager::allocate_parts(2, 1_000, |_, _| None).is_none());
+        assert!(DexManager::allocate_parts(2, 7, |_, _| None).is_none());
+    }
 
-        Ok(calculation)
+    #[test]
+    fn leg_minimum_is_proportional_to_quote() {
+        assert_eq!(DexManager::leg_min_amount_out(600, 950, 1_000), 570);
+        assert_eq!(DexManager::leg_min_amount_out(1, 999, 1_000), 0);
+        assert_eq!(DexManager::leg_min_amount_out(1_000, 950, 1_000), 950);
+        assert_eq!(DexManager::leg_min_amount_out(0, 950, 0), 0);
+        assert_eq!(
+            DexManager::leg_min_amount_out(u64::MAX / 2, u64::MAX - 1, u64::MAX),
+            u64::MAX / 2 - 1
+        );
     }
 }
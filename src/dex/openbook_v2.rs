@@ -0,0 +1,885 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::cell::OnceCell;
+
+use crate::{
+    dex::{
+        get_token_balance,
+        types::{SwapMode, SwapParams},
+        verify_received_amount, DexInterface,
+    },
+    error::AutoBuyerError,
+    state::{constants, PoolConfig, PriceQ64, SwapCalculation, TradingPair},
+};
+
+/// Дискриминатор аккаунта Market
+const MARKET_DISCRIMINATOR: [u8; 8] = [219, 190, 213, 55, 0, 227, 198, 154];
+
+/// Дискриминатор аккаунта BookSide
+const BOOK_SIDE_DISCRIMINATOR: [u8; 8] = [72, 44, 225, 141, 178, 130, 97, 57];
+
+/// Дискриминатор инструкции place_take_order
+const PLACE_TAKE_ORDER_DISCRIMINATOR: [u8; 8] = [3, 44, 71, 3, 26, 199, 203, 85];
+
+/// Смещение корня дерева ордеров с фиксированной ценой в данных BookSide
+const FIXED_ROOT_OFFSET: usize = 8;
+
+/// Смещение первого узла дерева в данных BookSide
+const NODES_OFFSET: usize = 840;
+
+/// Размер узла дерева ордеров
+const NODE_SIZE: usize = 88;
+
+/// Количество узлов в BookSide
+const MAX_ORDERTREE_NODES: usize = 1024;
+
+/// Тег внутреннего узла дерева
+const NODE_TAG_INNER: u8 = 1;
+
+/// Тег листового узла (ордера)
+const NODE_TAG_LEAF: u8 = 2;
+
+/// Масштаб ставок комиссий рынка (1e6 = 100%)
+const FEES_SCALE_FACTOR: i128 = 1_000_000;
+
+/// Максимальное количество ордеров, сводимых одной заявкой
+const MATCH_LIMIT: u8 = 50;
+
+/// Сторона заявки OpenBook: покупка базового токена
+const SIDE_BID: u8 = 0;
+
+/// Сторона заявки OpenBook: продажа базового токена
+const SIDE_ASK: u8 = 1;
+
+/// Тип заявки Immediate-or-Cancel
+const ORDER_TYPE_IMMEDIATE_OR_CANCEL: u8 = 1;
+
+/// Количество аккаунтов, необходимых для обмена через OpenBook v2
+const OPENBOOK_ACCOUNTS_LEN: usize = 17;
+
+/// Структура для работы с книгой ордеров OpenBook v2
+///
+/// Котировка строится по ордерам с фиксированной ценой; ордера, привязанные к
+/// оракулу, не учитываются, поэтому фактическое исполнение может быть лучше.
+///
+/// Аккаунты (после общих 0-4):
+/// 5. `[]` Программа OpenBook v2
+/// 6. `[writable]` Market
+/// 7. `[]` Market authority
+/// 8. `[writable]` Bids
+/// 9. `[writable]` Asks
+/// 10. `[writable]` Хранилище базового токена рынка
+/// 11. `[writable]` Хранилище токена-котировки рынка
+/// 12. `[writable]` Event heap
+/// 13. `[]` Oracle A (или программа OpenBook, если оракула нет)
+/// 14. `[]` Oracle B (или программа OpenBook, если оракула нет)
+/// 15. `[]` Программа токенов
+/// 16. `[]` Системная программа
+pub struct OpenBookV2 {
+    /// Состояние рынка, загруженное в рамках текущей инструкции
+    market: OnceCell<(Pubkey, Market)>,
+}
+
+/// Состояние рынка OpenBook v2 (до резерва)
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct Market {
+    pub bump: u8,
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+    pub padding1: [u8; 5],
+    pub market_authority: Pubkey,
+    pub time_expiry: i64,
+    pub collect_fee_admin: Pubkey,
+    pub open_orders_admin: Pubkey,
+    pub consume_events_admin: Pubkey,
+    pub close_market_admin: Pubkey,
+    pub name: [u8; 16],
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub event_heap: Pubkey,
+    pub oracle_a: Pubkey,
+    pub oracle_b: Pubkey,
+    pub oracle_config: [u8; 88],
+    pub quote_lot_size: i64,
+    pub base_lot_size: i64,
+    pub seq_num: u64,
+    pub registration_time: i64,
+    pub maker_fee: i64,
+    pub taker_fee: i64,
+    pub fees_accrued: u128,
+    pub fees_to_referrers: u128,
+    pub referrer_rebates_accrued: u64,
+    pub fees_available: u64,
+    pub maker_volume: u128,
+    pub taker_volume_wo_oo: u128,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub market_base_vault: Pubkey,
+    pub base_deposit_total: u64,
+    pub market_quote_vault: Pubkey,
+    pub quote_deposit_total: u64,
+}
+
+/// Ордер книги в лотах рынка
+#[derive(Debug, Clone, Copy)]
+struct BookOrder {
+    /// Цена в лотах котировки за лот базового токена
+    price_lots: i64,
+    /// Количество в лотах базового токена
+    quantity: i64,
+}
+
+/// Результат сведения заявки с книгой
+#[derive(Debug, Clone, Copy)]
+struct BookFill {
+    /// Исполненное количество в лотах базового токена
+    base_lots: i64,
+    /// Исполненная сумма в лотах котировки
+    quote_lots: i64,
+}
+
+/// Инструкция place_take_order OpenBook v2
+#[derive(BorshSerialize)]
+struct PlaceTakeOrderInstruction {
+    discriminator: [u8; 8],
+    side: u8,
+    price_lots: i64,
+    max_base_lots: i64,
+    max_quote_lots_including_fees: i64,
+    order_type: u8,
+    limit: u8,
+}
+
+impl OpenBookV2 {
+    /// Создать новый экземпляр OpenBook v2
+    pub fn new() -> Self {
+        Self {
+            market: OnceCell::new(),
+        }
+    }
+
+    /// Загрузить состояние рынка
+    fn load_market(&self, market_account: &AccountInfo) -> Result<&Market, AutoBuyerError> {
+        if let Some((market_address, market)) = self.market.get() {
+            if market_address != market_account.key() {
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+            return Ok(market);
+        }
+
+        if market_account.owner() != &constants::OPENBOOK_V2_PROGRAM_ID {
+            return Err(AutoBuyerError::InvalidAccountOwner);
+        }
+
+        let data = market_account
+            .try_borrow_data()
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        if data.len() < 8 || data[..8] != MARKET_DISCRIMINATOR {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let market =
+            Market::deserialize(&mut &data[8..]).map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        Ok(&self
+            .market
+            .get_or_init(|| (*market_account.key(), market))
+            .1)
+    }
+
+    /// Проверить, что переданные аккаунты соответствуют рынку
+    fn validate_pool_accounts(
+        &self,
+        market: &Market,
+        accounts: &[AccountInfo],
+    ) -> Result<(), AutoBuyerError> {
+        if accounts[7].key() != &market.market_authority {
+            msg!("Error: Market authority does not match OpenBook market");
+            return Err(AutoBuyerError::InvalidMarket);
+        }
+
+        if accounts[8].key() != &market.bids
+            || accounts[9].key() != &market.asks
+            || accounts[12].key() != &market.event_heap
+        {
+            msg!("Error: Book accounts do not match OpenBook market");
+            return Err(AutoBuyerError::InvalidMarket);
+        }
+
+        if accounts[10].key() != &market.market_base_vault
+            || accounts[11].key() != &market.market_quote_vault
+        {
+            msg!("Error: Market vaults do not match OpenBook market");
+            return Err(AutoBuyerError::InvalidPoolVault);
+        }
+
+        // Отсутствующий оракул передается как программа OpenBook
+        for (account, oracle) in [
+            (&accounts[13], &market.oracle_a),
+            (&accounts[14], &market.oracle_b),
+        ] {
+            let expected = if oracle == &Pubkey::default() {
+                &constants::OPENBOOK_V2_PROGRAM_ID
+            } else {
+                oracle
+            };
+            if account.key() != expected {
+                msg!("Error: Oracle does not match OpenBook market");
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+        }
+
+        if accounts[15].key() != &constants::TOKEN_PROGRAM_ID
+            || accounts[16].key() != &constants::SYSTEM_PROGRAM_ID
+        {
+            msg!("Error: Invalid token or system program");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if market.base_lot_size <= 0
+            || market.quote_lot_size <= 0
+            || !(0..FEES_SCALE_FACTOR as i64).contains(&market.taker_fee)
+        {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        Ok(())
+    }
+
+    /// Проверить, что рынок доступен для заявок тейкера без подписи администратора
+    fn check_market_status(&self, market: &Market) -> Result<(), AutoBuyerError> {
+        if market.open_orders_admin != Pubkey::default() {
+            msg!("Error: Market requires open orders admin signature");
+            return Err(AutoBuyerError::PoolNotTradable);
+        }
+
+        if market.time_expiry != 0 {
+            let clock = Clock::get().map_err(|_| AutoBuyerError::InvalidParameters)?;
+            if clock.unix_timestamp >= market.time_expiry {
+                msg!("Error: Market expired at {}", market.time_expiry);
+                return Err(AutoBuyerError::PoolNotTradable);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Сторона заявки: true, если входной токен - токен-котировка рынка (покупка базового)
+    fn is_bid(market: &Market, trading_pair: &TradingPair) -> bool {
+        trading_pair.quote_mint == market.quote_mint
+    }
+
+    /// Загрузить ордера стороны книги в порядке лучшей цены
+    ///
+    /// Обходится только дерево ордеров с фиксированной ценой, просроченные ордера пропускаются.
+    fn load_book_orders(
+        book_side: &AccountInfo,
+        best_is_lowest: bool,
+        now: u64,
+    ) -> Result<Vec<BookOrder>, AutoBuyerError> {
+        if book_side.owner() != &constants::OPENBOOK_V2_PROGRAM_ID {
+            return Err(AutoBuyerError::InvalidAccountOwner);
+        }
+
+        let data = book_side
+            .try_borrow_data()
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        Self::read_book_orders(&data, best_is_lowest, now)
+    }
+
+    /// Прочитать ордера из данных аккаунта стороны книги
+    fn read_book_orders(
+        data: &[u8],
+        best_is_lowest: bool,
+        now: u64,
+    ) -> Result<Vec<BookOrder>, AutoBuyerError> {
+        if data.len() < NODES_OFFSET + MAX_ORDERTREE_NODES * NODE_SIZE
+            || data[..8] != BOOK_SIDE_DISCRIMINATOR
+        {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let read_u32 =
+            |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+
+        let root = read_u32(FIXED_ROOT_OFFSET);
+        let leaf_count = read_u32(FIXED_ROOT_OFFSET + 4);
+
+        let mut orders = Vec::new();
+        if leaf_count == 0 {
+            return Ok(orders);
+        }
+
+        // Обход в глубину: дочерний узел с лучшей ценой извлекается первым
+        let mut stack = vec![root];
+        while let Some(index) = stack.pop() {
+            if index as usize >= MAX_ORDERTREE_NODES {
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+            let offset = NODES_OFFSET + index as usize * NODE_SIZE;
+
+            match data[offset] {
+                NODE_TAG_INNER => {
+                    let left = read_u32(offset + 24);
+                    let right = read_u32(offset + 28);
+                    if best_is_lowest {
+                        stack.extend([right, left]);
+                    } else {
+                        stack.extend([left, right]);
+                    }
+                }
+                NODE_TAG_LEAF => {
+                    let time_in_force =
+                        u16::from_le_bytes(data[offset + 2..offset + 4].try_into().unwrap());
+                    let timestamp =
+                        u64::from_le_bytes(data[offset + 64..offset + 72].try_into().unwrap());
+                    if time_in_force != 0 && now >= timestamp + time_in_force as u64 {
+                        continue;
+                    }
+
+                    let key =
+                        u128::from_le_bytes(data[offset + 8..offset + 24].try_into().unwrap());
+                    let quantity =
+                        i64::from_le_bytes(data[offset + 56..offset + 64].try_into().unwrap());
+                    orders.push(BookOrder {
+                        price_lots: (key >> 64) as i64,
+                        quantity,
+                    });
+
+                    if orders.len() >= MATCH_LIMIT as usize {
+                        break;
+                    }
+                }
+                _ => return Err(AutoBuyerError::InvalidParameters),
+            }
+        }
+
+        Ok(orders)
+    }
+
+    /// Свести заявку тейкера с ордерами книги
+    fn match_orders(orders: &[BookOrder], max_base_lots: i64, max_quote_lots: i64) -> BookFill {
+        let mut base_left = max_base_lots;
+        let mut quote_left = max_quote_lots;
+
+        for order in orders {
+            if order.price_lots <= 0 || order.quantity <= 0 {
+                continue;
+            }
+
+            let match_base = order
+                .quantity
+                .min(base_left)
+                .min(quote_left / order.price_lots);
+            if match_base == 0 {
+                break;
+            }
+
+            base_left -= match_base;
+            quote_left -= match_base * order.price_lots;
+        }
+
+        BookFill {
+            base_lots: max_base_lots - base_left,
+            quote_lots: max_quote_lots - quote_left,
+        }
+    }
+
+    /// Комиссия тейкера с округлением вверх
+    fn taker_fee(market: &Market, quote_native: i128) -> i128 {
+        (quote_native * market.taker_fee as i128 + FEES_SCALE_FACTOR - 1) / FEES_SCALE_FACTOR
+    }
+
+    /// Максимальное количество лотов котировки за вычетом комиссии тейкера
+    fn subtract_taker_fees(market: &Market, quote_lots: i64) -> i64 {
+        (quote_lots as i128 * FEES_SCALE_FACTOR / (FEES_SCALE_FACTOR + market.taker_fee as i128))
+            as i64
+    }
+
+    /// Рассчитать исполнение заявки тейкера на `amount_in` входного токена
+    ///
+    /// Возвращает количество выходного токена, комиссию и лучшую цену книги.
+    fn calculate_fill(
+        market: &Market,
+        orders: &[BookOrder],
+        amount_in: u64,
+        is_bid: bool,
+    ) -> Result<(u64, u64, PriceQ64), AutoBuyerError> {
+        let best = orders
+            .first()
+            .ok_or(AutoBuyerError::InsufficientLiquidity)?;
+        let base_lot_size = market.base_lot_size as i128;
+        let quote_lot_size = market.quote_lot_size as i128;
+        let best_quote_native = u64::try_from(best.price_lots as i128 * quote_lot_size)
+            .map_err(|_| AutoBuyerError::MathOverflow)?;
+
+        let (amount_out, fee_amount, spot_price) = if is_bid {
+            let max_quote_lots_including_fees = i64::try_from(amount_in as i128 / quote_lot_size)
+                .map_err(|_| AutoBuyerError::MathOverflow)?;
+            let max_quote_lots = Self::subtract_taker_fees(market, max_quote_lots_including_fees);
+            let fill = Self::match_orders(orders, i64::MAX, max_quote_lots);
+
+            let fee = Self::taker_fee(market, fill.quote_lots as i128 * quote_lot_size);
+            let spot_price = PriceQ64::from_ratio(market.base_lot_size as u64, best_quote_native)
+                .unwrap_or_default();
+            (fill.base_lots as i128 * base_lot_size, fee, spot_price)
+        } else {
+            let max_base_lots = i64::try_from(amount_in as i128 / base_lot_size)
+                .map_err(|_| AutoBuyerError::MathOverflow)?;
+            let fill = Self::match_orders(orders, max_base_lots, i64::MAX);
+
+            let quote_native = fill.quote_lots as i128 * quote_lot_size;
+            let fee = Self::taker_fee(market, quote_native);
+            let spot_price = PriceQ64::from_ratio(best_quote_native, market.base_lot_size as u64)
+                .unwrap_or_default();
+            (quote_native - fee, fee, spot_price)
+        };
+
+        if amount_out <= 0 {
+            return Err(AutoBuyerError::InsufficientLiquidity);
+        }
+
+        Ok((
+            u64::try_from(amount_out).map_err(|_| AutoBuyerError::MathOverflow)?,
+            u64::try_from(fee_amount).map_err(|_| AutoBuyerError::MathOverflow)?,
+            spot_price,
+        ))
+    }
+
+    /// Предельная цена заявки в лотах, при которой исполнение дает не меньше `min_amount_out`
+    fn limit_price_lots(
+        market: &Market,
+        amount_in: u64,
+        min_amount_out: u64,
+        is_bid: bool,
+    ) -> Result<i64, AutoBuyerError> {
+        let base_lot_size = market.base_lot_size as i128;
+        let quote_lot_size = market.quote_lot_size as i128;
+
+        let price_lots = if is_bid {
+            // Без минимального выхода цена покупки не ограничена
+            if min_amount_out == 0 {
+                return Ok(i64::MAX);
+            }
+
+            // Средняя цена не выше суммы котировки без комиссии на минимальный выход
+            let max_quote_lots =
+                Self::subtract_taker_fees(market, (amount_in as i128 / quote_lot_size) as i64);
+            max_quote_lots as i128 * base_lot_size / min_amount_out as i128
+        } else {
+            // Выручка до комиссии, необходимая для получения минимального выхода
+            let fee_factor = (FEES_SCALE_FACTOR + market.taker_fee as i128).max(0) as u128;
+            let min_quote_native =
+                (min_amount_out as u128 * fee_factor).div_ceil(FEES_SCALE_FACTOR as u128);
+            let min_quote_lots = min_quote_native.div_ceil(quote_lot_size as u128);
+            let base_lots = amount_in as u128 / base_lot_size as u128;
+            if base_lots == 0 {
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+            min_quote_lots.div_ceil(base_lots).max(1) as i128
+        };
+
+        if price_lots <= 0 {
+            msg!("Error: Minimum amount out is not reachable at any price");
+            return Err(AutoBuyerError::SlippageTooHigh);
+        }
+
+        i64::try_from(price_lots).map_err(|_| AutoBuyerError::MathOverflow)
+    }
+
+    /// Выставить IOC-заявку тейкера через CPI
+    fn execute_take_order(
+        &self,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        let market = self.load_market(&accounts[6])?;
+        let is_bid = Self::is_bid(market, &swap_params.trading_pair);
+
+        let user_account = &accounts[0];
+        let (source_token_account, destination_token_account) =
+            swap_params.direction.user_token_accounts(accounts);
+        let (user_base_account, user_quote_account) = if is_bid {
+            (destination_token_account, source_token_account)
+        } else {
+            (source_token_account, destination_token_account)
+        };
+        let openbook_program = &accounts[5];
+        let market_account = &accounts[6];
+        let market_authority = &accounts[7];
+        let bids = &accounts[8];
+        let asks = &accounts[9];
+        let market_base_vault = &accounts[10];
+        let market_quote_vault = &accounts[11];
+        let event_heap = &accounts[12];
+        let oracle_a = &accounts[13];
+        let oracle_b = &accounts[14];
+        let token_program = &accounts[15];
+        let system_program = &accounts[16];
+
+        let price_lots = Self::limit_price_lots(
+            market,
+            swap_params.amount_in,
+            swap_params.min_amount_out,
+            is_bid,
+        )?;
+        let (side, max_base_lots, max_quote_lots_including_fees) = if is_bid {
+            (
+                SIDE_BID,
+                i64::MAX,
+                (swap_params.amount_in / market.quote_lot_size as u64) as i64,
+            )
+        } else {
+            (
+                SIDE_ASK,
+                (swap_params.amount_in / market.base_lot_size as u64) as i64,
+                i64::MAX,
+            )
+        };
+
+        let instruction_data = borsh::to_vec(&PlaceTakeOrderInstruction {
+            discriminator: PLACE_TAKE_ORDER_DISCRIMINATOR,
+            side,
+            price_lots,
+            max_base_lots,
+            max_quote_lots_including_fees,
+            order_type: ORDER_TYPE_IMMEDIATE_OR_CANCEL,
+            limit: MATCH_LIMIT,
+        })
+        .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        let instruction = Instruction {
+            program_id: openbook_program.key(),
+            accounts: &[
+                AccountMeta::readonly_signer(user_account.key()),
+                // Пользователь оплачивает штраф за неисполненную заявку
+                AccountMeta::writable_signer(user_account.key()),
+                AccountMeta::writable(market_account.key()),
+                AccountMeta::readonly(market_authority.key()),
+                AccountMeta::writable(bids.key()),
+                AccountMeta::writable(asks.key()),
+                AccountMeta::writable(market_base_vault.key()),
+                AccountMeta::writable(market_quote_vault.key()),
+                AccountMeta::writable(event_heap.key()),
+                AccountMeta::writable(user_base_account.key()),
+                AccountMeta::writable(user_quote_account.key()),
+                AccountMeta::readonly(oracle_a.key()),
+                AccountMeta::readonly(oracle_b.key()),
+                AccountMeta::readonly(token_program.key()),
+                AccountMeta::readonly(system_program.key()),
+                // open_orders_admin не задан: передается программа OpenBook
+                AccountMeta::readonly(openbook_program.key()),
+            ],
+            data: &instruction_data,
+        };
+
+        let account_infos = [
+            user_account,
+            user_account,
+            market_account,
+            market_authority,
+            bids,
+            asks,
+            market_base_vault,
+            market_quote_vault,
+            event_heap,
+            user_base_account,
+            user_quote_account,
+            oracle_a,
+            oracle_b,
+            token_program,
+            system_program,
+            openbook_program,
+        ];
+
+        let balance_before = get_token_balance(destination_token_account)?;
+
+        invoke(&instruction, &account_infos).map_err(|_| AutoBuyerError::CpiError)?;
+
+        verify_received_amount(
+            destination_token_account,
+            balance_before,
+            swap_params.min_amount_out,
+        )
+    }
+}
+
+impl DexInterface for OpenBookV2 {
+    fn accounts_len(&self) -> usize {
+        OPENBOOK_ACCOUNTS_LEN
+    }
+
+    fn find_trading_pair(
+        &self,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> Result<Option<TradingPair>, AutoBuyerError> {
+        if accounts.len() < 7 || accounts[5].key() != &constants::OPENBOOK_V2_PROGRAM_ID {
+            return Ok(None);
+        }
+
+        if accounts.len() < OPENBOOK_ACCOUNTS_LEN {
+            msg!(
+                "Error: Insufficient accounts for OpenBook v2. Expected {}, got {}",
+                OPENBOOK_ACCOUNTS_LEN,
+                accounts.len()
+            );
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let market_account = &accounts[6];
+        let market = match self.load_market(market_account) {
+            Ok(market) => market,
+            Err(_) => return Ok(None),
+        };
+
+        let is_correct_pair = (market.base_mint == *base_mint && market.quote_mint == *quote_mint)
+            || (market.base_mint == *quote_mint && market.quote_mint == *base_mint);
+
+        if !is_correct_pair {
+            return Ok(None);
+        }
+
+        self.validate_pool_accounts(market, accounts)?;
+        self.check_market_status(market)?;
+
+        let pool_config = PoolConfig {
+            pool_address: *market_account.key(),
+            token_a_account: market.market_base_vault,
+            token_b_account: market.market_quote_vault,
+            token_a_mint: market.base_mint,
+            token_b_mint: market.quote_mint,
+            // taker_fee хранится в миллионных долях
+            fee_rate: (market.taker_fee / 100) as u16,
+        };
+
+        Ok(Some(TradingPair {
+            base_mint: *base_mint,
+            quote_mint: *quote_mint,
+            pool_config,
+        }))
+    }
+
+    fn calculate_swap(
+        &self,
+        trading_pair: &TradingPair,
+        amount_in: u64,
+        accounts: &[AccountInfo],
+    ) -> Result<SwapCalculation, AutoBuyerError> {
+        let market = self.load_market(&accounts[6])?;
+        let is_bid = Self::is_bid(market, trading_pair);
+
+        // Покупка базового токена забирает ордера asks по возрастанию цены, продажа - bids
+        let clock = Clock::get().map_err(|_| AutoBuyerError::InvalidParameters)?;
+        let orders = if is_bid {
+            Self::load_book_orders(&accounts[9], true, clock.unix_timestamp as u64)?
+        } else {
+            Self::load_book_orders(&accounts[8], false, clock.unix_timestamp as u64)?
+        };
+
+        let (amount_out, fee_amount, spot_price) =
+            Self::calculate_fill(market, &orders, amount_in, is_bid)?;
+
+        let price_per_unit = PriceQ64::from_ratio(amount_out, amount_in).unwrap_or_default();
+
+        Ok(SwapCalculation {
+            amount_in,
+            amount_out,
+            fee_amount,
+            price_per_unit,
+            price_impact_bps: price_per_unit.impact_bps(spot_price),
+        })
+    }
+
+    fn execute_swap(
+        &self,
+        _program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        if swap_params.mode != SwapMode::ExactIn {
+            return Err(AutoBuyerError::SwapModeNotSupported);
+        }
+
+        msg!(
+            "Executing OpenBook v2 IOC order: {} -> {}",
+            swap_params.amount_in,
+            swap_params.calculation.amount_out
+        );
+
+        let amount_received = self.execute_take_order(accounts, swap_params)?;
+
+        msg!(
+            "OpenBook v2 order completed successfully, received {}",
+            amount_received
+        );
+        Ok(amount_received)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::zeroed_state;
+
+    /// Рынок с заданными размерами лотов и комиссией тейкера
+    fn market(base_lot_size: i64, quote_lot_size: i64, taker_fee: i64) -> Market {
+        let mut market: Market = zeroed_state();
+        market.base_lot_size = base_lot_size;
+        market.quote_lot_size = quote_lot_size;
+        market.taker_fee = taker_fee;
+        market
+    }
+
+    /// Записать внутренний узел дерева
+    fn write_inner(data: &mut [u8], index: usize, left: u32, right: u32) {
+        let offset = NODES_OFFSET + index * NODE_SIZE;
+        data[offset] = NODE_TAG_INNER;
+        data[offset + 24..offset + 28].copy_from_slice(&left.to_le_bytes());
+        data[offset + 28..offset + 32].copy_from_slice(&right.to_le_bytes());
+    }
+
+    /// Записать лист дерева (ордер)
+    fn write_leaf(
+        data: &mut [u8],
+        index: usize,
+        price_lots: i64,
+        quantity: i64,
+        time_in_force: u16,
+        timestamp: u64,
+    ) {
+        let offset = NODES_OFFSET + index * NODE_SIZE;
+        data[offset] = NODE_TAG_LEAF;
+        data[offset + 2..offset + 4].copy_from_slice(&time_in_force.to_le_bytes());
+        let key = ((price_lots as u128) << 64) | index as u128;
+        data[offset + 8..offset + 24].copy_from_slice(&key.to_le_bytes());
+        data[offset + 56..offset + 64].copy_from_slice(&quantity.to_le_bytes());
+        data[offset + 64..offset + 72].copy_from_slice(&timestamp.to_le_bytes());
+    }
+
+    /// Сторона книги из трех ордеров: 100, 105 и 110 лотов котировки
+    fn book_side(expiring_index: Option<usize>) -> Vec<u8> {
+        let mut data = vec![0u8; NODES_OFFSET + MAX_ORDERTREE_NODES * NODE_SIZE];
+        data[..8].copy_from_slice(&BOOK_SIDE_DISCRIMINATOR);
+        data[FIXED_ROOT_OFFSET..FIXED_ROOT_OFFSET + 4].copy_from_slice(&0u32.to_le_bytes());
+        data[FIXED_ROOT_OFFSET + 4..FIXED_ROOT_OFFSET + 8].copy_from_slice(&3u32.to_le_bytes());
+
+        write_inner(&mut data, 0, 1, 2);
+        write_inner(&mut data, 2, 3, 4);
+        for (index, price_lots) in [(1, 100), (3, 105), (4, 110)] {
+            let time_in_force = if expiring_index == Some(index) { 10 } else { 0 };
+            write_leaf(&mut data, index, price_lots, 5, time_in_force, 100);
+        }
+        data
+    }
+
+    fn prices(orders: &[BookOrder]) -> Vec<i64> {
+        orders.iter().map(|order| order.price_lots).collect()
+    }
+
+    #[test]
+    fn book_walk_starts_from_best_price() {
+        let data = book_side(None);
+
+        let asks = OpenBookV2::read_book_orders(&data, true, 0).unwrap();
+        assert_eq!(prices(&asks), [100, 105, 110]);
+
+        let bids = OpenBookV2::read_book_orders(&data, false, 0).unwrap();
+        assert_eq!(prices(&bids), [110, 105, 100]);
+    }
+
+    #[test]
+    fn book_walk_skips_expired_orders() {
+        let data = book_side(Some(3));
+
+        let active = OpenBookV2::read_book_orders(&data, true, 105).unwrap();
+        assert_eq!(prices(&active), [100, 105, 110]);
+
+        let expired = OpenBookV2::read_book_orders(&data, true, 110).unwrap();
+        assert_eq!(prices(&expired), [100, 110]);
+    }
+
+    #[test]
+    fn book_walk_rejects_foreign_data() {
+        let mut data = book_side(None);
+        data[0] ^= 1;
+        assert!(OpenBookV2::read_book_orders(&data, true, 0).is_err());
+    }
+
+    #[test]
+    fn match_orders_walks_levels_until_base_limit() {
+        let orders = [
+            BookOrder {
+                price_lots: 100,
+                quantity: 5,
+            },
+            BookOrder {
+                price_lots: 105,
+                quantity: 10,
+            },
+        ];
+
+        let fill = OpenBookV2::match_orders(&orders, 8, i64::MAX);
+        assert_eq!(fill.base_lots, 8);
+        assert_eq!(fill.quote_lots, 5 * 100 + 3 * 105);
+    }
+
+    #[test]
+    fn match_orders_stops_at_quote_limit() {
+        let orders = [
+            BookOrder {
+                price_lots: 100,
+                quantity: 5,
+            },
+            BookOrder {
+                price_lots: 105,
+                quantity: 10,
+            },
+        ];
+
+        let fill = OpenBookV2::match_orders(&orders, i64::MAX, 700);
+        assert_eq!(fill.base_lots, 6);
+        assert_eq!(fill.quote_lots, 5 * 100 + 105);
+    }
+
+    #[test]
+    fn bid_limit_price_without_minimum_is_unbounded() {
+        let market = market(1_000, 1, 400);
+        assert_eq!(
+            OpenBookV2::limit_price_lots(&market, 1_000_000, 0, true).unwrap(),
+            i64::MAX
+        );
+    }
+
+    #[test]
+    fn bid_limit_price_covers_minimum_output() {
+        // 100 000 лотов котировки на 1 000 000 базового токена: не дороже 100 за лот
+        let market = market(1_000, 1, 0);
+        assert_eq!(
+            OpenBookV2::limit_price_lots(&market, 100_000, 1_000_000, true).unwrap(),
+            100
+        );
+    }
+
+    #[test]
+    fn ask_limit_price_rounds_up() {
+        // 10 лотов базового токена должны принести не меньше 1001 единицы котировки
+        let market = market(1_000, 1, 0);
+        assert_eq!(
+            OpenBookV2::limit_price_lots(&market, 10_000, 1_001, false).unwrap(),
+            101
+        );
+    }
+}
@@ -0,0 +1,840 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::cell::OnceCell;
+
+use crate::{
+    dex::{
+        get_token_balance,
+        types::{SwapMode, SwapParams},
+        verify_received_amount, DexInterface,
+    },
+    error::AutoBuyerError,
+    state::{constants, PoolConfig, PriceQ64, SwapCalculation, TradingPair},
+};
+
+/// Дискриминатор заголовка рынка Phoenix
+const MARKET_HEADER_DISCRIMINANT: u64 = 12610206325545081173;
+
+/// Размер заголовка рынка
+const MARKET_HEADER_SIZE: usize = 576;
+
+/// Смещение параметров FIFO рынка (после заголовка и резерва)
+const FIFO_MARKET_OFFSET: usize = MARKET_HEADER_SIZE + 256;
+
+/// Смещение дерева заявок на покупку
+const BIDS_OFFSET: usize = FIFO_MARKET_OFFSET + 48;
+
+/// Размер заголовка красно-черного дерева и аллокатора узлов
+const TREE_HEADER_SIZE: usize = 32;
+
+/// Размер узла дерева: регистры и пара ключ-значение
+const TREE_NODE_SIZE: usize = 64;
+
+/// Статус активного рынка
+const MARKET_STATUS_ACTIVE: u64 = 1;
+
+/// Тег инструкции Swap
+const SWAP_INSTRUCTION_TAG: u8 = 0;
+
+/// Вариант пакета ордера ImmediateOrCancel
+const ORDER_PACKET_IMMEDIATE_OR_CANCEL: u8 = 2;
+
+/// Сторона ордера Phoenix: покупка базового токена
+const SIDE_BID: u8 = 0;
+
+/// Сторона ордера Phoenix: продажа базового токена
+const SIDE_ASK: u8 = 1;
+
+/// Поведение при сделке с самим собой: отменить встречный ордер
+const SELF_TRADE_CANCEL_PROVIDE: u8 = 1;
+
+/// Максимальное количество ордеров, сводимых одной заявкой
+const MATCH_LIMIT: u64 = 64;
+
+/// PDA журнала событий программы Phoenix (seeds: ["log"])
+const PHOENIX_LOG_AUTHORITY: Pubkey = [
+    97, 168, 97, 115, 124, 201, 1, 140, 31, 126, 69, 145, 243, 168, 100, 198, 200, 161, 77, 108,
+    203, 4, 205, 101, 236, 120, 68, 224, 62, 59, 217, 50,
+];
+
+/// Количество аккаунтов, необходимых для обмена через Phoenix
+const PHOENIX_ACCOUNTS_LEN: usize = 11;
+
+/// Структура для работы с книгой ордеров Phoenix
+///
+/// Котировка учитывает комиссию тейкера рынка; просроченные ордера пропускаются.
+///
+/// Аккаунты (после общих 0-4):
+/// 5. `[]` Программа Phoenix
+/// 6. `[writable]` Рынок
+/// 7. `[]` Log authority
+/// 8. `[writable]` Хранилище базового токена
+/// 9. `[writable]` Хранилище токена-котировки
+/// 10. `[]` Программа токенов
+pub struct Phoenix {
+    /// Заголовок и параметры рынка, загруженные в рамках текущей инструкции
+    market: OnceCell<(Pubkey, MarketHeader, FifoParams)>,
+}
+
+/// Параметры токена рынка
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct TokenParams {
+    pub decimals: u32,
+    pub vault_bump: u32,
+    pub mint_key: Pubkey,
+    pub vault_key: Pubkey,
+}
+
+/// Заголовок рынка Phoenix (до резерва)
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct MarketHeader {
+    pub discriminant: u64,
+    pub status: u64,
+    pub bids_size: u64,
+    pub asks_size: u64,
+    pub num_seats: u64,
+    pub base_params: TokenParams,
+    pub base_lot_size: u64,
+    pub quote_params: TokenParams,
+    pub quote_lot_size: u64,
+    pub tick_size_in_quote_atoms_per_base_unit: u64,
+    pub authority: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub market_sequence_number: u64,
+    pub successor: Pubkey,
+    pub raw_base_units_per_base_unit: u32,
+}
+
+/// Параметры FIFO рынка после заголовка
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct FifoParams {
+    pub base_lots_per_base_unit: u64,
+    pub tick_size_in_quote_lots_per_base_unit: u64,
+    pub order_sequence_number: u64,
+    pub taker_fee_bps: u64,
+    pub collected_quote_lot_fees: u64,
+    pub unclaimed_quote_lot_fees: u64,
+}
+
+/// Ордер книги Phoenix
+#[derive(Debug, Clone, Copy)]
+struct RestingOrder {
+    /// Цена в тиках
+    price_in_ticks: u64,
+    /// Количество в лотах базового токена
+    num_base_lots: u64,
+}
+
+/// Пакет IOC-ордера Phoenix
+#[derive(BorshSerialize)]
+struct ImmediateOrCancelPacket {
+    tag: u8,
+    side: u8,
+    price_in_ticks: Option<u64>,
+    num_base_lots: u64,
+    num_quote_lots: u64,
+    min_base_lots_to_fill: u64,
+    min_quote_lots_to_fill: u64,
+    self_trade_behavior: u8,
+    match_limit: Option<u64>,
+    client_order_id: u128,
+    use_only_deposited_funds: bool,
+    last_valid_slot: Option<u64>,
+    last_valid_unix_timestamp_in_seconds: Option<u64>,
+}
+
+/// Инструкция обмена Phoenix
+#[derive(BorshSerialize)]
+struct PhoenixSwapInstruction {
+    instruction: u8,
+    order_packet: ImmediateOrCancelPacket,
+}
+
+impl Phoenix {
+    /// Создать новый экземпляр Phoenix
+    pub fn new() -> Self {
+        Self {
+            market: OnceCell::new(),
+        }
+    }
+
+    /// Загрузить заголовок и параметры рынка
+    fn load_market(
+        &self,
+        market_account: &AccountInfo,
+    ) -> Result<(&MarketHeader, &FifoParams), AutoBuyerError> {
+        if let Some((market_address, header, params)) = self.market.get() {
+            if market_address != market_account.key() {
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+            return Ok((header, params));
+        }
+
+        if market_account.owner() != &constants::PHOENIX_PROGRAM_ID {
+            return Err(AutoBuyerError::InvalidAccountOwner);
+        }
+
+        let data = market_account
+            .try_borrow_data()
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        if data.len() < BIDS_OFFSET {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let header = MarketHeader::deserialize(&mut &data[..])
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+        if header.discriminant != MARKET_HEADER_DISCRIMINANT {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let params = FifoParams::deserialize(&mut &data[FIFO_MARKET_OFFSET..])
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        let (_, header, params) = self
+            .market
+            .get_or_init(|| (*market_account.key(), header, params));
+        Ok((header, params))
+    }
+
+    /// Проверить, что переданные аккаунты соответствуют рынку
+    fn validate_pool_accounts(
+        &self,
+        header: &MarketHeader,
+        params: &FifoParams,
+        accounts: &[AccountInfo],
+    ) -> Result<(), AutoBuyerError> {
+        if accounts[7].key() != &PHOENIX_LOG_AUTHORITY {
+            msg!("Error: Invalid Phoenix log authority");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if accounts[8].key() != &header.base_params.vault_key
+            || accounts[9].key() != &header.quote_params.vault_key
+        {
+            msg!("Error: Market vaults do not match Phoenix market");
+            return Err(AutoBuyerError::InvalidPoolVault);
+        }
+
+        if accounts[10].key() != &constants::TOKEN_PROGRAM_ID {
+            msg!("Error: Invalid token program");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if header.base_lot_size == 0
+            || header.quote_lot_size == 0
+            || params.base_lots_per_base_unit == 0
+            || params.tick_size_in_quote_lots_per_base_unit == 0
+            || params.taker_fee_bps >= constants::BASIS_POINTS as u64
+        {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        Ok(())
+    }
+
+    /// Проверить, что рынок принимает заявки тейкера
+    fn check_market_status(&self, header: &MarketHeader) -> Result<(), AutoBuyerError> {
+        if header.status != MARKET_STATUS_ACTIVE {
+            msg!(
+                "Error: Market status {} does not allow swaps",
+                header.status
+            );
+            return Err(AutoBuyerError::PoolNotTradable);
+        }
+
+        Ok(())
+    }
+
+    /// Сторона заявки: true, если входной токен - токен-котировка рынка (покупка базового)
+    fn is_bid(header: &MarketHeader, trading_pair: &TradingPair) -> bool {
+        trading_pair.quote_mint == header.quote_params.mint_key
+    }
+
+    /// Загрузить действующие ордера стороны книги в порядке лучшей цены
+    fn load_book_orders(
+        &self,
+        header: &MarketHeader,
+        market_account: &AccountInfo,
+        is_bid: bool,
+    ) -> Result<Vec<RestingOrder>, AutoBuyerError> {
+        let data = market_account
+            .try_borrow_data()
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        // Заявка на покупку сводится с asks, на продажу - с bids
+        let (tree_offset, capacity) = if is_bid {
+            (
+                BIDS_OFFSET + TREE_HEADER_SIZE + header.bids_size as usize * TREE_NODE_SIZE,
+                header.asks_size as usize,
+            )
+        } else {
+            (BIDS_OFFSET, header.bids_size as usize)
+        };
+
+        let clock = Clock::get().map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        Self::read_book_orders(
+            &data,
+            tree_offset,
+            capacity,
+            clock.slot,
+            clock.unix_timestamp as u64,
+        )
+    }
+
+    /// Прочитать действующие ордера дерева стороны книги в порядке лучшей цены
+    ///
+    /// Ключ `FIFOOrderId` упорядочивает обе стороны от лучшей цены (asks по возрастанию цены,
+    /// bids по убыванию), поэтому красно-черное дерево обходится слева направо. Обход
+    /// прекращается после `MATCH_LIMIT` действующих ордеров.
+    fn read_book_orders(
+        data: &[u8],
+        tree_offset: usize,
+        capacity: usize,
+        slot: u64,
+        unix_timestamp: u64,
+    ) -> Result<Vec<RestingOrder>, AutoBuyerError> {
+        if data.len() < tree_offset + TREE_HEADER_SIZE + capacity * TREE_NODE_SIZE {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let read_u32 =
+            |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+        let read_u64 =
+            |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        // Индексы узлов начинаются с 1, 0 - отсутствие узла
+        let node_offset =
+            |index: u32| tree_offset + TREE_HEADER_SIZE + (index as usize - 1) * TREE_NODE_SIZE;
+
+        let mut orders = Vec::with_capacity(MATCH_LIMIT as usize);
+        let mut stack = Vec::new();
+        let mut current = read_u32(tree_offset);
+        let mut visited = 0;
+
+        while orders.len() < MATCH_LIMIT as usize {
+            // Спуск к лучшему (левому) ключу поддерева
+            while current != 0 {
+                if current as usize > capacity || stack.len() >= capacity {
+                    return Err(AutoBuyerError::InvalidParameters);
+                }
+                stack.push(current);
+                current = read_u32(node_offset(current));
+            }
+
+            let Some(index) = stack.pop() else {
+                break;
+            };
+            visited += 1;
+            if visited > capacity {
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+
+            let offset = node_offset(index);
+            current = read_u32(offset + 4);
+
+            let last_valid_slot = read_u64(offset + 48);
+            let last_valid_timestamp = read_u64(offset + 56);
+            let expired = (last_valid_slot != 0 && slot > last_valid_slot)
+                || (last_valid_timestamp != 0 && unix_timestamp > last_valid_timestamp);
+            if expired {
+                continue;
+            }
+
+            orders.push(RestingOrder {
+                price_in_ticks: read_u64(offset + 16),
+                num_base_lots: read_u64(offset + 40),
+            });
+        }
+
+        Ok(orders)
+    }
+
+    /// Комиссия тейкера в лотах котировки с округлением вверх
+    fn taker_fee_lots(params: &FifoParams, quote_lots: u64) -> u64 {
+        (quote_lots as u128 * params.taker_fee_bps as u128)
+            .div_ceil(constants::BASIS_POINTS as u128) as u64
+    }
+
+    /// Рассчитать исполнение IOC-заявки на `amount_in` входного токена
+    ///
+    /// Возвращает количество выходного токена, комиссию и лучшую цену книги.
+    fn calculate_fill(
+        header: &MarketHeader,
+        params: &FifoParams,
+        orders: &[RestingOrder],
+        amount_in: u64,
+        is_bid: bool,
+    ) -> Result<(u64, u64, PriceQ64), AutoBuyerError> {
+        let best = orders
+            .first()
+            .ok_or(AutoBuyerError::InsufficientLiquidity)?;
+        let base_lots_per_base_unit = params.base_lots_per_base_unit as u128;
+        let tick_size = params.tick_size_in_quote_lots_per_base_unit as u128;
+
+        // Цена в "скорректированных" лотах котировки (умноженных на base_lots_per_base_unit)
+        let best_price_quote_atoms =
+            best.price_in_ticks as u128 * tick_size * header.quote_lot_size as u128;
+        let best_price_base_atoms = base_lots_per_base_unit * header.base_lot_size as u128;
+
+        let (amount_out, fee_amount, spot_price) = if is_bid {
+            let quote_lots_budget = amount_in / header.quote_lot_size;
+            let quote_lots_after_fee = quote_lots_budget as u128 * constants::BASIS_POINTS as u128
+                / (constants::BASIS_POINTS as u128 + params.taker_fee_bps as u128);
+            let mut adjusted_left = quote_lots_after_fee * base_lots_per_base_unit;
+            let mut base_lots: u128 = 0;
+
+            for order in orders {
+                let lot_cost = order.price_in_ticks as u128 * tick_size;
+                if lot_cost == 0 {
+                    continue;
+                }
+                let matched = (order.num_base_lots as u128).min(adjusted_left / lot_cost);
+                if matched == 0 {
+                    break;
+                }
+                adjusted_left -= matched * lot_cost;
+                base_lots += matched;
+            }
+
+            let quote_lots_used = (quote_lots_after_fee * base_lots_per_base_unit - adjusted_left)
+                .div_ceil(base_lots_per_base_unit) as u64;
+            let fee = Self::taker_fee_lots(params, quote_lots_used) as u128
+                * header.quote_lot_size as u128;
+            let spot_price = PriceQ64::from_ratio(
+                u64::try_from(best_price_base_atoms).map_err(|_| AutoBuyerError::MathOverflow)?,
+                u64::try_from(best_price_quote_atoms).map_err(|_| AutoBuyerError::MathOverflow)?,
+            )
+            .unwrap_or_default();
+            (base_lots * header.base_lot_size as u128, fee, spot_price)
+        } else {
+            let mut base_lots_left = (amount_in / header.base_lot_size) as u128;
+            let mut adjusted_quote: u128 = 0;
+
+            for order in orders {
+                let matched = (order.num_base_lots as u128).min(base_lots_left);
+                if matched == 0 {
+                    break;
+                }
+                base_lots_left -= matched;
+                adjusted_quote += matched * order.price_in_ticks as u128 * tick_size;
+            }
+
+            let quote_lots = (adjusted_quote / base_lots_per_base_unit) as u64;
+            let fee_lots = Self::taker_fee_lots(params, quote_lots);
+            let spot_price = PriceQ64::from_ratio(
+                u64::try_from(best_price_quote_atoms).map_err(|_| AutoBuyerError::MathOverflow)?,
+                u64::try_from(best_price_base_atoms).map_err(|_| AutoBuyerError::MathOverflow)?,
+            )
+            .unwrap_or_default();
+            (
+                (quote_lots - fee_lots) as u128 * header.quote_lot_size as u128,
+                fee_lots as u128 * header.quote_lot_size as u128,
+                spot_price,
+            )
+        };
+
+        if amount_out == 0 {
+            return Err(AutoBuyerError::InsufficientLiquidity);
+        }
+
+        Ok((
+            u64::try_from(amount_out).map_err(|_| AutoBuyerError::MathOverflow)?,
+            u64::try_from(fee_amount).map_err(|_| AutoBuyerError::MathOverflow)?,
+            spot_price,
+        ))
+    }
+
+    /// Выполнить IOC-обмен через CPI
+    fn execute_phoenix_swap(
+        &self,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        let (header, _) = self.load_market(&accounts[6])?;
+        let is_bid = Self::is_bid(header, &swap_params.trading_pair);
+
+        let user_account = &accounts[0];
+        let (source_token_account, destination_token_account) =
+            swap_params.direction.user_token_accounts(accounts);
+        let (user_base_account, user_quote_account) = if is_bid {
+            (destination_token_account, source_token_account)
+        } else {
+            (source_token_account, destination_token_account)
+        };
+        let phoenix_program = &accounts[5];
+        let market_account = &accounts[6];
+        let log_authority = &accounts[7];
+        let base_vault = &accounts[8];
+        let quote_vault = &accounts[9];
+        let token_program = &accounts[10];
+
+        // Минимальное исполнение задается в лотах выходного токена
+        let order_packet = if is_bid {
+            ImmediateOrCancelPacket {
+                side: SIDE_BID,
+                num_base_lots: 0,
+                num_quote_lots: swap_params.amount_in / header.quote_lot_size,
+                min_base_lots_to_fill: swap_params.min_amount_out / header.base_lot_size,
+                min_quote_lots_to_fill: 0,
+                ..Self::empty_packet()
+            }
+        } else {
+            ImmediateOrCancelPacket {
+                side: SIDE_ASK,
+                num_base_lots: swap_params.amount_in / header.base_lot_size,
+                num_quote_lots: 0,
+                min_base_lots_to_fill: 0,
+                min_quote_lots_to_fill: swap_params.min_amount_out / header.quote_lot_size,
+                ..Self::empty_packet()
+            }
+        };
+
+        let instruction_data = borsh::to_vec(&PhoenixSwapInstruction {
+            instruction: SWAP_INSTRUCTION_TAG,
+            order_packet,
+        })
+        .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        let instruction = Instruction {
+            program_id: phoenix_program.key(),
+            accounts: &[
+                AccountMeta::readonly(phoenix_program.key()),
+                AccountMeta::readonly(log_authority.key()),
+                AccountMeta::writable(market_account.key()),
+                AccountMeta::readonly_signer(user_account.key()),
+                AccountMeta::writable(user_base_account.key()),
+                AccountMeta::writable(user_quote_account.key()),
+                AccountMeta::writable(base_vault.key()),
+                AccountMeta::writable(quote_vault.key()),
+                AccountMeta::readonly(token_program.key()),
+            ],
+            data: &instruction_data,
+        };
+
+        let account_infos = [
+            phoenix_program,
+            log_authority,
+            market_account,
+            user_account,
+            user_base_account,
+            user_quote_account,
+            base_vault,
+            quote_vault,
+            token_program,
+        ];
+
+        let balance_before = get_token_balance(destination_token_account)?;
+
+        invoke(&instruction, &account_infos).map_err(|_| AutoBuyerError::CpiError)?;
+
+        verify_received_amount(
+            destination_token_account,
+            balance_before,
+            swap_params.min_amount_out,
+        )
+    }
+
+    /// Пакет IOC-ордера без цены и ограничений по времени
+    fn empty_packet() -> ImmediateOrCancelPacket {
+        ImmediateOrCancelPacket {
+            tag: ORDER_PACKET_IMMEDIATE_OR_CANCEL,
+            side: SIDE_BID,
+            price_in_ticks: None,
+            num_base_lots: 0,
+            num_quote_lots: 0,
+            min_base_lots_to_fill: 0,
+            min_quote_lots_to_fill: 0,
+            self_trade_behavior: SELF_TRADE_CANCEL_PROVIDE,
+            match_limit: Some(MATCH_LIMIT),
+            client_order_id: 0,
+            use_only_deposited_funds: false,
+            last_valid_slot: None,
+            last_valid_unix_timestamp_in_seconds: None,
+        }
+    }
+}
+
+impl DexInterface for Phoenix {
+    fn accounts_len(&self) -> usize {
+        PHOENIX_ACCOUNTS_LEN
+    }
+
+    fn find_trading_pair(
+        &self,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> Result<Option<TradingPair>, AutoBuyerError> {
+        if accounts.len() < 7 || accounts[5].key() != &constants::PHOENIX_PROGRAM_ID {
+            return Ok(None);
+        }
+
+        if accounts.len() < PHOENIX_ACCOUNTS_LEN {
+            msg!(
+                "Error: Insufficient accounts for Phoenix. Expected {}, got {}",
+                PHOENIX_ACCOUNTS_LEN,
+                accounts.len()
+            );
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let market_account = &accounts[6];
+        let (header, params) = match self.load_market(market_account) {
+            Ok(market) => market,
+            Err(_) => return Ok(None),
+        };
+
+        let market_base_mint = &header.base_params.mint_key;
+        let market_quote_mint = &header.quote_params.mint_key;
+        let is_correct_pair = (market_base_mint == base_mint && market_quote_mint == quote_mint)
+            || (market_base_mint == quote_mint && market_quote_mint == base_mint);
+
+        if !is_correct_pair {
+            return Ok(None);
+        }
+
+        self.validate_pool_accounts(header, params, accounts)?;
+        self.check_market_status(header)?;
+
+        let pool_config = PoolConfig {
+            pool_address: *market_account.key(),
+            token_a_account: header.base_params.vault_key,
+            token_b_account: header.quote_params.vault_key,
+            token_a_mint: *market_base_mint,
+            token_b_mint: *market_quote_mint,
+            fee_rate: params.taker_fee_bps as u16,
+        };
+
+        Ok(Some(TradingPair {
+            base_mint: *base_mint,
+            quote_mint: *quote_mint,
+            pool_config,
+        }))
+    }
+
+    fn calculate_swap(
+        &self,
+        trading_pair: &TradingPair,
+        amount_in: u64,
+        accounts: &[AccountInfo],
+    ) -> Result<SwapCalculation, AutoBuyerError> {
+        let market_account = &accounts[6];
+        let (header, params) = self.load_market(market_account)?;
+        let is_bid = Self::is_bid(header, trading_pair);
+
+        let orders = self.load_book_orders(header, market_account, is_bid)?;
+        let (amount_out, fee_amount, spot_price) =
+            Self::calculate_fill(header, params, &orders, amount_in, is_bid)?;
+
+        let price_per_unit = PriceQ64::from_ratio(amount_out, amount_in).unwrap_or_default();
+
+        Ok(SwapCalculation {
+            amount_in,
+            amount_out,
+            fee_amount,
+            price_per_unit,
+            price_impact_bps: price_per_unit.impact_bps(spot_price),
+        })
+    }
+
+    fn execute_swap(
+        &self,
+        _program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        if swap_params.mode != SwapMode::ExactIn {
+            return Err(AutoBuyerError::SwapModeNotSupported);
+        }
+
+        msg!(
+            "Executing Phoenix swap: {} -> {}",
+            swap_params.amount_in,
+            swap_params.calculation.amount_out
+        );
+
+        let amount_received = self.execute_phoenix_swap(accounts, swap_params)?;
+
+        msg!(
+            "Phoenix swap completed successfully, received {}",
+            amount_received
+        );
+        Ok(amount_received)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::zeroed_state;
+
+    /// Рынок с единичными лотами
+    fn header() -> MarketHeader {
+        let mut header: MarketHeader = zeroed_state();
+        header.base_lot_size = 1;
+        header.quote_lot_size = 1;
+        header
+    }
+
+    /// Параметры FIFO с единичным тиком и заданной комиссией тейкера
+    fn params(taker_fee_bps: u64) -> FifoParams {
+        FifoParams {
+            base_lots_per_base_unit: 1,
+            tick_size_in_quote_lots_per_base_unit: 1,
+            order_sequence_number: 0,
+            taker_fee_bps,
+            collected_quote_lot_fees: 0,
+            unclaimed_quote_lot_fees: 0,
+        }
+    }
+
+    /// Смещение узла дерева с индексом `index` (с 1)
+    fn node_offset(index: u32) -> usize {
+        TREE_HEADER_SIZE + (index as usize - 1) * TREE_NODE_SIZE
+    }
+
+    /// Построить сбалансированное поддерево из узлов `first..first + count`
+    ///
+    /// Узлы пронумерованы в порядке ключей, возвращается индекс корня.
+    fn link(data: &mut [u8], first: u32, count: u32) -> u32 {
+        if count == 0 {
+            return 0;
+        }
+        let middle = first + count / 2;
+        let left = link(data, first, middle - first);
+        let right = link(data, middle + 1, first + count - middle - 1);
+        let offset = node_offset(middle);
+        data[offset..offset + 4].copy_from_slice(&left.to_le_bytes());
+        data[offset + 4..offset + 8].copy_from_slice(&right.to_le_bytes());
+        middle
+    }
+
+    /// Дерево стороны книги с ордерами по 5 лотов и ценами в порядке ключей `FIFOOrderId`
+    ///
+    /// Для asks цены идут по возрастанию, для bids - по убыванию, номера ордеров bids
+    /// хранятся инвертированными, как в Phoenix.
+    fn tree(prices: &[u64], is_bid: bool, expiring: Option<u64>) -> Vec<u8> {
+        let mut data = vec![0u8; TREE_HEADER_SIZE + prices.len() * TREE_NODE_SIZE];
+        for (position, price) in prices.iter().enumerate() {
+            let offset = node_offset(position as u32 + 1);
+            let sequence_number = if is_bid {
+                !(position as u64)
+            } else {
+                position as u64
+            };
+            data[offset + 16..offset + 24].copy_from_slice(&price.to_le_bytes());
+            data[offset + 24..offset + 32].copy_from_slice(&sequence_number.to_le_bytes());
+            data[offset + 40..offset + 48].copy_from_slice(&5u64.to_le_bytes());
+            if expiring == Some(*price) {
+                data[offset + 48..offset + 56].copy_from_slice(&10u64.to_le_bytes());
+            }
+        }
+        let root = link(&mut data, 1, prices.len() as u32);
+        data[..4].copy_from_slice(&root.to_le_bytes());
+        data
+    }
+
+    fn prices(orders: &[RestingOrder]) -> Vec<u64> {
+        orders.iter().map(|order| order.price_in_ticks).collect()
+    }
+
+    fn order(price_in_ticks: u64, num_base_lots: u64) -> RestingOrder {
+        RestingOrder {
+            price_in_ticks,
+            num_base_lots,
+        }
+    }
+
+    #[test]
+    fn book_walk_starts_from_best_price() {
+        let asks = tree(&[100, 105, 108, 110, 120], false, None);
+        let orders = Phoenix::read_book_orders(&asks, 0, 5, 0, 0).unwrap();
+        assert_eq!(prices(&orders), [100, 105, 108, 110, 120]);
+
+        // Лучший bid - крайний левый узел дерева
+        let bids = tree(&[120, 110, 108, 105, 100], true, None);
+        let orders = Phoenix::read_book_orders(&bids, 0, 5, 0, 0).unwrap();
+        assert_eq!(prices(&orders), [120, 110, 108, 105, 100]);
+    }
+
+    #[test]
+    fn book_walk_stops_at_match_limit() {
+        let ascending: Vec<u64> = (1..=200).collect();
+        let asks = tree(&ascending, false, None);
+        let orders = Phoenix::read_book_orders(&asks, 0, 200, 0, 0).unwrap();
+        assert_eq!(prices(&orders), ascending[..MATCH_LIMIT as usize]);
+
+        let descending: Vec<u64> = (1..=200).rev().collect();
+        let bids = tree(&descending, true, None);
+        let orders = Phoenix::read_book_orders(&bids, 0, 200, 0, 0).unwrap();
+        assert_eq!(prices(&orders), descending[..MATCH_LIMIT as usize]);
+    }
+
+    #[test]
+    fn book_walk_skips_expired_orders() {
+        let data = tree(&[100, 105, 110], false, Some(105));
+
+        let live = Phoenix::read_book_orders(&data, 0, 3, 10, 0).unwrap();
+        assert_eq!(prices(&live), [100, 105, 110]);
+
+        let expired = Phoenix::read_book_orders(&data, 0, 3, 11, 0).unwrap();
+        assert_eq!(prices(&expired), [100, 110]);
+    }
+
+    #[test]
+    fn book_walk_rejects_corrupted_tree() {
+        let mut data = tree(&[100, 105, 110], false, None);
+        // Левый потомок корня ссылается на сам корень
+        let root = u32::from_le_bytes(data[..4].try_into().unwrap());
+        let offset = node_offset(root);
+        data[offset..offset + 4].copy_from_slice(&root.to_le_bytes());
+        assert!(Phoenix::read_book_orders(&data, 0, 3, 0, 0).is_err());
+
+        let short = tree(&[100, 105, 110], false, None);
+        assert!(Phoenix::read_book_orders(&short, 0, 4, 0, 0).is_err());
+    }
+
+    #[test]
+    fn bid_fill_keeps_fee_within_budget() {
+        let asks = [order(100, 5), order(105, 5)];
+
+        let (amount_out, fee, _) =
+            Phoenix::calculate_fill(&header(), &params(0), &asks, 1_000, true).unwrap();
+        assert_eq!((amount_out, fee), (9, 0));
+
+        // 999 лотов после комиссии: 5 * 100 + 4 * 105 = 920, комиссия 1
+        let (amount_out, fee, _) =
+            Phoenix::calculate_fill(&header(), &params(10), &asks, 1_000, true).unwrap();
+        assert_eq!((amount_out, fee), (9, 1));
+    }
+
+    #[test]
+    fn ask_fill_deducts_taker_fee() {
+        let bids = [order(110, 5), order(105, 5)];
+
+        // 5 * 110 + 2 * 105 = 760, комиссия округляется вверх до 1
+        let (amount_out, fee, _) =
+            Phoenix::calculate_fill(&header(), &params(10), &bids, 7, false).unwrap();
+        assert_eq!((amount_out, fee), (759, 1));
+    }
+
+    #[test]
+    fn empty_book_has_no_liquidity() {
+        assert!(matches!(
+            Phoenix::calculate_fill(&header(), &params(0), &[], 1_000, true),
+            Err(AutoBuyerError::InsufficientLiquidity)
+        ));
+    }
+}
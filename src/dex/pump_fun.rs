@@ -0,0 +1,552 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::cell::OnceCell;
+use spl_token::{solana_program::program_pack::Pack, state::Account as TokenAccount};
+
+use crate::{
+    dex::{
+        get_token_balance, load_anchor_account,
+        token_2022::is_token_program,
+        types::{SwapDirection, SwapMode, SwapParams},
+        verify_received_amount, DexInterface,
+    },
+    error::AutoBuyerError,
+    state::{constants, PoolConfig, PriceQ64, SwapCalculation, TradingPair},
+};
+
+/// Дискриминатор аккаунта BondingCurve
+const BONDING_CURVE_DISCRIMINATOR: [u8; 8] = [23, 183, 248, 55, 96, 216, 172, 96];
+
+/// Дискриминатор аккаунта Global
+const GLOBAL_DISCRIMINATOR: [u8; 8] = [167, 232, 232, 177, 200, 108, 114, 127];
+
+/// Дискриминатор инструкции buy
+const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+
+/// Количество аккаунтов, необходимых для покупки через кривую pump.fun
+const PUMP_FUN_ACCOUNTS_LEN: usize = 18;
+
+/// Структура для работы с кривой связывания pump.fun
+///
+/// Покупка оплачивается лампортами пользователя (аккаунт 0), токен-котировка - WSOL.
+///
+/// Аккаунты (после общих 0-4):
+/// 5. `[]` Программа pump.fun
+/// 6. `[writable]` BondingCurve
+/// 7. `[writable]` Токеновый аккаунт кривой (associated bonding curve)
+/// 8. `[]` Global
+/// 9. `[writable]` Получатель комиссии
+/// 10. `[writable]` Хранилище создателя (creator vault)
+/// 11. `[]` Системная программа
+/// 12. `[]` Программа токенов
+/// 13. `[]` Event authority
+/// 14. `[writable]` Глобальный счетчик объема (global volume accumulator)
+/// 15. `[writable]` Счетчик объема пользователя (user volume accumulator)
+/// 16. `[]` Конфигурация комиссий (fee config)
+/// 17. `[]` Программа комиссий pump.fun
+pub struct PumpFun {
+    /// Состояние кривой и глобальная конфигурация, загруженные в рамках текущей инструкции
+    curve: OnceCell<(Pubkey, BondingCurve, PumpGlobal)>,
+}
+
+/// Состояние кривой связывания
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct BondingCurve {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub token_total_supply: u64,
+    pub complete: bool,
+    pub creator: Pubkey,
+}
+
+/// Глобальная конфигурация pump.fun (до списка получателей комиссии)
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct PumpGlobal {
+    pub initialized: bool,
+    pub authority: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub fee_basis_points: u64,
+    pub withdraw_authority: Pubkey,
+    pub enable_migrate: bool,
+    pub pool_migration_fee: u64,
+    pub creator_fee_basis_points: u64,
+    pub fee_recipients: [Pubkey; 7],
+}
+
+/// Инструкция покупки pump.fun
+#[derive(BorshSerialize)]
+struct PumpFunBuyInstruction {
+    discriminator: [u8; 8],
+    /// Количество токена для покупки
+    amount: u64,
+    /// Максимальная стоимость в лампортах с учетом комиссий
+    max_sol_cost: u64,
+}
+
+impl PumpFun {
+    /// Создать новый экземпляр pump.fun
+    pub fn new() -> Self {
+        Self {
+            curve: OnceCell::new(),
+        }
+    }
+
+    /// Загрузить состояние кривой и глобальную конфигурацию
+    fn load_curve(
+        &self,
+        curve_account: &AccountInfo,
+        global_account: &AccountInfo,
+    ) -> Result<(&BondingCurve, &PumpGlobal), AutoBuyerError> {
+        if let Some((curve_address, curve, global)) = self.curve.get() {
+            if curve_address != curve_account.key() {
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+            return Ok((curve, global));
+        }
+
+        let curve: BondingCurve = load_anchor_account(
+            curve_account,
+            &constants::PUMP_FUN_PROGRAM_ID,
+            &BONDING_CURVE_DISCRIMINATOR,
+        )?;
+        let global: PumpGlobal = load_anchor_account(
+            global_account,
+            &constants::PUMP_FUN_PROGRAM_ID,
+            &GLOBAL_DISCRIMINATOR,
+        )?;
+
+        let (_, curve, global) = self
+            .curve
+            .get_or_init(|| (*curve_account.key(), curve, global));
+        Ok((curve, global))
+    }
+
+    /// Проверить, что переданные аккаунты соответствуют кривой
+    fn validate_pool_accounts(
+        &self,
+        global: &PumpGlobal,
+        base_mint: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> Result<(), AutoBuyerError> {
+        if !global.initialized {
+            msg!("Error: Pump.fun global config is not initialized");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        // Токеновый аккаунт кривой должен принадлежать кривой и хранить целевой токен
+        let curve_token_account = {
+            let data = accounts[7]
+                .try_borrow_data()
+                .map_err(|_| AutoBuyerError::InvalidParameters)?;
+            TokenAccount::unpack(
+                data.get(..TokenAccount::LEN)
+                    .ok_or(AutoBuyerError::InvalidPoolVault)?,
+            )
+            .map_err(|_| AutoBuyerError::InvalidPoolVault)?
+        };
+        if curve_token_account.owner.to_bytes() != *accounts[6].key()
+            || curve_token_account.mint.to_bytes() != *base_mint
+        {
+            msg!("Error: Token account does not belong to bonding curve");
+            return Err(AutoBuyerError::InvalidPoolVault);
+        }
+
+        let fee_recipient = accounts[9].key();
+        if fee_recipient != &global.fee_recipient
+            && !global.fee_recipients.iter().any(|key| key == fee_recipient)
+        {
+            msg!("Error: Invalid pump.fun fee recipient");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if accounts[11].key() != &constants::SYSTEM_PROGRAM_ID
+            || !is_token_program(accounts[12].key())
+            || accounts[7].owner() != accounts[12].key()
+        {
+            msg!("Error: Invalid system or token program");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if accounts[17].key() != &constants::PUMP_FEE_PROGRAM_ID {
+            msg!("Error: Invalid pump.fun fee program");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if global
+            .fee_basis_points
+            .saturating_add(global.creator_fee_basis_points)
+            >= constants::BASIS_POINTS as u64
+        {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        Ok(())
+    }
+
+    /// Ставки комиссий протокола и создателя в базисных пунктах
+    fn fee_basis_points(curve: &BondingCurve, global: &PumpGlobal) -> (u64, u64) {
+        let creator_fee_basis_points = if curve.creator != Pubkey::default() {
+            global.creator_fee_basis_points
+        } else {
+            0
+        };
+        (global.fee_basis_points, creator_fee_basis_points)
+    }
+
+    /// Комиссия с округлением вверх
+    fn ceil_fee(amount: u64, fee_basis_points: u64) -> u64 {
+        (amount as u128 * fee_basis_points as u128).div_ceil(constants::BASIS_POINTS as u128) as u64
+    }
+
+    /// Стоимость покупки `amount` токенов по кривой без комиссий (как в pump.fun)
+    fn buy_cost(curve: &BondingCurve, amount: u64) -> Result<u64, AutoBuyerError> {
+        let remaining = curve
+            .virtual_token_reserves
+            .checked_sub(amount)
+            .filter(|remaining| *remaining > 0)
+            .ok_or(AutoBuyerError::InsufficientLiquidity)?;
+
+        let cost = amount as u128 * curve.virtual_sol_reserves as u128 / remaining as u128 + 1;
+        u64::try_from(cost).map_err(|_| AutoBuyerError::MathOverflow)
+    }
+
+    /// Рассчитать количество токенов, покупаемых на `sol_amount` лампортов с учетом комиссий
+    ///
+    /// Возвращает количество токенов, стоимость по кривой и суммарную комиссию.
+    fn calculate_buy(
+        curve: &BondingCurve,
+        global: &PumpGlobal,
+        sol_amount: u64,
+    ) -> Result<(u64, u64, u64), AutoBuyerError> {
+        if curve.virtual_token_reserves == 0 || curve.virtual_sol_reserves == 0 {
+            return Err(AutoBuyerError::InsufficientLiquidity);
+        }
+
+        let (fee_basis_points, creator_fee_basis_points) = Self::fee_basis_points(curve, global);
+        let total_fee_basis_points = fee_basis_points + creator_fee_basis_points;
+
+        // Сумма, остающаяся на кривую после комиссий, с запасом на округление стоимости
+        let net_sol = (sol_amount as u128 * constants::BASIS_POINTS as u128
+            / (constants::BASIS_POINTS as u128 + total_fee_basis_points as u128))
+            .saturating_sub(1);
+
+        let tokens_out = net_sol * curve.virtual_token_reserves as u128
+            / (curve.virtual_sol_reserves as u128 + net_sol);
+        let mut tokens_out = (tokens_out as u64).min(curve.real_token_reserves);
+
+        // Комиссии округляются вверх по отдельности, поэтому стоимость может превысить сумму
+        // на несколько лампортов: количество уменьшается, пока стоимость не уложится в сумму
+        loop {
+            if tokens_out == 0 {
+                return Err(AutoBuyerError::InsufficientLiquidity);
+            }
+
+            let sol_cost = Self::buy_cost(curve, tokens_out)?;
+            let fee_amount = Self::ceil_fee(sol_cost, fee_basis_points)
+                + Self::ceil_fee(sol_cost, creator_fee_basis_points);
+
+            let excess = match (sol_cost + fee_amount).checked_sub(sol_amount) {
+                Some(excess) if excess > 0 => excess,
+                _ => return Ok((tokens_out, sol_cost, fee_amount)),
+            };
+
+            // Шаг по спотовой цене не меньше необходимого: цена растет вдоль кривой
+            let step = (excess as u128 * curve.virtual_token_reserves as u128
+                / curve.virtual_sol_reserves as u128)
+                .max(1);
+            tokens_out = tokens_out.saturating_sub(step.min(u64::MAX as u128) as u64);
+        }
+    }
+
+    /// Выполнить покупку через CPI
+    fn execute_buy(
+        &self,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        let user_account = &accounts[0];
+        let destination_token_account = &accounts[2];
+        let mint = &accounts[3];
+        let pump_program = &accounts[5];
+        let bonding_curve = &accounts[6];
+        let associated_bonding_curve = &accounts[7];
+        let global = &accounts[8];
+        let fee_recipient = &accounts[9];
+        let creator_vault = &accounts[10];
+        let system_program = &accounts[11];
+        let token_program = &accounts[12];
+        let event_authority = &accounts[13];
+        let global_volume_accumulator = &accounts[14];
+        let user_volume_accumulator = &accounts[15];
+        let fee_config = &accounts[16];
+        let fee_program = &accounts[17];
+
+        // Покупка ограничена суммой `amount_in`, списываемой с лампортов пользователя
+        let available_lamports = *user_account
+            .try_borrow_lamports()
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+        if available_lamports < swap_params.amount_in {
+            msg!(
+                "Error: Insufficient lamports. Required: {}, available: {}",
+                swap_params.amount_in,
+                available_lamports
+            );
+            return Err(AutoBuyerError::InsufficientFunds);
+        }
+
+        let instruction_data = borsh::to_vec(&PumpFunBuyInstruction {
+            discriminator: BUY_DISCRIMINATOR,
+            amount: swap_params.calculation.amount_out,
+            max_sol_cost: swap_params.amount_in,
+        })
+        .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        let instruction = Instruction {
+            program_id: pump_program.key(),
+            accounts: &[
+                AccountMeta::readonly(global.key()),
+                AccountMeta::writable(fee_recipient.key()),
+                AccountMeta::readonly(mint.key()),
+                AccountMeta::writable(bonding_curve.key()),
+                AccountMeta::writable(associated_bonding_curve.key()),
+                AccountMeta::writable(destination_token_account.key()),
+                AccountMeta::writable_signer(user_account.key()),
+                AccountMeta::readonly(system_program.key()),
+                AccountMeta::readonly(token_program.key()),
+                AccountMeta::writable(creator_vault.key()),
+                AccountMeta::readonly(event_authority.key()),
+                AccountMeta::readonly(pump_program.key()),
+                AccountMeta::writable(global_volume_accumulator.key()),
+                AccountMeta::writable(user_volume_accumulator.key()),
+                AccountMeta::readonly(fee_config.key()),
+                AccountMeta::readonly(fee_program.key()),
+            ],
+            data: &instruction_data,
+        };
+
+        let account_infos = [
+            global,
+            fee_recipient,
+            mint,
+            bonding_curve,
+            associated_bonding_curve,
+            destination_token_account,
+            user_account,
+            system_program,
+            token_program,
+            creator_vault,
+            event_authority,
+            pump_program,
+            global_volume_accumulator,
+            user_volume_accumulator,
+            fee_config,
+            fee_program,
+        ];
+
+        let balance_before = get_token_balance(destination_token_account)?;
+
+        invoke(&instruction, &account_infos).map_err(|_| AutoBuyerError::CpiError)?;
+
+        verify_received_amount(
+            destination_token_account,
+            balance_before,
+            swap_params.min_amount_out,
+        )
+    }
+}
+
+impl DexInterface for PumpFun {
+    fn accounts_len(&self) -> usize {
+        PUMP_FUN_ACCOUNTS_LEN
+    }
+
+    fn spends_lamports(&self) -> bool {
+        true
+    }
+
+    fn find_trading_pair(
+        &self,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> Result<Option<TradingPair>, AutoBuyerError> {
+        if accounts.len() < 7 || accounts[5].key() != &constants::PUMP_FUN_PROGRAM_ID {
+            return Ok(None);
+        }
+
+        if accounts.len() < PUMP_FUN_ACCOUNTS_LEN {
+            msg!(
+                "Error: Insufficient accounts for pump.fun. Expected {}, got {}",
+                PUMP_FUN_ACCOUNTS_LEN,
+                accounts.len()
+            );
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        // Кривая принимает только SOL: продажа и покупка за другой токен не поддерживаются
+        if quote_mint != &constants::WSOL_MINT {
+            msg!("Error: Pump.fun bonding curve supports only buys for SOL");
+            return Err(AutoBuyerError::SwapModeNotSupported);
+        }
+
+        let curve_account = &accounts[6];
+        let (curve, global) = match self.load_curve(curve_account, &accounts[8]) {
+            Ok(curve) => curve,
+            Err(_) => return Ok(None),
+        };
+
+        self.validate_pool_accounts(global, base_mint, accounts)?;
+
+        if curve.complete {
+            msg!("Error: Bonding curve is complete, token has migrated to an AMM pool");
+            return Err(AutoBuyerError::PoolNotTradable);
+        }
+
+        let (fee_basis_points, creator_fee_basis_points) = Self::fee_basis_points(curve, global);
+
+        let pool_config = PoolConfig {
+            pool_address: *curve_account.key(),
+            token_a_account: *accounts[7].key(),
+            token_b_account: *curve_account.key(),
+            token_a_mint: *base_mint,
+            token_b_mint: constants::WSOL_MINT,
+            fee_rate: (fee_basis_points + creator_fee_basis_points) as u16,
+        };
+
+        Ok(Some(TradingPair {
+            base_mint: *base_mint,
+            quote_mint: *quote_mint,
+            pool_config,
+        }))
+    }
+
+    fn calculate_swap(
+        &self,
+        _trading_pair: &TradingPair,
+        amount_in: u64,
+        accounts: &[AccountInfo],
+    ) -> Result<SwapCalculation, AutoBuyerError> {
+        let (curve, global) = self.load_curve(&accounts[6], &accounts[8])?;
+
+        let (amount_out, sol_cost, fee_amount) = Self::calculate_buy(curve, global, amount_in)?;
+        let sol_spent = sol_cost + fee_amount;
+
+        let price_per_unit = PriceQ64::from_ratio(amount_out, sol_spent).unwrap_or_default();
+        let spot_price =
+            PriceQ64::from_ratio(curve.virtual_token_reserves, curve.virtual_sol_reserves)
+                .unwrap_or_default();
+
+        Ok(SwapCalculation {
+            amount_in: sol_spent,
+            amount_out,
+            fee_amount,
+            price_per_unit,
+            price_impact_bps: price_per_unit.impact_bps(spot_price),
+        })
+    }
+
+    fn execute_swap(
+        &self,
+        _program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        if swap_params.mode != SwapMode::ExactIn || swap_params.direction != SwapDirection::Buy {
+            return Err(AutoBuyerError::SwapModeNotSupported);
+        }
+
+        msg!(
+            "Executing pump.fun buy: {} -> {}",
+            swap_params.amount_in,
+            swap_params.calculation.amount_out
+        );
+
+        let amount_received = self.execute_buy(accounts, swap_params)?;
+
+        msg!(
+            "Pump.fun buy completed successfully, received {}",
+            amount_received
+        );
+        Ok(amount_received)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Кривая в начальном состоянии pump.fun
+    fn curve(creator: Pubkey) -> BondingCurve {
+        BondingCurve {
+            virtual_token_reserves: 1_073_000_000_000_000,
+            virtual_sol_reserves: 30_000_000_000,
+            real_token_reserves: 793_100_000_000_000,
+            real_sol_reserves: 0,
+            token_total_supply: 1_000_000_000_000_000,
+            complete: false,
+            creator,
+        }
+    }
+
+    fn global(fee_basis_points: u64, creator_fee_basis_points: u64) -> PumpGlobal {
+        PumpGlobal {
+            initialized: true,
+            authority: Pubkey::default(),
+            fee_recipient: Pubkey::default(),
+            initial_virtual_token_reserves: 1_073_000_000_000_000,
+            initial_virtual_sol_reserves: 30_000_000_000,
+            initial_real_token_reserves: 793_100_000_000_000,
+            token_total_supply: 1_000_000_000_000_000,
+            fee_basis_points,
+            withdraw_authority: Pubkey::default(),
+            enable_migrate: true,
+            pool_migration_fee: 0,
+            creator_fee_basis_points,
+            fee_recipients: [Pubkey::default(); 7],
+        }
+    }
+
+    #[test]
+    fn buy_cost_never_exceeds_sol_amount() {
+        let curve = curve([1; 32]);
+        let global = global(95, 5);
+
+        for sol_amount in [1_000_u64, 12_345, 999_999, 100_000_000, 3_333_333_333] {
+            let (tokens_out, sol_cost, fee_amount) =
+                PumpFun::calculate_buy(&curve, &global, sol_amount).unwrap();
+            assert!(tokens_out > 0);
+            assert!(
+                sol_cost + fee_amount <= sol_amount,
+                "cost {} exceeds {}",
+                sol_cost + fee_amount,
+                sol_amount
+            );
+        }
+    }
+
+    #[test]
+    fn creator_fee_applies_only_with_creator() {
+        let global = global(95, 5);
+
+        assert_eq!(PumpFun::fee_basis_points(&curve([1; 32]), &global), (95, 5));
+        assert_eq!(
+            PumpFun::fee_basis_points(&curve(Pubkey::default()), &global),
+            (95, 0)
+        );
+    }
+}
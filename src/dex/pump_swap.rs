@@ -0,0 +1,815 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::cell::OnceCell;
+
+use crate::{
+    dex::{
+        get_token_balance, load_anchor_account,
+        token_2022::is_token_program,
+        types::{SwapMode, SwapParams},
+        verify_received_amount, DexInterface,
+    },
+    error::AutoBuyerError,
+    state::{constants, PoolConfig, PriceQ64, SwapCalculation, TradingPair},
+};
+
+/// Дискриминатор аккаунта Pool
+const POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+
+/// Дискриминатор аккаунта GlobalConfig
+const GLOBAL_CONFIG_DISCRIMINATOR: [u8; 8] = [149, 8, 156, 202, 160, 252, 176, 217];
+
+/// Дискриминатор инструкции buy
+const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+
+/// Дискриминатор инструкции sell
+const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+/// Флаг глобальной конфигурации, запрещающий покупки
+const DISABLE_BUY_FLAG: u8 = 1 << 3;
+
+/// Флаг глобальной конфигурации, запрещающий продажи
+const DISABLE_SELL_FLAG: u8 = 1 << 4;
+
+/// Количество аккаунтов, необходимых для обмена через PumpSwap
+const PUMP_SWAP_ACCOUNTS_LEN: usize = 23;
+
+/// Количество аккаунтов инструкции buy PumpSwap
+const BUY_INSTRUCTION_ACCOUNTS_LEN: usize = 23;
+
+/// Количество аккаунтов инструкции sell PumpSwap (без счетчиков объема)
+const SELL_INSTRUCTION_ACCOUNTS_LEN: usize = 21;
+
+/// Структура для работы с AMM PumpSwap
+///
+/// Сюда переходят токены, завершившие кривую связывания pump.fun. Комиссии LP,
+/// протокола и создателя монеты взимаются с токена-котировки пула в обоих направлениях.
+///
+/// Аккаунты (после общих 0-4):
+/// 5. `[]` Программа PumpSwap
+/// 6. `[]` Pool
+/// 7. `[]` GlobalConfig
+/// 8. `[writable]` Хранилище базового токена пула
+/// 9. `[writable]` Хранилище токена-котировки пула
+/// 10. `[]` Получатель комиссии протокола
+/// 11. `[writable]` Токеновый аккаунт получателя комиссии протокола
+/// 12. `[]` Программа базового токена
+/// 13. `[]` Программа токена-котировки
+/// 14. `[]` Системная программа
+/// 15. `[]` Программа ассоциированных токеновых аккаунтов
+/// 16. `[]` Event authority
+/// 17. `[writable]` Токеновый аккаунт хранилища создателя монеты
+/// 18. `[]` Authority хранилища создателя монеты
+/// 19. `[writable]` Глобальный счетчик объема (только для buy)
+/// 20. `[writable]` Счетчик объема пользователя (только для buy)
+/// 21. `[]` Конфигурация комиссий (fee config)
+/// 22. `[]` Программа комиссий pump.fun
+pub struct PumpSwap {
+    /// Состояние пула и глобальная конфигурация, загруженные в рамках текущей инструкции
+    pool: OnceCell<(Pubkey, PumpSwapPool, GlobalConfig)>,
+}
+
+/// Состояние пула PumpSwap
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct PumpSwapPool {
+    pub pool_bump: u8,
+    pub index: u16,
+    pub creator: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub pool_base_token_account: Pubkey,
+    pub pool_quote_token_account: Pubkey,
+    pub lp_supply: u64,
+    pub coin_creator: Pubkey,
+}
+
+/// Глобальная конфигурация PumpSwap
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct GlobalConfig {
+    pub admin: Pubkey,
+    pub lp_fee_basis_points: u64,
+    pub protocol_fee_basis_points: u64,
+    pub disable_flags: u8,
+    pub protocol_fee_recipients: [Pubkey; 8],
+    pub coin_creator_fee_basis_points: u64,
+}
+
+/// Ставки комиссий пула в базисных пунктах
+#[derive(Debug, Clone, Copy)]
+struct FeeBasisPoints {
+    lp: u64,
+    protocol: u64,
+    coin_creator: u64,
+}
+
+impl FeeBasisPoints {
+    /// Суммарная ставка комиссий
+    fn total(&self) -> u64 {
+        self.lp + self.protocol + self.coin_creator
+    }
+
+    /// Сумма комиссий с `amount`, каждая округляется вверх
+    fn fees_on(&self, amount: u64) -> u64 {
+        PumpSwap::ceil_fee(amount, self.lp)
+            + PumpSwap::ceil_fee(amount, self.protocol)
+            + PumpSwap::ceil_fee(amount, self.coin_creator)
+    }
+}
+
+/// Инструкция buy/sell PumpSwap
+#[derive(BorshSerialize)]
+struct PumpSwapInstruction {
+    discriminator: [u8; 8],
+    /// `base_amount_out` для buy, `base_amount_in` для sell
+    base_amount: u64,
+    /// `max_quote_amount_in` для buy, `min_quote_amount_out` для sell
+    quote_amount: u64,
+}
+
+impl PumpSwap {
+    /// Создать новый экземпляр PumpSwap
+    pub fn new() -> Self {
+        Self {
+            pool: OnceCell::new(),
+        }
+    }
+
+    /// Загрузить состояние пула и глобальную конфигурацию
+    fn load_pool(
+        &self,
+        pool_account: &AccountInfo,
+        global_config_account: &AccountInfo,
+    ) -> Result<(&PumpSwapPool, &GlobalConfig), AutoBuyerError> {
+        if let Some((pool_address, pool, global_config)) = self.pool.get() {
+            if pool_address != pool_account.key() {
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+            return Ok((pool, global_config));
+        }
+
+        let pool: PumpSwapPool = load_anchor_account(
+            pool_account,
+            &constants::PUMP_SWAP_PROGRAM_ID,
+            &POOL_DISCRIMINATOR,
+        )?;
+        let global_config: GlobalConfig = load_anchor_account(
+            global_config_account,
+            &constants::PUMP_SWAP_PROGRAM_ID,
+            &GLOBAL_CONFIG_DISCRIMINATOR,
+        )?;
+
+        let (_, pool, global_config) = self
+            .pool
+            .get_or_init(|| (*pool_account.key(), pool, global_config));
+        Ok((pool, global_config))
+    }
+
+    /// Проверить, что переданные аккаунты соответствуют пулу и глобальной конфигурации
+    fn validate_pool_accounts(
+        &self,
+        pool: &PumpSwapPool,
+        global_config: &GlobalConfig,
+        accounts: &[AccountInfo],
+    ) -> Result<(), AutoBuyerError> {
+        if accounts[8].key() != &pool.pool_base_token_account
+            || accounts[9].key() != &pool.pool_quote_token_account
+        {
+            msg!("Error: Pool token accounts do not match PumpSwap pool");
+            return Err(AutoBuyerError::InvalidPoolVault);
+        }
+
+        let protocol_fee_recipient = accounts[10].key();
+        if protocol_fee_recipient == &Pubkey::default()
+            || !global_config
+                .protocol_fee_recipients
+                .iter()
+                .any(|key| key == protocol_fee_recipient)
+        {
+            msg!("Error: Invalid PumpSwap protocol fee recipient");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let (base_mint, quote_mint) = Self::mint_accounts(pool, accounts);
+        if accounts[12].key() != base_mint.owner()
+            || accounts[13].key() != quote_mint.owner()
+            || !is_token_program(accounts[12].key())
+            || !is_token_program(accounts[13].key())
+        {
+            msg!("Error: Invalid token program for PumpSwap pool");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if accounts[14].key() != &constants::SYSTEM_PROGRAM_ID
+            || accounts[15].key() != &constants::ASSOCIATED_TOKEN_PROGRAM_ID
+        {
+            msg!("Error: Invalid system or associated token program");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if accounts[22].key() != &constants::PUMP_FEE_PROGRAM_ID {
+            msg!("Error: Invalid PumpSwap fee program");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if Self::fee_basis_points(pool, global_config).total() >= constants::BASIS_POINTS as u64 {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        Ok(())
+    }
+
+    /// Проверить, что глобальная конфигурация разрешает обмен в нужном направлении
+    fn check_pool_status(
+        &self,
+        global_config: &GlobalConfig,
+        is_buy: bool,
+    ) -> Result<(), AutoBuyerError> {
+        let disable_flag = if is_buy {
+            DISABLE_BUY_FLAG
+        } else {
+            DISABLE_SELL_FLAG
+        };
+
+        if global_config.disable_flags & disable_flag != 0 {
+            msg!(
+                "Error: PumpSwap disable flags {} do not allow this swap",
+                global_config.disable_flags
+            );
+            return Err(AutoBuyerError::PoolNotTradable);
+        }
+
+        Ok(())
+    }
+
+    /// Направление обмена: true, если входной токен - токен-котировка пула (инструкция buy)
+    fn is_buy(pool: &PumpSwapPool, trading_pair: &TradingPair) -> bool {
+        trading_pair.quote_mint == pool.quote_mint
+    }
+
+    /// Минты базового токена и токена-котировки пула из общих аккаунтов 3-4
+    fn mint_accounts<'a>(
+        pool: &PumpSwapPool,
+        accounts: &'a [AccountInfo],
+    ) -> (&'a AccountInfo, &'a AccountInfo) {
+        if accounts[3].key() == &pool.base_mint {
+            (&accounts[3], &accounts[4])
+        } else {
+            (&accounts[4], &accounts[3])
+        }
+    }
+
+    /// Ставки комиссий LP, протокола и создателя монеты
+    fn fee_basis_points(pool: &PumpSwapPool, global_config: &GlobalConfig) -> FeeBasisPoints {
+        let coin_creator = if pool.coin_creator != Pubkey::default() {
+            global_config.coin_creator_fee_basis_points
+        } else {
+            0
+        };
+
+        FeeBasisPoints {
+            lp: global_config.lp_fee_basis_points,
+            protocol: global_config.protocol_fee_basis_points,
+            coin_creator,
+        }
+    }
+
+    /// Комиссия с округлением вверх
+    fn ceil_fee(amount: u64, fee_basis_points: u64) -> u64 {
+        (amount as u128 * fee_basis_points as u128).div_ceil(constants::BASIS_POINTS as u128) as u64
+    }
+
+    /// Стоимость покупки `base_amount_out` в токене-котировке без комиссий (как в PumpSwap)
+    fn buy_quote_cost(
+        base_reserve: u64,
+        quote_reserve: u64,
+        base_amount_out: u64,
+    ) -> Result<u64, AutoBuyerError> {
+        let remaining = base_reserve
+            .checked_sub(base_amount_out)
+            .filter(|remaining| *remaining > 0)
+            .ok_or(AutoBuyerError::InsufficientLiquidity)?;
+
+        let cost = (quote_reserve as u128 * base_amount_out as u128).div_ceil(remaining as u128);
+        u64::try_from(cost).map_err(|_| AutoBuyerError::MathOverflow)
+    }
+
+    /// Рассчитать покупку базового токена на `quote_amount_in` с учетом комиссий
+    ///
+    /// Возвращает количество базового токена, полную стоимость и комиссии.
+    fn calculate_buy(
+        fees: &FeeBasisPoints,
+        base_reserve: u64,
+        quote_reserve: u64,
+        quote_amount_in: u64,
+    ) -> Result<(u64, u64, u64), AutoBuyerError> {
+        // Стоимость без комиссий не больше `quote_amount_in / (1 + fees)`. Комиссии
+        // округляются вверх по отдельности, поэтому она уменьшается, пока стоимость с
+        // комиссиями не уложится в сумму (не более нескольких шагов)
+        let mut max_quote_cost = u64::try_from(
+            quote_amount_in as u128 * constants::BASIS_POINTS as u128
+                / (constants::BASIS_POINTS as u128 + fees.total() as u128),
+        )
+        .map_err(|_| AutoBuyerError::MathOverflow)?;
+        while max_quote_cost + fees.fees_on(max_quote_cost) > quote_amount_in {
+            max_quote_cost -= 1;
+        }
+
+        // Наибольший выход, для которого ceil(Q·b / (B - b)) не превышает эту стоимость
+        let base_amount_out = u64::try_from(
+            max_quote_cost as u128 * base_reserve as u128
+                / (quote_reserve as u128 + max_quote_cost as u128),
+        )
+        .map_err(|_| AutoBuyerError::MathOverflow)?;
+
+        if base_amount_out == 0 {
+            return Err(AutoBuyerError::InsufficientLiquidity);
+        }
+
+        let quote_cost = Self::buy_quote_cost(base_reserve, quote_reserve, base_amount_out)?;
+        let fee_amount = fees.fees_on(quote_cost);
+
+        Ok((base_amount_out, quote_cost + fee_amount, fee_amount))
+    }
+
+    /// Рассчитать продажу `base_amount_in` базового токена с учетом комиссий
+    ///
+    /// Возвращает количество токена-котировки после комиссий и сами комиссии.
+    fn calculate_sell(
+        fees: &FeeBasisPoints,
+        base_reserve: u64,
+        quote_reserve: u64,
+        base_amount_in: u64,
+    ) -> Result<(u64, u64), AutoBuyerError> {
+        let quote_amount_out = u64::try_from(
+            quote_reserve as u128 * base_amount_in as u128
+                / (base_reserve as u128 + base_amount_in as u128),
+        )
+        .map_err(|_| AutoBuyerError::MathOverflow)?;
+
+        let fee_amount = fees.fees_on(quote_amount_out);
+        let amount_out = quote_amount_out
+            .checked_sub(fee_amount)
+            .filter(|amount_out| *amount_out > 0)
+            .ok_or(AutoBuyerError::InsufficientLiquidity)?;
+
+        Ok((amount_out, fee_amount))
+    }
+
+    /// Аккаунты инструкции buy/sell в порядке PumpSwap с признаком writable
+    ///
+    /// На входе аккаунты в порядке описания структуры, где 0-4 уже приведены к виду
+    /// (пользователь, минт базового токена, минт котировки, токеновые аккаунты пользователя
+    /// для базового токена и котировки). Возвращает упорядоченные аккаунты и их число в
+    /// инструкции: sell не принимает счетчики объема, поэтому они переносятся в конец.
+    fn instruction_accounts<T: Copy>(
+        accounts: [T; PUMP_SWAP_ACCOUNTS_LEN],
+        is_buy: bool,
+    ) -> ([(T, bool); PUMP_SWAP_ACCOUNTS_LEN], usize) {
+        let [user, base_mint, quote_mint, user_base_account, user_quote_account, pump_swap_program, pool_account, global_config, pool_base_token_account, pool_quote_token_account, protocol_fee_recipient, protocol_fee_recipient_token_account, base_token_program, quote_token_program, system_program, associated_token_program, event_authority, coin_creator_vault_ata, coin_creator_vault_authority, global_volume_accumulator, user_volume_accumulator, fee_config, fee_program] =
+            accounts;
+
+        // В buy счетчики объема идут перед аккаунтами комиссий
+        let (tail, accounts_count) = if is_buy {
+            (
+                [
+                    (global_volume_accumulator, true),
+                    (user_volume_accumulator, true),
+                    (fee_config, false),
+                    (fee_program, false),
+                ],
+                BUY_INSTRUCTION_ACCOUNTS_LEN,
+            )
+        } else {
+            (
+                [
+                    (fee_config, false),
+                    (fee_program, false),
+                    (global_volume_accumulator, true),
+                    (user_volume_accumulator, true),
+                ],
+                SELL_INSTRUCTION_ACCOUNTS_LEN,
+            )
+        };
+
+        let ordered_accounts = [
+            (pool_account, false),
+            (user, true),
+            (global_config, false),
+            (base_mint, false),
+            (quote_mint, false),
+            (user_base_account, true),
+            (user_quote_account, true),
+            (pool_base_token_account, true),
+            (pool_quote_token_account, true),
+            (protocol_fee_recipient, false),
+            (protocol_fee_recipient_token_account, true),
+            (base_token_program, false),
+            (quote_token_program, false),
+            (system_program, false),
+            (associated_token_program, false),
+            (event_authority, false),
+            (pump_swap_program, false),
+            (coin_creator_vault_ata, true),
+            (coin_creator_vault_authority, false),
+            tail[0],
+            tail[1],
+            tail[2],
+            tail[3],
+        ];
+        (ordered_accounts, accounts_count)
+    }
+
+    /// Выполнить buy или sell через CPI
+    fn execute_pump_swap(
+        &self,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        let (pool, _) = self.load_pool(&accounts[6], &accounts[7])?;
+        let is_buy = Self::is_buy(pool, &swap_params.trading_pair);
+
+        let (source_token_account, destination_token_account) =
+            swap_params.direction.user_token_accounts(accounts);
+        let (user_base_account, user_quote_account) = if is_buy {
+            (destination_token_account, source_token_account)
+        } else {
+            (source_token_account, destination_token_account)
+        };
+        let (base_mint, quote_mint) = Self::mint_accounts(pool, accounts);
+        let pump_swap_program = &accounts[5];
+
+        // Покупка задается точным выходом базового токена с лимитом стоимости `amount_in`
+        let instruction_data = if is_buy {
+            borsh::to_vec(&PumpSwapInstruction {
+                discriminator: BUY_DISCRIMINATOR,
+                base_amount: swap_params.calculation.amount_out,
+                quote_amount: swap_params.amount_in,
+            })
+        } else {
+            borsh::to_vec(&PumpSwapInstruction {
+                discriminator: SELL_DISCRIMINATOR,
+                base_amount: swap_params.amount_in,
+                quote_amount: swap_params.min_amount_out,
+            })
+        }
+        .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        let mut swap_accounts: [&AccountInfo; PUMP_SWAP_ACCOUNTS_LEN] =
+            core::array::from_fn(|i| &accounts[i]);
+        swap_accounts[1..5].copy_from_slice(&[
+            base_mint,
+            quote_mint,
+            user_base_account,
+            user_quote_account,
+        ]);
+        let (ordered_accounts, accounts_count) = Self::instruction_accounts(swap_accounts, is_buy);
+
+        // Подписывает инструкцию только пользователь, второй аккаунт
+        let account_metas: [AccountMeta; PUMP_SWAP_ACCOUNTS_LEN] = core::array::from_fn(|i| {
+            let (account, is_writable) = ordered_accounts[i];
+            AccountMeta::new(account.key(), is_writable, i == 1)
+        });
+
+        let instruction = Instruction {
+            program_id: pump_swap_program.key(),
+            accounts: &account_metas[..accounts_count],
+            data: &instruction_data,
+        };
+
+        let account_infos = ordered_accounts.map(|(account, _)| account);
+
+        let balance_before = get_token_balance(destination_token_account)?;
+
+        if is_buy {
+            invoke(&instruction, &account_infos)
+        } else {
+            let sell_account_infos: &[&AccountInfo; SELL_INSTRUCTION_ACCOUNTS_LEN] = account_infos
+                [..SELL_INSTRUCTION_ACCOUNTS_LEN]
+                .try_into()
+                .map_err(|_| AutoBuyerError::InvalidParameters)?;
+            invoke(&instruction, sell_account_infos)
+        }
+        .map_err(|_| AutoBuyerError::CpiError)?;
+
+        verify_received_amount(
+            destination_token_account,
+            balance_before,
+            swap_params.min_amount_out,
+        )
+    }
+}
+
+impl DexInterface for PumpSwap {
+    fn accounts_len(&self) -> usize {
+        PUMP_SWAP_ACCOUNTS_LEN
+    }
+
+    fn find_trading_pair(
+        &self,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> Result<Option<TradingPair>, AutoBuyerError> {
+        if accounts.len() < 7 || accounts[5].key() != &constants::PUMP_SWAP_PROGRAM_ID {
+            return Ok(None);
+        }
+
+        if accounts.len() < PUMP_SWAP_ACCOUNTS_LEN {
+            msg!(
+                "Error: Insufficient accounts for PumpSwap. Expected {}, got {}",
+                PUMP_SWAP_ACCOUNTS_LEN,
+                accounts.len()
+            );
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let pool_account = &accounts[6];
+        let (pool, global_config) = match self.load_pool(pool_account, &accounts[7]) {
+            Ok(pool) => pool,
+            Err(_) => return Ok(None),
+        };
+
+        let is_correct_pair = (pool.base_mint == *base_mint && pool.quote_mint == *quote_mint)
+            || (pool.base_mint == *quote_mint && pool.quote_mint == *base_mint);
+
+        if !is_correct_pair {
+            return Ok(None);
+        }
+
+        self.validate_pool_accounts(pool, global_config, accounts)?;
+        self.check_pool_status(global_config, quote_mint == &pool.quote_mint)?;
+
+        let pool_config = PoolConfig {
+            pool_address: *pool_account.key(),
+            token_a_account: pool.pool_base_token_account,
+            token_b_account: pool.pool_quote_token_account,
+            token_a_mint: pool.base_mint,
+            token_b_mint: pool.quote_mint,
+            fee_rate: Self::fee_basis_points(pool, global_config).total() as u16,
+        };
+
+        Ok(Some(TradingPair {
+            base_mint: *base_mint,
+            quote_mint: *quote_mint,
+            pool_config,
+        }))
+    }
+
+    fn calculate_swap(
+        &self,
+        trading_pair: &TradingPair,
+        amount_in: u64,
+        accounts: &[AccountInfo],
+    ) -> Result<SwapCalculation, AutoBuyerError> {
+        let (pool, global_config) = self.load_pool(&accounts[6], &accounts[7])?;
+        let fees = Self::fee_basis_points(pool, global_config);
+
+        let base_reserve = get_token_balance(&accounts[8])?;
+        let quote_reserve = get_token_balance(&accounts[9])?;
+        if base_reserve == 0 || quote_reserve == 0 {
+            return Err(AutoBuyerError::InsufficientLiquidity);
+        }
+
+        let (amount_in, amount_out, fee_amount, spot_price) = if Self::is_buy(pool, trading_pair) {
+            let (amount_out, quote_spent, fee_amount) =
+                Self::calculate_buy(&fees, base_reserve, quote_reserve, amount_in)?;
+            let spot_price = PriceQ64::from_ratio(base_reserve, quote_reserve).unwrap_or_default();
+            (quote_spent, amount_out, fee_amount, spot_price)
+        } else {
+            let (amount_out, fee_amount) =
+                Self::calculate_sell(&fees, base_reserve, quote_reserve, amount_in)?;
+            let spot_price = PriceQ64::from_ratio(quote_reserve, base_reserve).unwrap_or_default();
+            (amount_in, amount_out, fee_amount, spot_price)
+        };
+
+        let price_per_unit = PriceQ64::from_ratio(amount_out, amount_in).unwrap_or_default();
+
+        Ok(SwapCalculation {
+            amount_in,
+            amount_out,
+            fee_amount,
+            price_per_unit,
+            price_impact_bps: price_per_unit.impact_bps(spot_price),
+        })
+    }
+
+    fn execute_swap(
+        &self,
+        _program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        if swap_params.mode != SwapMode::ExactIn {
+            return Err(AutoBuyerError::SwapModeNotSupported);
+        }
+
+        msg!(
+            "Executing PumpSwap swap: {} -> {}",
+            swap_params.amount_in,
+            swap_params.calculation.amount_out
+        );
+
+        let amount_received = self.execute_pump_swap(accounts, swap_params)?;
+
+        msg!(
+            "PumpSwap swap completed successfully, received {}",
+            amount_received
+        );
+        Ok(amount_received)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::zeroed_state;
+
+    /// Резервы пула: 200 млн базового токена (6 знаков) и 100 SOL
+    const BASE_RESERVE: u64 = 200_000_000_000_000;
+    const QUOTE_RESERVE: u64 = 100_000_000_000;
+
+    /// Комиссии LP и протокола с комиссией создателя монеты и без нее
+    const WITH_CREATOR_FEE: FeeBasisPoints = FeeBasisPoints {
+        lp: 20,
+        protocol: 5,
+        coin_creator: 5,
+    };
+    const WITHOUT_CREATOR_FEE: FeeBasisPoints = FeeBasisPoints {
+        lp: 20,
+        protocol: 5,
+        coin_creator: 0,
+    };
+
+    /// Укладывается ли покупка `base_amount_out` в `quote_amount_in` по правилам PumpSwap
+    fn buy_fits(fees: &FeeBasisPoints, quote_amount_in: u64, base_amount_out: u64) -> bool {
+        let cost = PumpSwap::buy_quote_cost(BASE_RESERVE, QUOTE_RESERVE, base_amount_out).unwrap();
+        cost + fees.fees_on(cost) <= quote_amount_in
+    }
+
+    #[test]
+    fn coin_creator_fee_applies_only_with_creator() {
+        let mut pool: PumpSwapPool = zeroed_state();
+        let mut global_config: GlobalConfig = zeroed_state();
+        global_config.lp_fee_basis_points = 20;
+        global_config.protocol_fee_basis_points = 5;
+        global_config.coin_creator_fee_basis_points = 5;
+
+        assert_eq!(
+            PumpSwap::fee_basis_points(&pool, &global_config).total(),
+            WITHOUT_CREATOR_FEE.total()
+        );
+
+        pool.coin_creator = [7; 32];
+        assert_eq!(
+            PumpSwap::fee_basis_points(&pool, &global_config).total(),
+            WITH_CREATOR_FEE.total()
+        );
+    }
+
+    #[test]
+    fn buy_matches_reference_vectors() {
+        // (комиссии, сумма, выход, полная стоимость, комиссии)
+        let vectors = [
+            (
+                WITH_CREATOR_FEE,
+                1_000_000_000,
+                1_974_333_660_269,
+                1_000_000_000,
+                2_991_028,
+            ),
+            (
+                WITH_CREATOR_FEE,
+                12_345_679,
+                24_614_472_265,
+                12_345_679,
+                36_928,
+            ),
+            (WITH_CREATOR_FEE, 7, 7_999, 7, 3),
+            (
+                WITHOUT_CREATOR_FEE,
+                1_000_000_000,
+                1_975_308_639_202,
+                1_000_000_000,
+                2_493_767,
+            ),
+            (
+                WITHOUT_CREATOR_FEE,
+                12_345_679,
+                24_626_749_242,
+                12_345_679,
+                30_788,
+            ),
+            (WITHOUT_CREATOR_FEE, 7, 9_999, 7, 2),
+        ];
+
+        for (fees, amount_in, amount_out, total_cost, fee_amount) in vectors {
+            assert_eq!(
+                PumpSwap::calculate_buy(&fees, BASE_RESERVE, QUOTE_RESERVE, amount_in).unwrap(),
+                (amount_out, total_cost, fee_amount),
+                "amount in {amount_in}, fees {fees:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn buy_takes_largest_affordable_amount() {
+        for fees in [WITH_CREATOR_FEE, WITHOUT_CREATOR_FEE] {
+            for amount_in in [7, 12_345_679, 1_000_000_000, 55_555_555_555] {
+                let (amount_out, total_cost, _) =
+                    PumpSwap::calculate_buy(&fees, BASE_RESERVE, QUOTE_RESERVE, amount_in).unwrap();
+                assert!(total_cost <= amount_in);
+                assert!(buy_fits(&fees, amount_in, amount_out));
+                assert!(!buy_fits(&fees, amount_in, amount_out + 1));
+            }
+        }
+    }
+
+    #[test]
+    fn buy_of_dust_is_rejected() {
+        assert!(matches!(
+            PumpSwap::calculate_buy(&WITH_CREATOR_FEE, BASE_RESERVE, QUOTE_RESERVE, 1),
+            Err(AutoBuyerError::InsufficientLiquidity)
+        ));
+    }
+
+    #[test]
+    fn sell_matches_reference_vectors() {
+        // (комиссии, сумма, выход после комиссий, комиссии)
+        let vectors = [
+            (WITH_CREATOR_FEE, 1_000_000_000_000, 496_019_898, 1_492_539),
+            (WITH_CREATOR_FEE, 333_333, 163, 3),
+            (
+                WITHOUT_CREATOR_FEE,
+                1_000_000_000_000,
+                496_268_655,
+                1_243_782,
+            ),
+            (WITHOUT_CREATOR_FEE, 333_333, 164, 2),
+        ];
+
+        for (fees, amount_in, amount_out, fee_amount) in vectors {
+            assert_eq!(
+                PumpSwap::calculate_sell(&fees, BASE_RESERVE, QUOTE_RESERVE, amount_in).unwrap(),
+                (amount_out, fee_amount),
+                "amount in {amount_in}, fees {fees:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn buy_instruction_takes_23_accounts_in_program_order() {
+        let accounts: [usize; PUMP_SWAP_ACCOUNTS_LEN] = core::array::from_fn(|i| i);
+        let (ordered, accounts_count) = PumpSwap::instruction_accounts(accounts, true);
+
+        assert_eq!(accounts_count, 23);
+        assert_eq!(
+            ordered,
+            [
+                (6, false),
+                (0, true),
+                (7, false),
+                (1, false),
+                (2, false),
+                (3, true),
+                (4, true),
+                (8, true),
+                (9, true),
+                (10, false),
+                (11, true),
+                (12, false),
+                (13, false),
+                (14, false),
+                (15, false),
+                (16, false),
+                (5, false),
+                (17, true),
+                (18, false),
+                (19, true),
+                (20, true),
+                (21, false),
+                (22, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn sell_instruction_takes_21_accounts_without_volume_accumulators() {
+        let accounts: [usize; PUMP_SWAP_ACCOUNTS_LEN] = core::array::from_fn(|i| i);
+        let (buy_order, _) = PumpSwap::instruction_accounts(accounts, true);
+        let (ordered, accounts_count) = PumpSwap::instruction_accounts(accounts, false);
+
+        assert_eq!(accounts_count, 21);
+        assert_eq!(ordered[..19], buy_order[..19]);
+        assert_eq!(ordered[19..21], [(21, false), (22, false)]);
+    }
+}
@@ -1,21 +1,35 @@
 use pinocchio::{
-    account_info::AccountInfo, entrypoint::ProgramResult, instruction::Seed, instruction::Signer,
-    msg, program::invoke_signed, pubkey::Pubkey,
+    account_info::{AccountInfo, Ref},
+    instruction::Seed,
+    instruction::Signer,
+    msg,
+    program::invoke_signed,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
 };
 
-use spl_token::solana_program::program_pack::Pack;
-
 use borsh::{BorshDeserialize, BorshSerialize};
-use spl_token::state::Account as TokenAccount;
+use bytemuck::{Pod, Zeroable};
+use core::{cell::OnceCell, mem::size_of, ops::Deref};
 
 use crate::{
-    dex::{types::SwapParams, DexInterface},
+    dex::{
+        get_token_balance,
+        types::{SwapMode, SwapParams},
+        verify_received_amount, DexInterface,
+    },
     error::AutoBuyerError,
-    state::{constants, PoolConfig, SwapCalculation, TradingPair},
+    state::{constants, PoolConfig, PriceQ64, SwapCalculation, TradingPair},
 };
 
+/// Количество аккаунтов, необходимых для обмена через Raydium v4
+const RAYDIUM_ACCOUNTS_LEN: usize = 22;
+
 /// Структура для работы с Raydium v4
-pub struct RaydiumV4;
+pub struct RaydiumV4 {
+    /// Адрес пула, AmmInfo которого проверен в рамках текущей инструкции
+    pool_address: OnceCell<Pubkey>,
+}
 
 /// Инструкция обмена Raydium
 #[derive(BorshSerialize, BorshDeserialize)]
@@ -25,8 +39,79 @@ struct RaydiumSwapInstruction {
     minimum_amount_out: u64,
 }
 
-/// Состояние AMM пула Raydium v4
+/// Инструкция обмена Raydium с фиксированным выходом
+#[derive(BorshSerialize, BorshDeserialize)]
+struct RaydiumSwapBaseOutInstruction {
+    instruction: u8, // 11 для swap_base_out
+    max_amount_in: u64,
+    amount_out: u64,
+}
+
+/// Статус AMM пула Raydium v4
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AmmStatus {
+    Uninitialized,
+    Initialized,
+    Disabled,
+    WithdrawOnly,
+    LiquidityOnly,
+    OrderBookOnly,
+    SwapOnly,
+    WaitingTrade,
+}
+
+impl AmmStatus {
+    /// Преобразовать значение поля `AmmInfo.status`
+    fn from_u64(status: u64) -> Option<Self> {
+        match status {
+            0 => Some(AmmStatus::Uninitialized),
+            1 => Some(AmmStatus::Initialized),
+            2 => Some(AmmStatus::Disabled),
+            3 => Some(AmmStatus::WithdrawOnly),
+            4 => Some(AmmStatus::LiquidityOnly),
+            5 => Some(AmmStatus::OrderBookOnly),
+            6 => Some(AmmStatus::SwapOnly),
+            7 => Some(AmmStatus::WaitingTrade),
+            _ => None,
+        }
+    }
+
+    /// Участвует ли книга ордеров OpenBook в ликвидности пула
+    fn orderbook_permission(&self) -> bool {
+        matches!(
+            self,
+            AmmStatus::Initialized | AmmStatus::OrderBookOnly | AmmStatus::WaitingTrade
+        )
+    }
+
+    /// Разрешены ли обмены в этом статусе
+    fn swap_permission(&self) -> bool {
+        matches!(
+            self,
+            AmmStatus::Initialized | AmmStatus::SwapOnly | AmmStatus::WaitingTrade
+        )
+    }
+}
+
+/// Заголовок аккаунта OpenOrders рынка OpenBook (Serum)
+#[allow(dead_code)]
 #[derive(BorshDeserialize, Debug)]
+struct OpenOrdersHeader {
+    head_padding: [u8; 5],
+    account_flags: u64,
+    market: Pubkey,
+    owner: Pubkey,
+    native_coin_free: u64,
+    native_coin_total: u64,
+    native_pc_free: u64,
+    native_pc_total: u64,
+}
+
+/// Состояние AMM пула Raydium v4
+///
+/// Приводится к данным аккаунта через bytemuck без Borsh-десериализации. Поля `u128`
+/// хранятся как `[u64; 2]`, чтобы выравнивание совпадало с раскладкой Raydium.
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
 #[repr(C)]
 pub struct AmmInfo {
     pub status: u64,
@@ -61,11 +146,11 @@ pub struct AmmInfo {
     pub punish_pc_amount: u64,
     pub punish_coin_amount: u64,
     pub orderbook_to_init_time: u64,
-    pub swap_base_in_amount: u128,
-    pub swap_quote_out_amount: u128,
+    pub swap_base_in_amount: [u64; 2],
+    pub swap_quote_out_amount: [u64; 2],
     pub swap_base2_quote_fee: u64,
-    pub swap_quote_in_amount: u128,
-    pub swap_base_out_amount: u128,
+    pub swap_quote_in_amount: [u64; 2],
+    pub swap_base_out_amount: [u64; 2],
     pub swap_quote2_base_fee: u64,
     pub base_vault: Pubkey,
     pub quote_vault: Pubkey,
@@ -82,40 +167,168 @@ pub struct AmmInfo {
     pub lp_reserve: u64,
 }
 
+/// AmmInfo, приведенный к заимствованным данным аккаунта пула
+pub struct AmmInfoRef<'a> {
+    data: Ref<'a, [u8]>,
+}
+
+impl<'a> AmmInfoRef<'a> {
+    /// Проверить размер и выравнивание данных для приведения к AmmInfo
+    fn new(data: Ref<'a, [u8]>) -> Result<Self, AutoBuyerError> {
+        data.get(..size_of::<AmmInfo>())
+            .and_then(|data| bytemuck::try_from_bytes::<AmmInfo>(data).ok())
+            .ok_or(AutoBuyerError::InvalidParameters)?;
+        Ok(Self { data })
+    }
+}
+
+impl Deref for AmmInfoRef<'_> {
+    type Target = AmmInfo;
+
+    fn deref(&self) -> &AmmInfo {
+        // Размер и выравнивание проверены в `AmmInfoRef::new`
+        bytemuck::from_bytes(&self.data[..size_of::<AmmInfo>()])
+    }
+}
+
 impl RaydiumV4 {
     /// Создать новый экземпляр Raydium v4
     pub fn new() -> Self {
-        Self
+        Self {
+            pool_address: OnceCell::new(),
+        }
     }
 
     /// Загрузить информацию о пуле Raydium
-    fn load_amm_info(&self, pool_account: &AccountInfo) -> Result<AmmInfo, AutoBuyerError> {
-        if pool_account.owner() != &constants::RAYDIUM_V4_PROGRAM_ID {
-            return Err(AutoBuyerError::InvalidAccountOwner);
+    ///
+    /// AmmInfo читается на месте из заимствованных данных аккаунта без копирования.
+    /// Владелец пула проверяется один раз за инструкцию, в кэше хранится только адрес.
+    /// Представление нужно отпустить до CPI, изменяющего аккаунт пула.
+    fn load_amm_info<'a>(
+        &self,
+        pool_account: &'a AccountInfo,
+    ) -> Result<AmmInfoRef<'a>, AutoBuyerError> {
+        match self.pool_address.get() {
+            Some(pool_address) if pool_address != pool_account.key() => {
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+            Some(_) => {}
+            None if pool_account.owner() != &constants::RAYDIUM_V4_PROGRAM_ID => {
+                return Err(AutoBuyerError::InvalidAccountOwner);
+            }
+            None => {}
         }
 
-        let pool_data = pool_account
+        let data = pool_account
             .try_borrow_data()
             .map_err(|_| AutoBuyerError::InvalidParameters)?;
+        let amm_info = AmmInfoRef::new(data)?;
 
-        AmmInfo::try_from_slice(&pool_data).map_err(|_| AutoBuyerError::InvalidParameters)
+        self.pool_address.get_or_init(|| *pool_account.key());
+        Ok(amm_info)
     }
 
-    /// Получить баланс токенов из аккаунта
-    fn get_token_balance(account_info: &AccountInfo) -> Result<u64, AutoBuyerError> {
-        let token_account = TokenAccount::unpack(
-            &account_info
-                .try_borrow_data()
-                .map_err(|_| AutoBuyerError::CpiError)?,
-        )
-        .map_err(|e| {
-            msg!("Token error: {:?}", e);
-            AutoBuyerError::CpiError
-        })?;
-        Ok(token_account.amount)
+    /// Проверить, что переданные аккаунты пула соответствуют AmmInfo
+    fn validate_pool_accounts(
+        &self,
+        amm_info: &AmmInfo,
+        accounts: &[AccountInfo],
+    ) -> Result<(), AutoBuyerError> {
+        if accounts.len() < RAYDIUM_ACCOUNTS_LEN {
+            msg!(
+                "Error: Insufficient accounts for Raydium. Expected {}, got {}",
+                RAYDIUM_ACCOUNTS_LEN,
+                accounts.len()
+            );
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if accounts[9].key() != &constants::TOKEN_PROGRAM_ID {
+            msg!("Error: Invalid token program");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if accounts[7].key() != &amm_info.base_vault {
+            msg!("Error: Pool base vault does not match AMM");
+            return Err(AutoBuyerError::InvalidPoolVault);
+        }
+
+        if accounts[8].key() != &amm_info.quote_vault {
+            msg!("Error: Pool quote vault does not match AMM");
+            return Err(AutoBuyerError::InvalidPoolVault);
+        }
+
+        if accounts[12].key() != &amm_info.open_orders {
+            msg!("Error: Open orders do not match AMM");
+            return Err(AutoBuyerError::InvalidOpenOrders);
+        }
+
+        if accounts[13].key() != &amm_info.target_orders {
+            msg!("Error: Target orders do not match AMM");
+            return Err(AutoBuyerError::InvalidTargetOrders);
+        }
+
+        if accounts[14].key() != &amm_info.market_program_id {
+            msg!("Error: Market program does not match AMM");
+            return Err(AutoBuyerError::InvalidMarketProgram);
+        }
+
+        if accounts[15].key() != &amm_info.market_id {
+            msg!("Error: Market does not match AMM");
+            return Err(AutoBuyerError::InvalidMarket);
+        }
+
+        Ok(())
+    }
+
+    /// Проверить, что пул открыт для обменов
+    fn check_pool_status(&self, amm_info: &AmmInfo) -> Result<(), AutoBuyerError> {
+        let status = AmmStatus::from_u64(amm_info.status);
+
+        if !status.is_some_and(|status| status.swap_permission()) {
+            msg!(
+                "Error: Pool status {} does not allow swaps",
+                amm_info.status
+            );
+            return Err(AutoBuyerError::PoolNotTradable);
+        }
+
+        let clock = Clock::get().map_err(|_| AutoBuyerError::InvalidParameters)?;
+        if (clock.unix_timestamp as u64) < amm_info.pool_open_time {
+            msg!(
+                "Error: Pool opens at {}, current time {}",
+                amm_info.pool_open_time,
+                clock.unix_timestamp
+            );
+            return Err(AutoBuyerError::PoolNotOpen);
+        }
+
+        Ok(())
+    }
+
+    /// Деление с округлением вверх, повторяющее `checked_ceil_div` программы Raydium
+    ///
+    /// Если частное равно нулю, результат округляется до 1 только при
+    /// `dividend * 2 >= divisor`, иначе возвращается 0.
+    fn checked_ceil_div(dividend: u128, divisor: u128) -> Option<u128> {
+        let quotient = dividend.checked_div(divisor)?;
+
+        if quotient == 0 {
+            return if dividend.checked_mul(2)? >= divisor {
+                Some(1)
+            } else {
+                Some(0)
+            };
+        }
+
+        if dividend.checked_rem(divisor)? > 0 {
+            quotient.checked_add(1)
+        } else {
+            Some(quotient)
+        }
     }
 
-    /// Рассчитать количество выходного токена
+    /// Рассчитать количество выходного токена (Raydium swap_base_in)
     fn calculate_amount_out(
         &self,
         amount_in: u64,
@@ -128,19 +341,19 @@ impl RaydiumV4 {
             return Err(AutoBuyerError::InsufficientLiquidity);
         }
 
-        let fee_amount = amount_in
-            .checked_mul(fee_numerator)
-            .and_then(|x| x.checked_div(fee_denominator))
+        let fee_amount = (amount_in as u128)
+            .checked_mul(fee_numerator as u128)
+            .and_then(|x| Self::checked_ceil_div(x, fee_denominator as u128))
             .ok_or(AutoBuyerError::MathOverflow)?;
 
-        let amount_in_after_fee = amount_in
+        let amount_in_after_fee = (amount_in as u128)
             .checked_sub(fee_amount)
             .ok_or(AutoBuyerError::MathOverflow)?;
 
         let numerator = amount_in_after_fee
-            .checked_mul(reserve_out)
+            .checked_mul(reserve_out as u128)
             .ok_or(AutoBuyerError::MathOverflow)?;
-        let denominator = reserve_in
+        let denominator = (reserve_in as u128)
             .checked_add(amount_in_after_fee)
             .ok_or(AutoBuyerError::MathOverflow)?;
 
@@ -148,7 +361,132 @@ impl RaydiumV4 {
             .checked_div(denominator)
             .ok_or(AutoBuyerError::MathOverflow)?;
 
-        Ok((amount_out, fee_amount))
+        Ok((
+            u64::try_from(amount_out).map_err(|_| AutoBuyerError::MathOverflow)?,
+            u64::try_from(fee_amount).map_err(|_| AutoBuyerError::MathOverflow)?,
+        ))
+    }
+
+    /// Рассчитать количество входного токена для получения заданного выхода (Raydium swap_base_out)
+    ///
+    /// Округление выполняется вверх, в пользу пула.
+    fn calculate_amount_in(
+        &self,
+        amount_out: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+        fee_numerator: u64,
+        fee_denominator: u64,
+    ) -> Result<(u64, u64), AutoBuyerError> {
+        if reserve_in == 0 || reserve_out == 0 || amount_out >= reserve_out {
+            return Err(AutoBuyerError::InsufficientLiquidity);
+        }
+
+        let numerator = (reserve_in as u128)
+            .checked_mul(amount_out as u128)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+        let denominator = (reserve_out - amount_out) as u128;
+        let amount_in_before_fee =
+            Self::checked_ceil_div(numerator, denominator).ok_or(AutoBuyerError::MathOverflow)?;
+
+        let fee_denominator_after_fee = fee_denominator
+            .checked_sub(fee_numerator)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+
+        let amount_in = amount_in_before_fee
+            .checked_mul(fee_denominator as u128)
+            .and_then(|x| Self::checked_ceil_div(x, fee_denominator_after_fee as u128))
+            .ok_or(AutoBuyerError::MathOverflow)?;
+
+        let fee_amount = amount_in
+            .checked_sub(amount_in_before_fee)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+
+        Ok((
+            u64::try_from(amount_in).map_err(|_| AutoBuyerError::MathOverflow)?,
+            u64::try_from(fee_amount).map_err(|_| AutoBuyerError::MathOverflow)?,
+        ))
+    }
+
+    /// Загрузить заголовок аккаунта OpenOrders пула
+    fn load_open_orders(
+        &self,
+        amm_info: &AmmInfo,
+        open_orders_account: &AccountInfo,
+    ) -> Result<OpenOrdersHeader, AutoBuyerError> {
+        if open_orders_account.owner() != &amm_info.market_program_id {
+            return Err(AutoBuyerError::InvalidAccountOwner);
+        }
+
+        let data = open_orders_account
+            .try_borrow_data()
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        let open_orders = OpenOrdersHeader::deserialize(&mut data.as_ref())
+            .map_err(|_| AutoBuyerError::InvalidOpenOrders)?;
+
+        if open_orders.market != amm_info.market_id {
+            msg!("Error: Open orders belong to another market");
+            return Err(AutoBuyerError::InvalidOpenOrders);
+        }
+
+        Ok(open_orders)
+    }
+
+    /// Получить резервы пула (вход, выход) для торговой пары
+    ///
+    /// Резервы считаются так же, как в Raydium: баланс хранилища плюс средства
+    /// в OpenOrders (если книга ордеров активна) минус PnL, ожидающий вывода.
+    fn get_reserves(
+        &self,
+        amm_info: &AmmInfo,
+        trading_pair: &TradingPair,
+        accounts: &[AccountInfo],
+    ) -> Result<(u64, u64), AutoBuyerError> {
+        let token_a_vault = &accounts[7];
+        let token_b_vault = &accounts[8];
+
+        let vault_a = get_token_balance(token_a_vault)?;
+        let vault_b = get_token_balance(token_b_vault)?;
+
+        let orderbook_enabled = AmmStatus::from_u64(amm_info.status)
+            .is_some_and(|status| status.orderbook_permission());
+
+        let (open_orders_a, open_orders_b) = if orderbook_enabled {
+            let open_orders = self.load_open_orders(amm_info, &accounts[12])?;
+            (open_orders.native_coin_total, open_orders.native_pc_total)
+        } else {
+            (0, 0)
+        };
+
+        let reserve_a = vault_a
+            .checked_add(open_orders_a)
+            .and_then(|x| x.checked_sub(amm_info.base_need_take_pnl))
+            .ok_or(AutoBuyerError::MathOverflow)?;
+        let reserve_b = vault_b
+            .checked_add(open_orders_b)
+            .and_then(|x| x.checked_sub(amm_info.quote_need_take_pnl))
+            .ok_or(AutoBuyerError::MathOverflow)?;
+
+        // Входной токен - quote_mint торговой пары (при продаже это целевой токен)
+        if trading_pair.pool_config.token_a_mint == trading_pair.quote_mint {
+            Ok((reserve_a, reserve_b))
+        } else {
+            Ok((reserve_b, reserve_a))
+        }
+    }
+
+    /// Рассчитать цену за единицу и влияние на цену
+    fn calculate_price_metrics(
+        amount_in: u64,
+        amount_out: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+    ) -> (PriceQ64, u16) {
+        let price_per_unit = PriceQ64::from_ratio(amount_out, amount_in).unwrap_or_default();
+        let spot_price = PriceQ64::from_ratio(reserve_out, reserve_in).unwrap_or_default();
+
+        (price_per_unit, price_per_unit.impact_bps(spot_price))
     }
 
     /// Создать данные инструкции обмена для Raydium
@@ -156,29 +494,46 @@ impl RaydiumV4 {
         &self,
         swap_params: &SwapParams,
     ) -> Result<Vec<u8>, AutoBuyerError> {
-        let instruction_data = RaydiumSwapInstruction {
-            instruction: 9, // Raydium swap instruction
-            amount_in: swap_params.amount_in,
-            minimum_amount_out: swap_params.min_amount_out,
-        };
-        borsh::to_vec(&instruction_data).map_err(|_| AutoBuyerError::InvalidParameters)
+        match swap_params.mode {
+            SwapMode::ExactIn => {
+                let instruction_data = RaydiumSwapInstruction {
+                    instruction: 9, // Raydium swap_base_in instruction
+                    amount_in: swap_params.amount_in,
+                    minimum_amount_out: swap_params.min_amount_out,
+                };
+                borsh::to_vec(&instruction_data)
+            }
+            SwapMode::ExactOut => {
+                let instruction_data = RaydiumSwapBaseOutInstruction {
+                    instruction: 11, // Raydium swap_base_out instruction
+                    max_amount_in: swap_params.amount_in,
+                    amount_out: swap_params.min_amount_out,
+                };
+                borsh::to_vec(&instruction_data)
+            }
+        }
+        .map_err(|_| AutoBuyerError::InvalidParameters)
     }
 
     /// Выполнить обмен через CPI
+    ///
+    /// Возвращает фактически полученное количество выходного токена,
+    /// вычисленное по изменению баланса аккаунта назначения.
     fn execute_raydium_swap(
         &self,
         accounts: &[AccountInfo],
         swap_params: &SwapParams,
         _program_id: &Pubkey,
-    ) -> ProgramResult {
+    ) -> Result<u64, AutoBuyerError> {
         let instruction_data = self.create_swap_instruction_data(swap_params)?;
 
         let amm_account = &accounts[6];
-        let amm_info = self.load_amm_info(amm_account)?;
+        // Данные пула заимствуются только на время чтения nonce: CPI изменяет аккаунт пула
+        let nonce = self.load_amm_info(amm_account)?.nonce;
 
         let user_account = &accounts[0];
-        let source_token_account = &accounts[1];
-        let destination_token_account = &accounts[2];
+        let (source_token_account, destination_token_account) =
+            swap_params.direction.user_token_accounts(accounts);
         let raydium_program = &accounts[5];
         let amm_authority = &accounts[11]; // PDA
         let amm_open_orders = &accounts[12];
@@ -243,24 +598,37 @@ impl RaydiumV4 {
             user_account,
         ];
 
-        let binding = amm_info.nonce.to_le_bytes();
+        let binding = nonce.to_le_bytes();
         let seeds = &[
             Seed::from(b"amm_authority".as_ref()),
             Seed::from(binding.as_ref()),
         ];
 
+        let balance_before = get_token_balance(destination_token_account)?;
+
         invoke_signed(&instruction, &account_infos, &[Signer::from(seeds)])
+            .map_err(|_| AutoBuyerError::CpiError)?;
+
+        verify_received_amount(
+            destination_token_account,
+            balance_before,
+            swap_params.min_amount_out,
+        )
     }
 }
 
 impl DexInterface for RaydiumV4 {
+    fn accounts_len(&self) -> usize {
+        RAYDIUM_ACCOUNTS_LEN
+    }
+
     fn find_trading_pair(
         &self,
         base_mint: &Pubkey,
         quote_mint: &Pubkey,
         accounts: &[AccountInfo],
     ) -> Result<Option<TradingPair>, AutoBuyerError> {
-        if accounts.len() < 7 {
+        if accounts.len() < 7 || accounts[5].key() != &constants::RAYDIUM_V4_PROGRAM_ID {
             return Ok(None);
         }
 
@@ -278,14 +646,18 @@ impl DexInterface for RaydiumV4 {
             return Ok(None);
         }
 
+        self.validate_pool_accounts(&amm_info, accounts)?;
+        self.check_pool_status(&amm_info)?;
+
         let pool_config = PoolConfig {
             pool_address: *pool_account.key(),
             token_a_account: amm_info.base_vault,
             token_b_account: amm_info.quote_vault,
             token_a_mint: amm_info.base_mint,
             token_b_mint: amm_info.quote_mint,
-            fee_rate: (amm_info.trade_fee_numerator * constants::BASIS_POINTS as u64
-                / amm_info.trade_fee_denominator) as u16,
+            fee_rate: (amm_info.swap_fee_numerator as u128 * constants::BASIS_POINTS as u128)
+                .checked_div(amm_info.swap_fee_denominator as u128)
+                .ok_or(AutoBuyerError::MathOverflow)? as u16,
         };
 
         let trading_pair = TradingPair {
@@ -306,46 +678,56 @@ impl DexInterface for RaydiumV4 {
         let pool_account = &accounts[6];
         let amm_info = self.load_amm_info(pool_account)?;
 
-        let token_a_vault = &accounts[7];
-        let token_b_vault = &accounts[8];
-
-        let reserve_a = Self::get_token_balance(token_a_vault)?;
-        let reserve_b = Self::get_token_balance(token_b_vault)?;
-
-        let (reserve_in, reserve_out) =
-            if trading_pair.pool_config.token_a_mint == trading_pair.quote_mint {
-                (reserve_a, reserve_b)
-            } else {
-                (reserve_b, reserve_a)
-            };
+        let (reserve_in, reserve_out) = self.get_reserves(&amm_info, trading_pair, accounts)?;
 
         let (amount_out, fee_amount) = self.calculate_amount_out(
             amount_in,
             reserve_in,
             reserve_out,
-            amm_info.trade_fee_numerator,
-            amm_info.trade_fee_denominator,
+            amm_info.swap_fee_numerator,
+            amm_info.swap_fee_denominator,
         )?;
 
-        let price_per_unit = if amount_in > 0 {
-            amount_out as f64 / amount_in as f64
-        } else {
-            0.0
-        };
+        let (price_per_unit, price_impact_bps) =
+            Self::calculate_price_metrics(amount_in, amount_out, reserve_in, reserve_out);
 
-        let slippage_percent = if reserve_out > 0 {
-            let ideal_price = reserve_out as f64 / reserve_in as f64;
-            ((ideal_price - price_per_unit) / ideal_price * 100.0).abs()
-        } else {
-            0.0
-        };
+        Ok(SwapCalculation {
+            amount_in,
+            amount_out,
+            fee_amount,
+            price_per_unit,
+            price_impact_bps,
+        })
+    }
+
+    fn calculate_swap_exact_out(
+        &self,
+        trading_pair: &TradingPair,
+        amount_out: u64,
+        accounts: &[AccountInfo],
+    ) -> Result<SwapCalculation, AutoBuyerError> {
+        let pool_account = &accounts[6];
+        let amm_info = self.load_amm_info(pool_account)?;
+
+        let (reserve_in, reserve_out) = self.get_reserves(&amm_info, trading_pair, accounts)?;
+
+        let (amount_in, fee_amount) = self.calculate_amount_in(
+            amount_out,
+            reserve_in,
+            reserve_out,
+            amm_info.swap_fee_numerator,
+            amm_info.swap_fee_denominator,
+        )?;
+
+        let (price_per_unit, price_impact_bps) =
+            Self::calculate_price_metrics(amount_in, amount_out, reserve_in, reserve_out);
 
         Ok(SwapCalculation {
             amount_in,
             amount_out,
             fee_amount,
             price_per_unit,
-            slippage_percent,
+            price_impact_bps,
         })
     }
 
@@ -354,16 +736,153 @@ impl DexInterface for RaydiumV4 {
         program_id: &Pubkey,
         accounts: &[AccountInfo],
         swap_params: &SwapParams,
-    ) -> ProgramResult {
+    ) -> Result<u64, AutoBuyerError> {
         msg!(
             "Executing Raydium swap: {} -> {}",
             swap_params.amount_in,
             swap_params.calculation.amount_out
         );
 
-        self.execute_raydium_swap(accounts, swap_params, program_id)?;
+        let amount_received = self.execute_raydium_swap(accounts, swap_params, program_id)?;
 
-        msg!("Raydium swap completed successfully");
-        Ok(())
+        msg!(
+            "Raydium swap completed successfully, received {}",
+            amount_received
+        );
+        Ok(amount_received)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Комиссия пулов AMM v4: 25 / 10 000
+    const FEE_NUMERATOR: u64 = 25;
+    const FEE_DENOMINATOR: u64 = 10_000;
+
+    #[test]
+    fn ceil_div_rounds_small_quotients_to_nearest() {
+        // (делимое, делитель, результат) по правилам CheckedCeilDiv Raydium
+        let vectors = [
+            (10, 3, 4),
+            (9, 3, 3),
+            (2, 3, 1),
+            (1, 2, 1),
+            (1, 3, 0),
+            (0, 5, 0),
+        ];
+        for (dividend, divisor, expected) in vectors {
+            assert_eq!(
+                RaydiumV4::checked_ceil_div(dividend, divisor),
+                Some(expected),
+                "{dividend} / {divisor}"
+            );
+        }
+        assert_eq!(RaydiumV4::checked_ceil_div(1, 0), None);
+    }
+
+    #[test]
+    fn amount_out_matches_swap_base_in() {
+        // (вход, резерв входа, резерв выхода, выход, комиссия)
+        let vectors = [
+            (
+                1_000_000_000,
+                50_000_000_000,
+                2_000_000_000_000_000,
+                39_119_564_684_543,
+                2_500_000,
+            ),
+            (1, 1_000_000, 1_000_000, 0, 0),
+            (
+                12_345_678_901,
+                1_000_000_000_000_000_000,
+                3_000_000_000_000_000_000,
+                36_944_443_654,
+                30_864_198,
+            ),
+            (
+                9_999_999_999_999_999_999,
+                18_000_000_000_000_000_000,
+                18_000_000_000_000_000_000,
+                6_418_230_563_002_680_964,
+                25_000_000_000_000_000,
+            ),
+        ];
+
+        let raydium = RaydiumV4::new();
+        for (amount_in, reserve_in, reserve_out, amount_out, fee_amount) in vectors {
+            assert_eq!(
+                raydium
+                    .calculate_amount_out(
+                        amount_in,
+                        reserve_in,
+                        reserve_out,
+                        FEE_NUMERATOR,
+                        FEE_DENOMINATOR
+                    )
+                    .unwrap(),
+                (amount_out, fee_amount),
+                "amount in {amount_in}"
+            );
+        }
+    }
+
+    #[test]
+    fn amount_in_matches_swap_base_out() {
+        // (выход, резерв входа, резерв выхода, вход, комиссия)
+        let vectors = [
+            (
+                1_000_000_000_000,
+                50_000_000_000,
+                2_000_000_000_000_000,
+                25_075_195,
+                62_688,
+            ),
+            (1, 1_000_000, 1_000_000, 3, 1),
+            (
+                1_000_000_000_000_000_000,
+                3_000_000_000_000_000_000,
+                10_000_000_000_000_000_000,
+                334_168_755_221_386_802,
+                835_421_888_053_468,
+            ),
+        ];
+
+        let raydium = RaydiumV4::new();
+        for (amount_out, reserve_in, reserve_out, amount_in, fee_amount) in vectors {
+            assert_eq!(
+                raydium
+                    .calculate_amount_in(
+                        amount_out,
+                        reserve_in,
+                        reserve_out,
+                        FEE_NUMERATOR,
+                        FEE_DENOMINATOR
+                    )
+                    .unwrap(),
+                (amount_in, fee_amount),
+                "amount out {amount_out}"
+            );
+        }
+    }
+
+    #[test]
+    fn empty_or_drained_pool_is_rejected() {
+        let raydium = RaydiumV4::new();
+        assert!(matches!(
+            raydium.calculate_amount_out(1_000, 0, 1_000_000, FEE_NUMERATOR, FEE_DENOMINATOR),
+            Err(AutoBuyerError::InsufficientLiquidity)
+        ));
+        assert!(matches!(
+            raydium.calculate_amount_in(
+                1_000_000,
+                1_000_000,
+                1_000_000,
+                FEE_NUMERATOR,
+                FEE_DENOMINATOR
+            ),
+            Err(AutoBuyerError::InsufficientLiquidity)
+        ));
     }
 }
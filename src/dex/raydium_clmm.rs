@@ -0,0 +1,733 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::cell::OnceCell;
+
+use crate::{
+    dex::{
+        get_token_balance, load_anchor_account,
+        math::{self, InitializedTick, TickRange},
+        types::{SwapMode, SwapParams},
+        verify_received_amount, DexInterface,
+    },
+    error::AutoBuyerError,
+    state::{constants, PoolConfig, PriceQ64, SwapCalculation, TradingPair},
+};
+
+/// Дискриминатор аккаунта PoolState
+const POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+
+/// Дискриминатор аккаунта AmmConfig
+const AMM_CONFIG_DISCRIMINATOR: [u8; 8] = [218, 244, 33, 104, 203, 203, 43, 111];
+
+/// Дискриминатор аккаунта TickArrayState
+const TICK_ARRAY_DISCRIMINATOR: [u8; 8] = [192, 155, 85, 205, 49, 249, 129, 42];
+
+/// Дискриминатор аккаунта TickArrayBitmapExtension
+const BITMAP_EXTENSION_DISCRIMINATOR: [u8; 8] = [60, 150, 36, 219, 97, 128, 139, 153];
+
+/// Дискриминатор инструкции swap
+const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+
+/// Количество тиков в одном TickArrayState
+const TICK_ARRAY_SIZE: i32 = 60;
+
+/// Размер сериализованного тика
+const TICK_SIZE: usize = 168;
+
+/// Смещение адреса пула в данных TickArrayState
+const TICK_ARRAY_POOL_OFFSET: usize = 8;
+
+/// Смещение индекса первого тика в данных TickArrayState
+const TICK_ARRAY_START_OFFSET: usize = 40;
+
+/// Смещение первого тика в данных TickArrayState
+const TICKS_OFFSET: usize = 44;
+
+/// Количество массивов тиков, покрываемых одной строкой битовой карты
+const TICK_ARRAY_BITMAP_SIZE: i32 = 512;
+
+/// Количество строк битовой карты расширения в каждом направлении
+const EXTENSION_BITMAP_ROWS: usize = 14;
+
+/// Смещение положительной битовой карты в данных расширения
+const EXTENSION_POSITIVE_OFFSET: usize = 40;
+
+/// Смещение отрицательной битовой карты в данных расширения
+const EXTENSION_NEGATIVE_OFFSET: usize = EXTENSION_POSITIVE_OFFSET + EXTENSION_BITMAP_ROWS * 64;
+
+/// Бит статуса пула, запрещающий обмены
+const POOL_STATUS_SWAP_DISABLED: u8 = 1 << 4;
+
+/// Количество аккаунтов, необходимых для обмена через CLMM
+const CLMM_ACCOUNTS_LEN: usize = 16;
+
+/// Структура для работы с Raydium CLMM
+///
+/// Аккаунты (после общих 0-4):
+/// 5. `[]` Программа Raydium CLMM
+/// 6. `[writable]` PoolState
+/// 7. `[]` AmmConfig
+/// 8. `[writable]` Хранилище токена 0
+/// 9. `[writable]` Хранилище токена 1
+/// 10. `[writable]` Observation state
+/// 11. `[]` Программа токенов
+/// 12. `[]` TickArrayBitmapExtension
+/// 13. `[writable]` TickArray 0 (содержит текущий тик или первый в направлении обмена)
+/// 14. `[writable]` TickArray 1
+/// 15. `[writable]` TickArray 2
+pub struct RaydiumClmm {
+    /// Состояние пула и его конфигурация, загруженные в рамках текущей инструкции
+    pool: OnceCell<(Pubkey, ClmmPoolState, ClmmAmmConfig)>,
+}
+
+/// Состояние пула CLMM (до времени открытия)
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct ClmmPoolState {
+    pub bump: [u8; 1],
+    pub amm_config: Pubkey,
+    pub owner: Pubkey,
+    pub token_mint_0: Pubkey,
+    pub token_mint_1: Pubkey,
+    pub token_vault_0: Pubkey,
+    pub token_vault_1: Pubkey,
+    pub observation_key: Pubkey,
+    pub mint_decimals_0: u8,
+    pub mint_decimals_1: u8,
+    pub tick_spacing: u16,
+    pub liquidity: u128,
+    pub sqrt_price_x64: u128,
+    pub tick_current: i32,
+    pub padding3: u16,
+    pub padding4: u16,
+    pub fee_growth_global_0_x64: u128,
+    pub fee_growth_global_1_x64: u128,
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub swap_in_amount_token_0: u128,
+    pub swap_out_amount_token_1: u128,
+    pub swap_in_amount_token_1: u128,
+    pub swap_out_amount_token_0: u128,
+    pub status: u8,
+    pub padding: [u8; 7],
+    pub reward_infos: [u8; 507],
+    pub tick_array_bitmap: [u64; 16],
+    pub total_fees_token_0: u64,
+    pub total_fees_claimed_token_0: u64,
+    pub total_fees_token_1: u64,
+    pub total_fees_claimed_token_1: u64,
+    pub fund_fees_token_0: u64,
+    pub fund_fees_token_1: u64,
+    pub open_time: u64,
+}
+
+/// Конфигурация комиссий CLMM
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct ClmmAmmConfig {
+    pub bump: u8,
+    pub index: u16,
+    pub owner: Pubkey,
+    pub protocol_fee_rate: u32,
+    pub trade_fee_rate: u32,
+    pub tick_spacing: u16,
+    pub fund_fee_rate: u32,
+}
+
+/// Инструкция обмена CLMM
+#[derive(BorshSerialize)]
+struct ClmmSwapInstruction {
+    discriminator: [u8; 8],
+    amount: u64,
+    other_amount_threshold: u64,
+    /// 0 - без ограничения цены
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+}
+
+impl RaydiumClmm {
+    /// Создать новый экземпляр Raydium CLMM
+    pub fn new() -> Self {
+        Self {
+            pool: OnceCell::new(),
+        }
+    }
+
+    /// Загрузить состояние пула и конфигурацию комиссий
+    fn load_pool(
+        &self,
+        pool_account: &AccountInfo,
+        amm_config_account: &AccountInfo,
+    ) -> Result<(&ClmmPoolState, &ClmmAmmConfig), AutoBuyerError> {
+        if let Some((pool_address, pool, amm_config)) = self.pool.get() {
+            if pool_address != pool_account.key() {
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+            return Ok((pool, amm_config));
+        }
+
+        let pool: ClmmPoolState = load_anchor_account(
+            pool_account,
+            &constants::RAYDIUM_CLMM_PROGRAM_ID,
+            &POOL_STATE_DISCRIMINATOR,
+        )?;
+
+        if amm_config_account.key() != &pool.amm_config {
+            msg!("Error: AmmConfig does not match CLMM pool");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+        let amm_config: ClmmAmmConfig = load_anchor_account(
+            amm_config_account,
+            &constants::RAYDIUM_CLMM_PROGRAM_ID,
+            &AMM_CONFIG_DISCRIMINATOR,
+        )?;
+
+        let (_, pool, amm_config) = self
+            .pool
+            .get_or_init(|| (*pool_account.key(), pool, amm_config));
+        Ok((pool, amm_config))
+    }
+
+    /// Проверить, что переданные аккаунты соответствуют пулу
+    fn validate_pool_accounts(
+        &self,
+        pool: &ClmmPoolState,
+        amm_config: &ClmmAmmConfig,
+        accounts: &[AccountInfo],
+    ) -> Result<(), AutoBuyerError> {
+        if accounts[8].key() != &pool.token_vault_0 || accounts[9].key() != &pool.token_vault_1 {
+            msg!("Error: Pool vaults do not match CLMM pool");
+            return Err(AutoBuyerError::InvalidPoolVault);
+        }
+
+        if accounts[10].key() != &pool.observation_key {
+            msg!("Error: Observation state does not match CLMM pool");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if accounts[11].key() != &constants::TOKEN_PROGRAM_ID {
+            msg!("Error: Invalid token program");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if amm_config.trade_fee_rate >= math::FEE_RATE_DENOMINATOR || pool.tick_spacing == 0 {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        Ok(())
+    }
+
+    /// Проверить, что пул открыт для обменов
+    fn check_pool_status(&self, pool: &ClmmPoolState) -> Result<(), AutoBuyerError> {
+        if pool.status & POOL_STATUS_SWAP_DISABLED != 0 {
+            msg!("Error: Pool status {} does not allow swaps", pool.status);
+            return Err(AutoBuyerError::PoolNotTradable);
+        }
+
+        let clock = Clock::get().map_err(|_| AutoBuyerError::InvalidParameters)?;
+        if (clock.unix_timestamp as u64) <= pool.open_time {
+            msg!(
+                "Error: Pool opens at {}, current time {}",
+                pool.open_time,
+                clock.unix_timestamp
+            );
+            return Err(AutoBuyerError::PoolNotOpen);
+        }
+
+        Ok(())
+    }
+
+    /// Направление обмена: true, если входной токен - токен 0 пула
+    fn is_zero_for_one(pool: &ClmmPoolState, trading_pair: &TradingPair) -> bool {
+        trading_pair.quote_mint == pool.token_mint_0
+    }
+
+    /// Проверить бит инициализации массива тиков в битовой карте пула или расширения
+    fn is_tick_array_initialized(
+        pool: &ClmmPoolState,
+        bitmap_extension: &[u8],
+        start_tick_index: i32,
+    ) -> Result<bool, AutoBuyerError> {
+        let ticks_in_array = TICK_ARRAY_SIZE * pool.tick_spacing as i32;
+        let ticks_in_bitmap = TICK_ARRAY_BITMAP_SIZE * ticks_in_array;
+
+        // Битовая карта пула покрывает 1024 массива вокруг нулевого тика
+        if (-ticks_in_bitmap..ticks_in_bitmap).contains(&start_tick_index) {
+            let bit = (start_tick_index / ticks_in_array + TICK_ARRAY_BITMAP_SIZE) as usize;
+            return Ok(pool.tick_array_bitmap[bit / 64] >> (bit % 64) & 1 == 1);
+        }
+
+        // Остальные массивы отмечены в расширении, по строке на 512 массивов
+        let abs_index = start_tick_index.abs();
+        let mut row = abs_index / ticks_in_bitmap - 1;
+        if start_tick_index < 0 && abs_index % ticks_in_bitmap == 0 {
+            row -= 1;
+        }
+
+        let remainder = abs_index % ticks_in_bitmap;
+        let mut position = remainder / ticks_in_array;
+        if start_tick_index < 0 && remainder != 0 {
+            position = TICK_ARRAY_BITMAP_SIZE - position;
+        }
+
+        let row = row as usize;
+        if row >= EXTENSION_BITMAP_ROWS {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let base = if start_tick_index < 0 {
+            EXTENSION_NEGATIVE_OFFSET
+        } else {
+            EXTENSION_POSITIVE_OFFSET
+        };
+        let word_offset = base + row * 64 + position as usize / 64 * 8;
+        let word = u64::from_le_bytes(
+            bitmap_extension[word_offset..word_offset + 8]
+                .try_into()
+                .unwrap(),
+        );
+
+        Ok(word >> (position % 64) & 1 == 1)
+    }
+
+    /// Загрузить тики для обхода в направлении обмена
+    ///
+    /// Массивы, отсутствующие среди переданных, пропускаются, только если они
+    /// не инициализированы согласно битовой карте пула или расширения.
+    fn load_tick_range(
+        &self,
+        pool: &ClmmPoolState,
+        accounts: &[AccountInfo],
+        zero_for_one: bool,
+    ) -> Result<TickRange, AutoBuyerError> {
+        let pool_address = accounts[6].key();
+        let ticks_in_array = TICK_ARRAY_SIZE * pool.tick_spacing as i32;
+
+        let bitmap_extension_account = &accounts[12];
+        if bitmap_extension_account.owner() != &constants::RAYDIUM_CLMM_PROGRAM_ID {
+            return Err(AutoBuyerError::InvalidAccountOwner);
+        }
+        let bitmap_extension = bitmap_extension_account
+            .try_borrow_data()
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+        if bitmap_extension.len() < EXTENSION_NEGATIVE_OFFSET + EXTENSION_BITMAP_ROWS * 64
+            || bitmap_extension[..8] != BITMAP_EXTENSION_DISCRIMINATOR
+            || &bitmap_extension[8..40] != pool_address
+        {
+            msg!("Error: Invalid tick array bitmap extension");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let current_start = pool.tick_current.div_euclid(ticks_in_array) * ticks_in_array;
+
+        // Загрузка уникальных массивов тиков пула
+        let mut arrays: Vec<(i32, Vec<InitializedTick>)> = Vec::with_capacity(3);
+        for tick_array in &accounts[13..16] {
+            let (start_tick_index, ticks) = Self::parse_tick_array(tick_array, pool_address)?;
+            if !arrays.iter().any(|(start, _)| *start == start_tick_index) {
+                arrays.push((start_tick_index, ticks));
+            }
+        }
+
+        let mut remaining = arrays
+            .iter()
+            .filter(|(start, _)| {
+                if zero_for_one {
+                    *start <= current_start
+                } else {
+                    *start >= current_start
+                }
+            })
+            .count();
+
+        let mut start = current_start;
+        let mut ticks = Vec::new();
+        let mut boundary_tick = None;
+
+        while remaining > 0 && start + ticks_in_array > math::MIN_TICK && start <= math::MAX_TICK {
+            match arrays.iter().find(|(s, _)| *s == start) {
+                Some((_, array_ticks)) => {
+                    ticks.extend(array_ticks.iter().copied());
+                    remaining -= 1;
+                }
+                None if Self::is_tick_array_initialized(pool, &bitmap_extension, start)? => break,
+                None => {}
+            }
+
+            if zero_for_one {
+                boundary_tick = Some(start);
+                start -= ticks_in_array;
+            } else {
+                boundary_tick = Some(start + ticks_in_array);
+                start += ticks_in_array;
+            }
+        }
+
+        let Some(boundary_tick) = boundary_tick else {
+            msg!("Error: Tick array for the current tick is missing");
+            return Err(AutoBuyerError::InvalidParameters);
+        };
+
+        if zero_for_one {
+            ticks.retain(|tick| tick.index <= pool.tick_current);
+            ticks.sort_unstable_by_key(|tick| core::cmp::Reverse(tick.index));
+        } else {
+            ticks.retain(|tick| tick.index > pool.tick_current);
+            ticks.sort_unstable_by_key(|tick| tick.index);
+        }
+
+        Ok(TickRange {
+            ticks,
+            boundary_tick: boundary_tick.clamp(math::MIN_TICK, math::MAX_TICK),
+        })
+    }
+
+    /// Разобрать TickArrayState и вернуть индекс первого тика и инициализированные тики
+    fn parse_tick_array(
+        tick_array: &AccountInfo,
+        pool_address: &Pubkey,
+    ) -> Result<(i32, Vec<InitializedTick>), AutoBuyerError> {
+        if tick_array.owner() != &constants::RAYDIUM_CLMM_PROGRAM_ID {
+            return Err(AutoBuyerError::InvalidAccountOwner);
+        }
+
+        let data = tick_array
+            .try_borrow_data()
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        if data.len() < TICKS_OFFSET + TICK_ARRAY_SIZE as usize * TICK_SIZE
+            || data[..8] != TICK_ARRAY_DISCRIMINATOR
+        {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if &data[TICK_ARRAY_POOL_OFFSET..TICK_ARRAY_POOL_OFFSET + 32] != pool_address {
+            msg!("Error: Tick array belongs to another pool");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let start_tick_index = i32::from_le_bytes(
+            data[TICK_ARRAY_START_OFFSET..TICK_ARRAY_START_OFFSET + 4]
+                .try_into()
+                .unwrap(),
+        );
+
+        // Тик инициализирован, если его liquidity_gross отлична от нуля
+        let ticks = (0..TICK_ARRAY_SIZE as usize)
+            .filter_map(|i| {
+                let offset = TICKS_OFFSET + i * TICK_SIZE;
+                let liquidity_gross =
+                    u128::from_le_bytes(data[offset + 20..offset + 36].try_into().unwrap());
+                (liquidity_gross != 0).then(|| InitializedTick {
+                    index: i32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()),
+                    liquidity_net: i128::from_le_bytes(
+                        data[offset + 4..offset + 20].try_into().unwrap(),
+                    ),
+                })
+            })
+            .collect();
+
+        Ok((start_tick_index, ticks))
+    }
+
+    /// Выполнить обмен через CPI
+    fn execute_clmm_swap(
+        &self,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        let (pool, _) = self.load_pool(&accounts[6], &accounts[7])?;
+        let zero_for_one = Self::is_zero_for_one(pool, &swap_params.trading_pair);
+
+        let user_account = &accounts[0];
+        let (source_token_account, destination_token_account) =
+            swap_params.direction.user_token_accounts(accounts);
+        let clmm_program = &accounts[5];
+        let pool_account = &accounts[6];
+        let amm_config = &accounts[7];
+        let (input_vault, output_vault) = if zero_for_one {
+            (&accounts[8], &accounts[9])
+        } else {
+            (&accounts[9], &accounts[8])
+        };
+        let observation_state = &accounts[10];
+        let token_program = &accounts[11];
+        let bitmap_extension = &accounts[12];
+        let tick_array_0 = &accounts[13];
+        let tick_array_1 = &accounts[14];
+        let tick_array_2 = &accounts[15];
+
+        let instruction_data = borsh::to_vec(&ClmmSwapInstruction {
+            discriminator: SWAP_DISCRIMINATOR,
+            amount: swap_params.amount_in,
+            other_amount_threshold: swap_params.min_amount_out,
+            sqrt_price_limit_x64: 0,
+            is_base_input: true,
+        })
+        .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        let instruction = Instruction {
+            program_id: clmm_program.key(),
+            accounts: &[
+                AccountMeta::readonly_signer(user_account.key()),
+                AccountMeta::readonly(amm_config.key()),
+                AccountMeta::writable(pool_account.key()),
+                AccountMeta::writable(source_token_account.key()),
+                AccountMeta::writable(destination_token_account.key()),
+                AccountMeta::writable(input_vault.key()),
+                AccountMeta::writable(output_vault.key()),
+                AccountMeta::writable(observation_state.key()),
+                AccountMeta::readonly(token_program.key()),
+                AccountMeta::writable(tick_array_0.key()),
+                // Остальные аккаунты: расширение битовой карты и следующие массивы тиков
+                AccountMeta::readonly(bitmap_extension.key()),
+                AccountMeta::writable(tick_array_1.key()),
+                AccountMeta::writable(tick_array_2.key()),
+            ],
+            data: &instruction_data,
+        };
+
+        let account_infos = [
+            user_account,
+            amm_config,
+            pool_account,
+            source_token_account,
+            destination_token_account,
+            input_vault,
+            output_vault,
+            observation_state,
+            token_program,
+            tick_array_0,
+            bitmap_extension,
+            tick_array_1,
+            tick_array_2,
+        ];
+
+        let balance_before = get_token_balance(destination_token_account)?;
+
+        invoke(&instruction, &account_infos).map_err(|_| AutoBuyerError::CpiError)?;
+
+        verify_received_amount(
+            destination_token_account,
+            balance_before,
+            swap_params.min_amount_out,
+        )
+    }
+}
+
+impl DexInterface for RaydiumClmm {
+    fn accounts_len(&self) -> usize {
+        CLMM_ACCOUNTS_LEN
+    }
+
+    fn find_trading_pair(
+        &self,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> Result<Option<TradingPair>, AutoBuyerError> {
+        if accounts.len() < 7 || accounts[5].key() != &constants::RAYDIUM_CLMM_PROGRAM_ID {
+            return Ok(None);
+        }
+
+        if accounts.len() < CLMM_ACCOUNTS_LEN {
+            msg!(
+                "Error: Insufficient accounts for Raydium CLMM. Expected {}, got {}",
+                CLMM_ACCOUNTS_LEN,
+                accounts.len()
+            );
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let pool_account = &accounts[6];
+        let (pool, amm_config) = match self.load_pool(pool_account, &accounts[7]) {
+            Ok(pool) => pool,
+            Err(_) => return Ok(None),
+        };
+
+        let is_correct_pair = (pool.token_mint_0 == *base_mint && pool.token_mint_1 == *quote_mint)
+            || (pool.token_mint_0 == *quote_mint && pool.token_mint_1 == *base_mint);
+
+        if !is_correct_pair {
+            return Ok(None);
+        }
+
+        self.validate_pool_accounts(pool, amm_config, accounts)?;
+        self.check_pool_status(pool)?;
+
+        let pool_config = PoolConfig {
+            pool_address: *pool_account.key(),
+            token_a_account: pool.token_vault_0,
+            token_b_account: pool.token_vault_1,
+            token_a_mint: pool.token_mint_0,
+            token_b_mint: pool.token_mint_1,
+            // trade_fee_rate хранится в сотых долях базисного пункта
+            fee_rate: (amm_config.trade_fee_rate / 100) as u16,
+        };
+
+        Ok(Some(TradingPair {
+            base_mint: *base_mint,
+            quote_mint: *quote_mint,
+            pool_config,
+        }))
+    }
+
+    fn calculate_swap(
+        &self,
+        trading_pair: &TradingPair,
+        amount_in: u64,
+        accounts: &[AccountInfo],
+    ) -> Result<SwapCalculation, AutoBuyerError> {
+        let (pool, amm_config) = self.load_pool(&accounts[6], &accounts[7])?;
+        let zero_for_one = Self::is_zero_for_one(pool, trading_pair);
+
+        let tick_range = self.load_tick_range(pool, accounts, zero_for_one)?;
+
+        let (amount_out, fee_amount) = math::swap_exact_in(
+            pool.sqrt_price_x64,
+            pool.liquidity,
+            amm_config.trade_fee_rate,
+            &tick_range,
+            amount_in,
+            zero_for_one,
+        )?;
+
+        let price_per_unit = PriceQ64::from_ratio(amount_out, amount_in).unwrap_or_default();
+        let spot_price = math::spot_price_from_sqrt(pool.sqrt_price_x64, zero_for_one);
+
+        Ok(SwapCalculation {
+            amount_in,
+            amount_out,
+            fee_amount,
+            price_per_unit,
+            price_impact_bps: price_per_unit.impact_bps(spot_price),
+        })
+    }
+
+    fn execute_swap(
+        &self,
+        _program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        if swap_params.mode != SwapMode::ExactIn {
+            return Err(AutoBuyerError::SwapModeNotSupported);
+        }
+
+        msg!(
+            "Executing Raydium CLMM swap: {} -> {}",
+            swap_params.amount_in,
+            swap_params.calculation.amount_out
+        );
+
+        let amount_received = self.execute_clmm_swap(accounts, swap_params)?;
+
+        msg!(
+            "Raydium CLMM swap completed successfully, received {}",
+            amount_received
+        );
+        Ok(amount_received)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::zeroed_state;
+
+    /// Пул с заданным шагом тиков и пустой битовой картой
+    fn pool(tick_spacing: u16) -> ClmmPoolState {
+        let mut pool: ClmmPoolState = zeroed_state();
+        pool.tick_spacing = tick_spacing;
+        pool
+    }
+
+    fn extension() -> Vec<u8> {
+        vec![0u8; EXTENSION_NEGATIVE_OFFSET + EXTENSION_BITMAP_ROWS * 64]
+    }
+
+    /// Отметить массив тиков в строке расширения
+    fn set_extension_bit(extension: &mut [u8], negative: bool, row: usize, position: usize) {
+        let base = if negative {
+            EXTENSION_NEGATIVE_OFFSET
+        } else {
+            EXTENSION_POSITIVE_OFFSET
+        };
+        extension[base + row * 64 + position / 64 * 8 + position % 64 / 8] |= 1 << (position % 8);
+    }
+
+    fn initialized(pool: &ClmmPoolState, extension: &[u8], start: i32) -> bool {
+        RaydiumClmm::is_tick_array_initialized(pool, extension, start).unwrap()
+    }
+
+    #[test]
+    fn pool_bitmap_is_centered_on_zero_tick() {
+        let mut pool = pool(1);
+        // Бит 512 - массив с нулевого тика, бит 511 - предыдущий массив
+        pool.tick_array_bitmap[8] = 1;
+        pool.tick_array_bitmap[7] = 1 << 63;
+        let extension = extension();
+
+        assert!(initialized(&pool, &extension, 0));
+        assert!(initialized(&pool, &extension, -60));
+        assert!(!initialized(&pool, &extension, 60));
+        assert!(!initialized(&pool, &extension, -120));
+    }
+
+    #[test]
+    fn pool_bitmap_scales_with_tick_spacing() {
+        let mut pool = pool(10);
+        pool.tick_array_bitmap[8] = 1 << 1;
+        let extension = extension();
+
+        assert!(initialized(&pool, &extension, 600));
+        assert!(!initialized(&pool, &extension, 60));
+        // Последний массив битовой карты пула
+        pool.tick_array_bitmap[15] = 1 << 63;
+        assert!(initialized(&pool, &extension, 511 * 600));
+    }
+
+    #[test]
+    fn positive_extension_offsets() {
+        let pool = pool(1);
+        let mut extension = extension();
+        // 512 массивов по 60 тиков в строке: первая строка начинается с тика 30720
+        set_extension_bit(&mut extension, false, 0, 1);
+        set_extension_bit(&mut extension, false, 1, 0);
+
+        assert!(!initialized(&pool, &extension, 30720));
+        assert!(initialized(&pool, &extension, 30780));
+        assert!(initialized(&pool, &extension, 61440));
+        assert!(!initialized(&pool, &extension, 61500));
+    }
+
+    #[test]
+    fn negative_extension_offsets() {
+        let pool = pool(1);
+        let mut extension = extension();
+        // Первая отрицательная строка покрывает [-61440, -30780] в обратном порядке
+        set_extension_bit(&mut extension, true, 0, 511);
+        set_extension_bit(&mut extension, true, 0, 0);
+
+        assert!(initialized(&pool, &extension, -30780));
+        assert!(initialized(&pool, &extension, -61440));
+        assert!(!initialized(&pool, &extension, -30840));
+        assert!(!initialized(&pool, &extension, -61500));
+    }
+
+    #[test]
+    fn extension_rows_are_bounded() {
+        let pool = pool(1);
+        let extension = extension();
+
+        assert!(RaydiumClmm::is_tick_array_initialized(&pool, &extension, 15 * 30720).is_err());
+    }
+}
@@ -0,0 +1,834 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::cell::OnceCell;
+
+use crate::{
+    dex::{
+        get_token_balance, load_anchor_account,
+        token_2022::{get_transfer_fee, get_transfer_inverse_fee, is_token_program},
+        types::{SwapMode, SwapParams},
+        verify_received_amount, DexInterface,
+    },
+    error::AutoBuyerError,
+    state::{constants, PoolConfig, PriceQ64, SwapCalculation, TradingPair},
+};
+
+/// Дискриминатор аккаунта PoolState
+const POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+
+/// Дискриминатор аккаунта AmmConfig
+const AMM_CONFIG_DISCRIMINATOR: [u8; 8] = [218, 244, 33, 104, 203, 203, 43, 111];
+
+/// Дискриминатор инструкции swap_base_input
+const SWAP_BASE_INPUT_DISCRIMINATOR: [u8; 8] = [143, 190, 90, 218, 196, 30, 51, 222];
+
+/// Дискриминатор инструкции swap_base_output
+const SWAP_BASE_OUTPUT_DISCRIMINATOR: [u8; 8] = [55, 217, 98, 86, 163, 74, 180, 173];
+
+/// Знаменатель ставок комиссий CPMM
+const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+/// Бит статуса пула, запрещающий обмены
+const POOL_STATUS_SWAP_DISABLED: u8 = 1 << 2;
+
+/// Количество аккаунтов, необходимых для обмена через CPMM
+const CPMM_ACCOUNTS_LEN: usize = 14;
+
+/// Структура для работы с Raydium CPMM (CP-Swap)
+///
+/// Аккаунты (после общих 0-4):
+/// 5. `[]` Программа Raydium CPMM
+/// 6. `[writable]` PoolState
+/// 7. `[]` Authority пула
+/// 8. `[]` AmmConfig
+/// 9. `[writable]` Хранилище токена 0
+/// 10. `[writable]` Хранилище токена 1
+/// 11. `[]` Программа токена 0
+/// 12. `[]` Программа токена 1
+/// 13. `[writable]` Observation state
+pub struct RaydiumCpmm {
+    /// Состояние пула и его конфигурация, загруженные в рамках текущей инструкции
+    pool: OnceCell<(Pubkey, CpmmPoolState, AmmConfig)>,
+}
+
+/// Состояние пула CPMM
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct CpmmPoolState {
+    pub amm_config: Pubkey,
+    pub pool_creator: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub lp_mint: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_program: Pubkey,
+    pub token_1_program: Pubkey,
+    pub observation_key: Pubkey,
+    pub auth_bump: u8,
+    pub status: u8,
+    pub lp_mint_decimals: u8,
+    pub mint_0_decimals: u8,
+    pub mint_1_decimals: u8,
+    pub lp_supply: u64,
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub fund_fees_token_0: u64,
+    pub fund_fees_token_1: u64,
+    pub open_time: u64,
+    pub recent_epoch: u64,
+    pub creator_fee_on: u8,
+    pub enable_creator_fee: bool,
+    pub padding1: [u8; 6],
+    pub creator_fees_token_0: u64,
+    pub creator_fees_token_1: u64,
+}
+
+/// Конфигурация комиссий CPMM
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct AmmConfig {
+    pub bump: u8,
+    pub disable_create_pool: bool,
+    pub index: u16,
+    pub trade_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+    pub create_pool_fee: u64,
+    pub protocol_owner: Pubkey,
+    pub fund_owner: Pubkey,
+    pub creator_fee_rate: u64,
+}
+
+/// Токен, с которого взимается комиссия создателя пула
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CreatorFeeOn {
+    /// С входного токена в обоих направлениях
+    BothToken,
+    /// Только с токена 0
+    OnlyToken0,
+    /// Только с токена 1
+    OnlyToken1,
+}
+
+impl CreatorFeeOn {
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::BothToken),
+            1 => Some(Self::OnlyToken0),
+            2 => Some(Self::OnlyToken1),
+            _ => None,
+        }
+    }
+}
+
+/// Результат расчета обмена CPMM
+#[derive(Debug, Clone, Copy)]
+struct CpmmSwapResult {
+    /// Сумма, списываемая с пользователя (с комиссией перевода Token-2022)
+    amount_in: u64,
+    /// Сумма, получаемая пользователем (за вычетом комиссии перевода Token-2022)
+    amount_out: u64,
+    /// Торговая комиссия во входном токене
+    trade_fee: u64,
+    /// Комиссия создателя пула (во входном или выходном токене)
+    creator_fee: u64,
+    /// Доля протокола в торговой комиссии
+    protocol_fee: u64,
+    /// Доля фонда в торговой комиссии
+    fund_fee: u64,
+}
+
+/// Инструкция обмена CPMM
+#[derive(BorshSerialize)]
+struct CpmmSwapInstruction {
+    discriminator: [u8; 8],
+    /// `amount_in` для swap_base_input, `max_amount_in` для swap_base_output
+    amount_0: u64,
+    /// `minimum_amount_out` для swap_base_input, `amount_out` для swap_base_output
+    amount_1: u64,
+}
+
+impl RaydiumCpmm {
+    /// Создать новый экземпляр Raydium CPMM
+    pub fn new() -> Self {
+        Self {
+            pool: OnceCell::new(),
+        }
+    }
+
+    /// Загрузить состояние пула и конфигурацию комиссий
+    fn load_pool(
+        &self,
+        pool_account: &AccountInfo,
+        amm_config_account: &AccountInfo,
+    ) -> Result<(&CpmmPoolState, &AmmConfig), AutoBuyerError> {
+        if let Some((pool_address, pool, amm_config)) = self.pool.get() {
+            if pool_address != pool_account.key() {
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+            return Ok((pool, amm_config));
+        }
+
+        let pool: CpmmPoolState = load_anchor_account(
+            pool_account,
+            &constants::RAYDIUM_CPMM_PROGRAM_ID,
+            &POOL_STATE_DISCRIMINATOR,
+        )?;
+
+        if amm_config_account.key() != &pool.amm_config {
+            msg!("Error: AmmConfig does not match CPMM pool");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+        let amm_config: AmmConfig = load_anchor_account(
+            amm_config_account,
+            &constants::RAYDIUM_CPMM_PROGRAM_ID,
+            &AMM_CONFIG_DISCRIMINATOR,
+        )?;
+
+        let (_, pool, amm_config) = self
+            .pool
+            .get_or_init(|| (*pool_account.key(), pool, amm_config));
+        Ok((pool, amm_config))
+    }
+
+    /// Проверить, что переданные аккаунты соответствуют пулу
+    fn validate_pool_accounts(
+        &self,
+        pool: &CpmmPoolState,
+        accounts: &[AccountInfo],
+    ) -> Result<(), AutoBuyerError> {
+        if accounts[9].key() != &pool.token_0_vault || accounts[10].key() != &pool.token_1_vault {
+            msg!("Error: Pool vaults do not match CPMM pool");
+            return Err(AutoBuyerError::InvalidPoolVault);
+        }
+
+        if accounts[11].key() != &pool.token_0_program
+            || accounts[12].key() != &pool.token_1_program
+            || !is_token_program(&pool.token_0_program)
+            || !is_token_program(&pool.token_1_program)
+        {
+            msg!("Error: Invalid token program for CPMM pool");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if accounts[13].key() != &pool.observation_key {
+            msg!("Error: Observation state does not match CPMM pool");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        Ok(())
+    }
+
+    /// Проверить, что пул открыт для обменов
+    fn check_pool_status(&self, pool: &CpmmPoolState) -> Result<(), AutoBuyerError> {
+        if pool.status & POOL_STATUS_SWAP_DISABLED != 0 {
+            msg!("Error: Pool status {} does not allow swaps", pool.status);
+            return Err(AutoBuyerError::PoolNotTradable);
+        }
+
+        let clock = Clock::get().map_err(|_| AutoBuyerError::InvalidParameters)?;
+        if (clock.unix_timestamp as u64) <= pool.open_time {
+            msg!(
+                "Error: Pool opens at {}, current time {}",
+                pool.open_time,
+                clock.unix_timestamp
+            );
+            return Err(AutoBuyerError::PoolNotOpen);
+        }
+
+        Ok(())
+    }
+
+    /// Направление обмена: true, если входной токен - токен 0 пула
+    fn is_zero_for_one(pool: &CpmmPoolState, trading_pair: &TradingPair) -> bool {
+        trading_pair.quote_mint == pool.token_0_mint
+    }
+
+    /// Минты входного и выходного токена из общих аккаунтов 3-4
+    fn mint_accounts<'a>(
+        trading_pair: &TradingPair,
+        accounts: &'a [AccountInfo],
+    ) -> (&'a AccountInfo, &'a AccountInfo) {
+        if accounts[4].key() == &trading_pair.quote_mint {
+            (&accounts[4], &accounts[3])
+        } else {
+            (&accounts[3], &accounts[4])
+        }
+    }
+
+    /// Резервы пула за вычетом накопленных комиссий протокола, фонда и создателя
+    fn get_reserves(
+        &self,
+        pool: &CpmmPoolState,
+        trading_pair: &TradingPair,
+        accounts: &[AccountInfo],
+    ) -> Result<(u64, u64), AutoBuyerError> {
+        let vault_0 = get_token_balance(&accounts[9])?;
+        let vault_1 = get_token_balance(&accounts[10])?;
+
+        let reserve_0 = vault_0
+            .checked_sub(pool.protocol_fees_token_0)
+            .and_then(|x| x.checked_sub(pool.fund_fees_token_0))
+            .and_then(|x| x.checked_sub(pool.creator_fees_token_0))
+            .ok_or(AutoBuyerError::MathOverflow)?;
+        let reserve_1 = vault_1
+            .checked_sub(pool.protocol_fees_token_1)
+            .and_then(|x| x.checked_sub(pool.fund_fees_token_1))
+            .and_then(|x| x.checked_sub(pool.creator_fees_token_1))
+            .ok_or(AutoBuyerError::MathOverflow)?;
+
+        if reserve_0 == 0 || reserve_1 == 0 {
+            return Err(AutoBuyerError::InsufficientLiquidity);
+        }
+
+        if Self::is_zero_for_one(pool, trading_pair) {
+            Ok((reserve_0, reserve_1))
+        } else {
+            Ok((reserve_1, reserve_0))
+        }
+    }
+
+    /// Ставка комиссии создателя и признак ее взимания с входного токена
+    fn creator_fee_params(
+        pool: &CpmmPoolState,
+        amm_config: &AmmConfig,
+        zero_for_one: bool,
+    ) -> Result<(u64, bool), AutoBuyerError> {
+        let creator_fee_rate = if pool.enable_creator_fee {
+            amm_config.creator_fee_rate
+        } else {
+            0
+        };
+
+        let is_creator_fee_on_input = match CreatorFeeOn::from_u8(pool.creator_fee_on) {
+            Some(CreatorFeeOn::BothToken) => true,
+            Some(CreatorFeeOn::OnlyToken0) => zero_for_one,
+            Some(CreatorFeeOn::OnlyToken1) => !zero_for_one,
+            None => return Err(AutoBuyerError::InvalidParameters),
+        };
+
+        Ok((creator_fee_rate, is_creator_fee_on_input))
+    }
+
+    /// Проверить корректность ставок комиссий
+    fn validate_fee_rates(
+        amm_config: &AmmConfig,
+        creator_fee_rate: u64,
+    ) -> Result<(), AutoBuyerError> {
+        let total_fee_rate = amm_config
+            .trade_fee_rate
+            .checked_add(creator_fee_rate)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+
+        if total_fee_rate >= FEE_RATE_DENOMINATOR
+            || amm_config.protocol_fee_rate > FEE_RATE_DENOMINATOR
+            || amm_config.fund_fee_rate > FEE_RATE_DENOMINATOR
+        {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        Ok(())
+    }
+
+    /// Комиссия с округлением вверх
+    fn ceil_fee(amount: u64, fee_rate: u64) -> Option<u64> {
+        let fee = (amount as u128)
+            .checked_mul(fee_rate as u128)?
+            .div_ceil(FEE_RATE_DENOMINATOR as u128);
+        u64::try_from(fee).ok()
+    }
+
+    /// Доля торговой комиссии с округлением вниз
+    fn floor_fee(amount: u64, fee_rate: u64) -> Option<u64> {
+        let fee = (amount as u128).checked_mul(fee_rate as u128)? / FEE_RATE_DENOMINATOR as u128;
+        u64::try_from(fee).ok()
+    }
+
+    /// Сумма до удержания комиссии, после удержания которой остается `amount`
+    fn pre_fee_amount(amount: u64, fee_rate: u64) -> Option<u64> {
+        if fee_rate == 0 {
+            return Some(amount);
+        }
+
+        let pre_fee = (amount as u128)
+            .checked_mul(FEE_RATE_DENOMINATOR as u128)?
+            .div_ceil((FEE_RATE_DENOMINATOR - fee_rate) as u128);
+        u64::try_from(pre_fee).ok()
+    }
+
+    /// Рассчитать обмен с фиксированным входом
+    fn calculate_base_input(
+        &self,
+        pool: &CpmmPoolState,
+        amm_config: &AmmConfig,
+        trading_pair: &TradingPair,
+        amount_in: u64,
+        accounts: &[AccountInfo],
+    ) -> Result<(CpmmSwapResult, u64, u64), AutoBuyerError> {
+        let zero_for_one = Self::is_zero_for_one(pool, trading_pair);
+        let (creator_fee_rate, is_creator_fee_on_input) =
+            Self::creator_fee_params(pool, amm_config, zero_for_one)?;
+        Self::validate_fee_rates(amm_config, creator_fee_rate)?;
+
+        let (reserve_in, reserve_out) = self.get_reserves(pool, trading_pair, accounts)?;
+        let (input_mint, output_mint) = Self::mint_accounts(trading_pair, accounts);
+
+        // Пул получает сумму за вычетом комиссии перевода Token-2022
+        let transfer_fee_in = get_transfer_fee(input_mint, amount_in)?;
+        let actual_amount_in = amount_in
+            .checked_sub(transfer_fee_in)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+
+        let (trade_fee, mut creator_fee, amount_in_less_fees) = if is_creator_fee_on_input {
+            let total_fee = Self::ceil_fee(
+                actual_amount_in,
+                amm_config.trade_fee_rate + creator_fee_rate,
+            )
+            .ok_or(AutoBuyerError::MathOverflow)?;
+            let creator_fee = (total_fee as u128 * creator_fee_rate as u128)
+                .checked_div((amm_config.trade_fee_rate + creator_fee_rate) as u128)
+                .unwrap_or(0) as u64;
+            (
+                total_fee - creator_fee,
+                creator_fee,
+                actual_amount_in - total_fee,
+            )
+        } else {
+            let trade_fee = Self::ceil_fee(actual_amount_in, amm_config.trade_fee_rate)
+                .ok_or(AutoBuyerError::MathOverflow)?;
+            (trade_fee, 0, actual_amount_in - trade_fee)
+        };
+
+        let numerator = (amount_in_less_fees as u128)
+            .checked_mul(reserve_out as u128)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+        let denominator = (reserve_in as u128)
+            .checked_add(amount_in_less_fees as u128)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+        let mut amount_out_swapped = (numerator / denominator) as u64;
+
+        if !is_creator_fee_on_input {
+            creator_fee = Self::ceil_fee(amount_out_swapped, creator_fee_rate)
+                .ok_or(AutoBuyerError::MathOverflow)?;
+            amount_out_swapped -= creator_fee;
+        }
+
+        // Пользователь получает сумму за вычетом комиссии перевода Token-2022
+        let transfer_fee_out = get_transfer_fee(output_mint, amount_out_swapped)?;
+
+        let result = CpmmSwapResult {
+            amount_in,
+            amount_out: amount_out_swapped - transfer_fee_out,
+            trade_fee,
+            creator_fee,
+            protocol_fee: Self::floor_fee(trade_fee, amm_config.protocol_fee_rate)
+                .ok_or(AutoBuyerError::MathOverflow)?,
+            fund_fee: Self::floor_fee(trade_fee, amm_config.fund_fee_rate)
+                .ok_or(AutoBuyerError::MathOverflow)?,
+        };
+
+        Ok((result, reserve_in, reserve_out))
+    }
+
+    /// Рассчитать обмен с фиксированным выходом
+    fn calculate_base_output(
+        &self,
+        pool: &CpmmPoolState,
+        amm_config: &AmmConfig,
+        trading_pair: &TradingPair,
+        amount_out: u64,
+        accounts: &[AccountInfo],
+    ) -> Result<(CpmmSwapResult, u64, u64), AutoBuyerError> {
+        let zero_for_one = Self::is_zero_for_one(pool, trading_pair);
+        let (creator_fee_rate, is_creator_fee_on_input) =
+            Self::creator_fee_params(pool, amm_config, zero_for_one)?;
+        Self::validate_fee_rates(amm_config, creator_fee_rate)?;
+
+        let (reserve_in, reserve_out) = self.get_reserves(pool, trading_pair, accounts)?;
+        let (input_mint, output_mint) = Self::mint_accounts(trading_pair, accounts);
+
+        // Пул должен отправить больше на сумму комиссии перевода Token-2022
+        let transfer_fee_out = get_transfer_inverse_fee(output_mint, amount_out)?;
+        let amount_out_with_transfer_fee = amount_out
+            .checked_add(transfer_fee_out)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+
+        let (actual_amount_out, creator_fee_out) = if is_creator_fee_on_input {
+            (amount_out_with_transfer_fee, 0)
+        } else {
+            let pre_fee = Self::pre_fee_amount(amount_out_with_transfer_fee, creator_fee_rate)
+                .ok_or(AutoBuyerError::MathOverflow)?;
+            (pre_fee, pre_fee - amount_out_with_transfer_fee)
+        };
+
+        if actual_amount_out >= reserve_out {
+            return Err(AutoBuyerError::InsufficientLiquidity);
+        }
+
+        let numerator = (reserve_in as u128)
+            .checked_mul(actual_amount_out as u128)
+            .ok_or(AutoBuyerError::MathOverflow)?;
+        let amount_in_swapped =
+            u64::try_from(numerator.div_ceil((reserve_out - actual_amount_out) as u128))
+                .map_err(|_| AutoBuyerError::MathOverflow)?;
+
+        let (source_amount, trade_fee, creator_fee) = if is_creator_fee_on_input {
+            let total_fee_rate = amm_config.trade_fee_rate + creator_fee_rate;
+            let source_amount = Self::pre_fee_amount(amount_in_swapped, total_fee_rate)
+                .ok_or(AutoBuyerError::MathOverflow)?;
+            let total_fee = source_amount - amount_in_swapped;
+            let creator_fee = (total_fee as u128 * creator_fee_rate as u128)
+                .checked_div(total_fee_rate as u128)
+                .unwrap_or(0) as u64;
+            (source_amount, total_fee - creator_fee, creator_fee)
+        } else {
+            let source_amount = Self::pre_fee_amount(amount_in_swapped, amm_config.trade_fee_rate)
+                .ok_or(AutoBuyerError::MathOverflow)?;
+            (
+                source_amount,
+                source_amount - amount_in_swapped,
+                creator_fee_out,
+            )
+        };
+
+        // Пользователь доплачивает комиссию перевода Token-2022 входного токена
+        let transfer_fee_in = get_transfer_inverse_fee(input_mint, source_amount)?;
+
+        let result = CpmmSwapResult {
+            amount_in: source_amount
+                .checked_add(transfer_fee_in)
+                .ok_or(AutoBuyerError::MathOverflow)?,
+            amount_out,
+            trade_fee,
+            creator_fee,
+            protocol_fee: Self::floor_fee(trade_fee, amm_config.protocol_fee_rate)
+                .ok_or(AutoBuyerError::MathOverflow)?,
+            fund_fee: Self::floor_fee(trade_fee, amm_config.fund_fee_rate)
+                .ok_or(AutoBuyerError::MathOverflow)?,
+        };
+
+        Ok((result, reserve_in, reserve_out))
+    }
+
+    /// Сформировать расчет обмена для вызывающей стороны
+    fn build_calculation(
+        result: &CpmmSwapResult,
+        reserve_in: u64,
+        reserve_out: u64,
+    ) -> SwapCalculation {
+        msg!(
+            "CPMM fees: trade {}, creator {}, protocol {}, fund {}",
+            result.trade_fee,
+            result.creator_fee,
+            result.protocol_fee,
+            result.fund_fee
+        );
+
+        let price_per_unit =
+            PriceQ64::from_ratio(result.amount_out, result.amount_in).unwrap_or_default();
+        let spot_price = PriceQ64::from_ratio(reserve_out, reserve_in).unwrap_or_default();
+
+        SwapCalculation {
+            amount_in: result.amount_in,
+            amount_out: result.amount_out,
+            fee_amount: result.trade_fee + result.creator_fee,
+            price_per_unit,
+            price_impact_bps: price_per_unit.impact_bps(spot_price),
+        }
+    }
+
+    /// Выполнить обмен через CPI
+    fn execute_cpmm_swap(
+        &self,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        let (pool, _) = self.load_pool(&accounts[6], &accounts[8])?;
+        let zero_for_one = Self::is_zero_for_one(pool, &swap_params.trading_pair);
+
+        let user_account = &accounts[0];
+        let (source_token_account, destination_token_account) =
+            swap_params.direction.user_token_accounts(accounts);
+        let (input_mint, output_mint) = Self::mint_accounts(&swap_params.trading_pair, accounts);
+        let cpmm_program = &accounts[5];
+        let pool_account = &accounts[6];
+        let authority = &accounts[7];
+        let amm_config = &accounts[8];
+        let observation_state = &accounts[13];
+        let (input_vault, output_vault, input_token_program, output_token_program) = if zero_for_one
+        {
+            (&accounts[9], &accounts[10], &accounts[11], &accounts[12])
+        } else {
+            (&accounts[10], &accounts[9], &accounts[12], &accounts[11])
+        };
+
+        let discriminator = match swap_params.mode {
+            SwapMode::ExactIn => SWAP_BASE_INPUT_DISCRIMINATOR,
+            SwapMode::ExactOut => SWAP_BASE_OUTPUT_DISCRIMINATOR,
+        };
+        let instruction_data = borsh::to_vec(&CpmmSwapInstruction {
+            discriminator,
+            amount_0: swap_params.amount_in,
+            amount_1: swap_params.min_amount_out,
+        })
+        .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        let instruction = Instruction {
+            program_id: cpmm_program.key(),
+            accounts: &[
+                AccountMeta::readonly_signer(user_account.key()),
+                AccountMeta::readonly(authority.key()),
+                AccountMeta::readonly(amm_config.key()),
+                AccountMeta::writable(pool_account.key()),
+                AccountMeta::writable(source_token_account.key()),
+                AccountMeta::writable(destination_token_account.key()),
+                AccountMeta::writable(input_vault.key()),
+                AccountMeta::writable(output_vault.key()),
+                AccountMeta::readonly(input_token_program.key()),
+                AccountMeta::readonly(output_token_program.key()),
+                AccountMeta::readonly(input_mint.key()),
+                AccountMeta::readonly(output_mint.key()),
+                AccountMeta::writable(observation_state.key()),
+            ],
+            data: &instruction_data,
+        };
+
+        let account_infos = [
+            user_account,
+            authority,
+            amm_config,
+            pool_account,
+            source_token_account,
+            destination_token_account,
+            input_vault,
+            output_vault,
+            input_token_program,
+            output_token_program,
+            input_mint,
+            output_mint,
+            observation_state,
+        ];
+
+        let balance_before = get_token_balance(destination_token_account)?;
+
+        invoke(&instruction, &account_infos).map_err(|_| AutoBuyerError::CpiError)?;
+
+        verify_received_amount(
+            destination_token_account,
+            balance_before,
+            swap_params.min_amount_out,
+        )
+    }
+}
+
+impl DexInterface for RaydiumCpmm {
+    fn accounts_len(&self) -> usize {
+        CPMM_ACCOUNTS_LEN
+    }
+
+    fn find_trading_pair(
+        &self,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> Result<Option<TradingPair>, AutoBuyerError> {
+        if accounts.len() < 7 || accounts[5].key() != &constants::RAYDIUM_CPMM_PROGRAM_ID {
+            return Ok(None);
+        }
+
+        if accounts.len() < CPMM_ACCOUNTS_LEN {
+            msg!(
+                "Error: Insufficient accounts for Raydium CPMM. Expected {}, got {}",
+                CPMM_ACCOUNTS_LEN,
+                accounts.len()
+            );
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let pool_account = &accounts[6];
+        let (pool, amm_config) = match self.load_pool(pool_account, &accounts[8]) {
+            Ok(pool) => pool,
+            Err(_) => return Ok(None),
+        };
+
+        let is_correct_pair = (pool.token_0_mint == *base_mint && pool.token_1_mint == *quote_mint)
+            || (pool.token_0_mint == *quote_mint && pool.token_1_mint == *base_mint);
+
+        if !is_correct_pair {
+            return Ok(None);
+        }
+
+        self.validate_pool_accounts(pool, accounts)?;
+        self.check_pool_status(pool)?;
+
+        let pool_config = PoolConfig {
+            pool_address: *pool_account.key(),
+            token_a_account: pool.token_0_vault,
+            token_b_account: pool.token_1_vault,
+            token_a_mint: pool.token_0_mint,
+            token_b_mint: pool.token_1_mint,
+            // trade_fee_rate хранится в миллионных долях
+            fee_rate: (amm_config.trade_fee_rate / 100) as u16,
+        };
+
+        Ok(Some(TradingPair {
+            base_mint: *base_mint,
+            quote_mint: *quote_mint,
+            pool_config,
+        }))
+    }
+
+    fn calculate_swap(
+        &self,
+        trading_pair: &TradingPair,
+        amount_in: u64,
+        accounts: &[AccountInfo],
+    ) -> Result<SwapCalculation, AutoBuyerError> {
+        let (pool, amm_config) = self.load_pool(&accounts[6], &accounts[8])?;
+
+        let (result, reserve_in, reserve_out) =
+            self.calculate_base_input(pool, amm_config, trading_pair, amount_in, accounts)?;
+
+        Ok(Self::build_calculation(&result, reserve_in, reserve_out))
+    }
+
+    fn calculate_swap_exact_out(
+        &self,
+        trading_pair: &TradingPair,
+        amount_out: u64,
+        accounts: &[AccountInfo],
+    ) -> Result<SwapCalculation, AutoBuyerError> {
+        let (pool, amm_config) = self.load_pool(&accounts[6], &accounts[8])?;
+
+        let (result, reserve_in, reserve_out) =
+            self.calculate_base_output(pool, amm_config, trading_pair, amount_out, accounts)?;
+
+        Ok(Self::build_calculation(&result, reserve_in, reserve_out))
+    }
+
+    fn execute_swap(
+        &self,
+        _program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        msg!(
+            "Executing Raydium CPMM swap: {} -> {}",
+            swap_params.amount_in,
+            swap_params.calculation.amount_out
+        );
+
+        let amount_received = self.execute_cpmm_swap(accounts, swap_params)?;
+
+        msg!(
+            "Raydium CPMM swap completed successfully, received {}",
+            amount_received
+        );
+        Ok(amount_received)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::zeroed_state;
+
+    /// Пул с заданным режимом комиссии создателя, остальные поля нулевые
+    fn pool(creator_fee_on: u8, enable_creator_fee: bool) -> CpmmPoolState {
+        let mut pool: CpmmPoolState = zeroed_state();
+        pool.creator_fee_on = creator_fee_on;
+        pool.enable_creator_fee = enable_creator_fee;
+        pool
+    }
+
+    /// Конфигурация с торговой комиссией 0,25% и комиссией создателя `creator_fee_rate`
+    fn amm_config(creator_fee_rate: u64) -> AmmConfig {
+        let mut amm_config: AmmConfig = zeroed_state();
+        amm_config.trade_fee_rate = 2_500;
+        amm_config.protocol_fee_rate = 120_000;
+        amm_config.fund_fee_rate = 40_000;
+        amm_config.creator_fee_rate = creator_fee_rate;
+        amm_config
+    }
+
+    #[test]
+    fn creator_fee_side_follows_pool_mode() {
+        let amm_config = amm_config(1_000);
+
+        // (режим, направление 0 -> 1, взимается с входного токена)
+        let vectors = [
+            (0, true, true),
+            (0, false, true),
+            (1, true, true),
+            (1, false, false),
+            (2, true, false),
+            (2, false, true),
+        ];
+        for (creator_fee_on, zero_for_one, on_input) in vectors {
+            assert_eq!(
+                RaydiumCpmm::creator_fee_params(
+                    &pool(creator_fee_on, true),
+                    &amm_config,
+                    zero_for_one
+                )
+                .unwrap(),
+                (1_000, on_input),
+                "mode {creator_fee_on}, zero for one {zero_for_one}"
+            );
+        }
+    }
+
+    #[test]
+    fn disabled_creator_fee_has_zero_rate() {
+        let (creator_fee_rate, _) =
+            RaydiumCpmm::creator_fee_params(&pool(0, false), &amm_config(1_000), true).unwrap();
+        assert_eq!(creator_fee_rate, 0);
+    }
+
+    #[test]
+    fn unknown_creator_fee_mode_is_rejected() {
+        assert!(matches!(
+            RaydiumCpmm::creator_fee_params(&pool(3, true), &amm_config(1_000), true),
+            Err(AutoBuyerError::InvalidParameters)
+        ));
+    }
+
+    #[test]
+    fn total_fee_rate_must_stay_below_denominator() {
+        assert!(RaydiumCpmm::validate_fee_rates(&amm_config(0), 997_499).is_ok());
+        assert!(matches!(
+            RaydiumCpmm::validate_fee_rates(&amm_config(0), 997_500),
+            Err(AutoBuyerError::InvalidParameters)
+        ));
+        assert!(matches!(
+            RaydiumCpmm::validate_fee_rates(&amm_config(0), u64::MAX),
+            Err(AutoBuyerError::MathOverflow)
+        ));
+    }
+
+    #[test]
+    fn fees_round_like_cp_swap() {
+        assert_eq!(RaydiumCpmm::ceil_fee(1_000_000_000, 2_500), Some(2_500_000));
+        assert_eq!(RaydiumCpmm::ceil_fee(1, 2_500), Some(1));
+        assert_eq!(RaydiumCpmm::ceil_fee(1, 0), Some(0));
+        assert_eq!(RaydiumCpmm::floor_fee(2_500_000, 120_000), Some(300_000));
+        assert_eq!(RaydiumCpmm::floor_fee(8, 120_000), Some(0));
+    }
+
+    #[test]
+    fn pre_fee_amount_inverts_ceil_fee() {
+        assert_eq!(RaydiumCpmm::pre_fee_amount(997_500, 2_500), Some(1_000_000));
+        assert_eq!(RaydiumCpmm::pre_fee_amount(1, 2_500), Some(2));
+        assert_eq!(RaydiumCpmm::pre_fee_amount(123_456, 0), Some(123_456));
+        assert_eq!(RaydiumCpmm::pre_fee_amount(u64::MAX, 2_500), None);
+    }
+}
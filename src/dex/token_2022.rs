@@ -0,0 +1,246 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    sysvars::{clock::Clock, Sysvar},
+};
+
+use crate::{error::AutoBuyerError, state::constants};
+
+/// Смещение типа аккаунта в данных Token-2022 (после базового размера аккаунта)
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+
+/// Тип аккаунта Token-2022 для минта
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+/// Тип расширения TransferFeeConfig
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+
+/// Размер данных расширения TransferFeeConfig
+const TRANSFER_FEE_CONFIG_LEN: usize = 108;
+
+/// Максимальная комиссия перевода в базисных пунктах
+const MAX_FEE_BASIS_POINTS: u16 = 10000;
+
+/// Комиссия перевода, действующая с указанной эпохи
+#[derive(Debug, Clone, Copy, Default)]
+struct TransferFee {
+    epoch: u64,
+    maximum_fee: u64,
+    transfer_fee_basis_points: u16,
+}
+
+impl TransferFee {
+    fn parse(data: &[u8]) -> Self {
+        Self {
+            epoch: u64::from_le_bytes(data[0..8].try_into().unwrap()),
+            maximum_fee: u64::from_le_bytes(data[8..16].try_into().unwrap()),
+            transfer_fee_basis_points: u16::from_le_bytes(data[16..18].try_into().unwrap()),
+        }
+    }
+
+    /// Комиссия, удерживаемая при переводе `amount`
+    fn calculate_fee(&self, amount: u64) -> Option<u64> {
+        if self.transfer_fee_basis_points == 0 || amount == 0 {
+            return Some(0);
+        }
+
+        let fee = (amount as u128)
+            .checked_mul(self.transfer_fee_basis_points as u128)?
+            .div_ceil(MAX_FEE_BASIS_POINTS as u128);
+
+        Some(u64::try_from(fee).ok()?.min(self.maximum_fee))
+    }
+
+    /// Комиссия, которую нужно добавить к переводу, чтобы получатель получил `post_fee_amount`
+    fn calculate_inverse_fee(&self, post_fee_amount: u64) -> Option<u64> {
+        if self.transfer_fee_basis_points == 0 || post_fee_amount == 0 {
+            return Some(0);
+        }
+
+        if self.transfer_fee_basis_points == MAX_FEE_BASIS_POINTS {
+            return Some(self.maximum_fee);
+        }
+
+        let pre_fee_amount = (post_fee_amount as u128)
+            .checked_mul(MAX_FEE_BASIS_POINTS as u128)?
+            .div_ceil((MAX_FEE_BASIS_POINTS - self.transfer_fee_basis_points) as u128);
+        let fee = u64::try_from(pre_fee_amount - post_fee_amount as u128).ok()?;
+
+        Some(fee.min(self.maximum_fee))
+    }
+}
+
+/// Проверить, что программа является программой токенов SPL или Token-2022
+pub fn is_token_program(program_id: &[u8; 32]) -> bool {
+    program_id == &constants::TOKEN_PROGRAM_ID || program_id == &constants::TOKEN_2022_PROGRAM_ID
+}
+
+/// Найти комиссию перевода минта Token-2022, действующую в текущей эпохе
+fn current_transfer_fee(mint: &AccountInfo) -> Result<Option<TransferFee>, AutoBuyerError> {
+    if mint.owner() != &constants::TOKEN_2022_PROGRAM_ID {
+        return Ok(None);
+    }
+
+    let data = mint
+        .try_borrow_data()
+        .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+    if data.len() <= ACCOUNT_TYPE_OFFSET || data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_MINT {
+        return Ok(None);
+    }
+
+    // Обход TLV-расширений: тип (u16), длина (u16), значение
+    let mut offset = ACCOUNT_TYPE_OFFSET + 1;
+    while offset + 4 <= data.len() {
+        let extension_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let value_start = offset + 4;
+
+        if value_start + length > data.len() {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if extension_type == EXTENSION_TRANSFER_FEE_CONFIG {
+            if length < TRANSFER_FEE_CONFIG_LEN {
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+
+            let config = &data[value_start..value_start + TRANSFER_FEE_CONFIG_LEN];
+            let older = TransferFee::parse(&config[72..90]);
+            let newer = TransferFee::parse(&config[90..108]);
+
+            let clock = Clock::get().map_err(|_| AutoBuyerError::InvalidParameters)?;
+            let fee = if clock.epoch >= newer.epoch {
+                newer
+            } else {
+                older
+            };
+            return Ok(Some(fee));
+        }
+
+        offset = value_start + length;
+    }
+
+    Ok(None)
+}
+
+/// Комиссия перевода Token-2022, удерживаемая при переводе `amount`
+///
+/// Для минтов SPL Token и минтов без расширения комиссии возвращает 0.
+pub fn get_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64, AutoBuyerError> {
+    match current_transfer_fee(mint)? {
+        Some(fee) => fee
+            .calculate_fee(amount)
+            .ok_or(AutoBuyerError::MathOverflow),
+        None => Ok(0),
+    }
+}
+
+/// Комиссия перевода Token-2022, которую нужно добавить, чтобы получатель получил `amount`
+pub fn get_transfer_inverse_fee(mint: &AccountInfo, amount: u64) -> Result<u64, AutoBuyerError> {
+    match current_transfer_fee(mint)? {
+        Some(fee) => fee
+            .calculate_inverse_fee(amount)
+            .ok_or(AutoBuyerError::MathOverflow),
+        None => Ok(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Сумма, комиссия с которой равна одной ставке в базисных пунктах
+    const ONE: u64 = MAX_FEE_BASIS_POINTS as u64;
+
+    /// Комиссия 1 б.п. с потолком 5000 (векторы тестов spl-token-2022)
+    const ONE_BASIS_POINT: TransferFee = TransferFee {
+        epoch: 0,
+        maximum_fee: 5_000,
+        transfer_fee_basis_points: 1,
+    };
+
+    #[test]
+    fn fee_is_capped_by_maximum() {
+        let maximum_fee = ONE_BASIS_POINT.maximum_fee;
+        assert_eq!(ONE_BASIS_POINT.calculate_fee(u64::MAX), Some(maximum_fee));
+        assert_eq!(
+            ONE_BASIS_POINT.calculate_fee(maximum_fee * ONE),
+            Some(maximum_fee)
+        );
+        assert_eq!(
+            ONE_BASIS_POINT.calculate_fee(maximum_fee * ONE + 1),
+            Some(maximum_fee)
+        );
+        assert_eq!(
+            ONE_BASIS_POINT.calculate_fee(maximum_fee * ONE - 1),
+            Some(maximum_fee)
+        );
+    }
+
+    #[test]
+    fn fee_rounds_up_to_minimum() {
+        assert_eq!(ONE_BASIS_POINT.calculate_fee(1), Some(1));
+        assert_eq!(ONE_BASIS_POINT.calculate_fee(2), Some(1));
+        assert_eq!(ONE_BASIS_POINT.calculate_fee(ONE), Some(1));
+        assert_eq!(ONE_BASIS_POINT.calculate_fee(ONE + 1), Some(2));
+        assert_eq!(ONE_BASIS_POINT.calculate_fee(0), Some(0));
+    }
+
+    #[test]
+    fn zero_rate_or_zero_maximum_charges_nothing() {
+        let zero_rate = TransferFee {
+            epoch: 0,
+            maximum_fee: u64::MAX,
+            transfer_fee_basis_points: 0,
+        };
+        let zero_maximum = TransferFee {
+            epoch: 0,
+            maximum_fee: 0,
+            transfer_fee_basis_points: MAX_FEE_BASIS_POINTS,
+        };
+
+        for transfer_fee in [zero_rate, zero_maximum] {
+            for amount in [0, 1, ONE, u64::MAX] {
+                assert_eq!(transfer_fee.calculate_fee(amount), Some(0), "{amount}");
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_fee_is_capped_by_maximum() {
+        let maximum_fee = ONE_BASIS_POINT.maximum_fee;
+        for post_fee_amount in [
+            u64::MAX - maximum_fee,
+            maximum_fee * ONE - maximum_fee,
+            maximum_fee * ONE - maximum_fee + 1,
+            maximum_fee * ONE - maximum_fee - 1,
+        ] {
+            assert_eq!(
+                ONE_BASIS_POINT.calculate_inverse_fee(post_fee_amount),
+                Some(maximum_fee),
+                "{post_fee_amount}"
+            );
+        }
+    }
+
+    #[test]
+    fn inverse_fee_rounds_up_to_minimum() {
+        assert_eq!(ONE_BASIS_POINT.calculate_inverse_fee(1), Some(1));
+        assert_eq!(ONE_BASIS_POINT.calculate_inverse_fee(2), Some(1));
+        assert_eq!(ONE_BASIS_POINT.calculate_inverse_fee(ONE - 1), Some(1));
+        assert_eq!(ONE_BASIS_POINT.calculate_inverse_fee(ONE), Some(2));
+        assert_eq!(ONE_BASIS_POINT.calculate_inverse_fee(0), Some(0));
+    }
+
+    #[test]
+    fn full_rate_inverse_fee_is_maximum() {
+        // Как в CP-Swap: при ставке 100% получатель получает сумму сверх потолка комиссии
+        let full_rate = TransferFee {
+            epoch: 0,
+            maximum_fee: 7_000,
+            transfer_fee_basis_points: MAX_FEE_BASIS_POINTS,
+        };
+        assert_eq!(full_rate.calculate_inverse_fee(1_000_000), Some(7_000));
+        assert_eq!(full_rate.calculate_fee(1_000_000 + 7_000), Some(7_000));
+    }
+}
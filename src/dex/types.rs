@@ -1,3 +1,5 @@
+use pinocchio::{account_info::AccountInfo, pubkey::Pubkey};
+
 use crate::state::{SwapCalculation, TradingPair};
 
 /// Поддерживаемые провайдеры DEX
@@ -5,59 +7,153 @@ use crate::state::{SwapCalculation, TradingPair};
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DexProvider {
     RaydiumV4,
+    RaydiumCpmm,
+    #[cfg(feature = "raydium-clmm")]
+    RaydiumClmm,
+    Whirlpool,
+    MeteoraDlmm,
+    MeteoraDamm,
+    PumpFun,
+    PumpSwap,
+    OpenBookV2,
+    #[cfg(feature = "phoenix")]
+    Phoenix,
+    Saber,
     // Можно добавить другие DEX в будущем
-    // Orca,
     // Serum,
 }
 
+/// Направление обмена относительно целевого токена
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapDirection {
+    /// Покупка: токен-котировка -> целевой токен
+    Buy,
+    /// Продажа: целевой токен -> токен-котировка
+    Sell,
+}
+
+impl SwapDirection {
+    /// Получить аккаунты пользователя (источник, назначение) для направления обмена
+    pub fn user_token_accounts<'a>(
+        &self,
+        accounts: &'a [AccountInfo],
+    ) -> (&'a AccountInfo, &'a AccountInfo) {
+        match self {
+            SwapDirection::Buy => (&accounts[1], &accounts[2]),
+            SwapDirection::Sell => (&accounts[2], &accounts[1]),
+        }
+    }
+}
+
+/// Режим обмена
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapMode {
+    /// Фиксированный вход: списывается ровно `amount_in`, получается не меньше `min_amount_out`
+    ExactIn,
+    /// Фиксированный выход: получается ровно `min_amount_out`, списывается не больше `amount_in`
+    ExactOut,
+}
+
 /// Параметры для выполнения обмена
 #[derive(Debug, Clone)]
 pub struct SwapParams {
     /// Торговая пара
     #[allow(dead_code)]
     pub trading_pair: TradingPair,
-    /// Количество входного токена
+    /// Направление обмена
+    pub direction: SwapDirection,
+    /// Режим обмена
+    pub mode: SwapMode,
+    /// Количество входного токена (максимальное для `SwapMode::ExactOut`)
     pub amount_in: u64,
-    /// Минимальное количество выходного токена
+    /// Минимальное количество выходного токена (точное для `SwapMode::ExactOut`)
     pub min_amount_out: u64,
     /// Расчет обмена
     pub calculation: SwapCalculation,
 }
 
-/// Информация о ликвидности пула
-#[allow(dead_code)]
-#[derive(Debug, Clone)]
-pub struct PoolLiquidity {
-    /// Резерв токена A
-    pub reserve_a: u64,
-    /// Резерв токена B
-    pub reserve_b: u64,
-    /// Общее количество LP токенов
-    pub total_supply: u64,
-}
-
-/// Результат поиска пула
-#[allow(dead_code)]
+/// Результат котировки пула-кандидата
 #[derive(Debug, Clone)]
 pub struct PoolSearchResult {
+    /// Индекс кандидата в порядке передачи аккаунтов
+    pub candidate_index: usize,
+    /// Программа DEX пула
+    pub dex_program: Pubkey,
     /// Найденная торговая пара
     pub trading_pair: TradingPair,
-    /// Информация о ликвидности
-    pub liquidity: PoolLiquidity,
+    /// Котировка обмена на этом пуле
+    pub calculation: SwapCalculation,
     /// Рейтинг пула (для выбора лучшего)
-    pub score: f64,
+    pub score: u64,
 }
 
 impl PoolSearchResult {
-    /// Рассчитать рейтинг пула на основе ликвидности и других факторов
-    #[allow(dead_code)]
-    pub fn calculate_score(&mut self) {
-        // Простой алгоритм оценки: чем больше ликвидность, тем выше рейтинг
-        let total_liquidity = self
-            .liquidity
-            .reserve_a
-            .saturating_add(self.liquidity.reserve_b);
-        self.score = total_liquidity as f64;
+    /// Рассчитать рейтинг пула по котировке
+    ///
+    /// При фиксированном входе рейтинг - выход за вычетом комиссий, при фиксированном
+    /// выходе - чем меньше необходимый вход, тем выше рейтинг.
+    pub fn calculate_score(&mut self, mode: SwapMode) {
+        self.score = match mode {
+            SwapMode::ExactIn => self.calculation.amount_out,
+            SwapMode::ExactOut => u64::MAX - self.calculation.amount_in,
+        };
     }
 }
 
+/// Выбор пула для обмена среди кандидатов
+#[derive(Debug, Clone)]
+pub struct PoolSelection {
+    /// Выбранный пул
+    pub best: PoolSearchResult,
+    /// Второй по рейтингу пул, если кандидатов несколько
+    pub runner_up: Option<PoolSearchResult>,
+}
+
+/// Результат выполненного обмена
+#[derive(Debug, Clone)]
+pub struct SwapExecution {
+    /// Расчет обмена, в котором `amount_out` - фактически полученное количество
+    pub calculation: SwapCalculation,
+    /// Выбранный пул и второй по рейтингу кандидат
+    pub selection: PoolSelection,
+}
+
+/// Часть обмена, разделенного между пулами
+#[derive(Debug, Clone)]
+pub struct SplitLeg {
+    /// Выделенная пулу часть входа
+    pub amount_in: u64,
+    /// Котировка пула на выделенную часть
+    pub quote: PoolSearchResult,
+    /// Фактически полученное количество выходного токена
+    pub amount_received: u64,
+}
+
+/// Результат обмена, разделенного между пулами
+#[derive(Debug, Clone)]
+pub struct SplitExecution {
+    /// Суммарный расчет, в котором `amount_out` - фактически полученное количество
+    pub calculation: SwapCalculation,
+    /// Части обмена в порядке выполнения
+    pub legs: Vec<SplitLeg>,
+}
+
+/// Шаг многошагового маршрута
+#[derive(Debug, Clone)]
+pub struct RouteHop {
+    /// Котировка пула на фактический вход шага
+    pub quote: PoolSearchResult,
+    /// Фактически полученное количество выходного токена шага
+    pub amount_received: u64,
+}
+
+/// Результат обмена по многошаговому маршруту
+#[derive(Debug, Clone)]
+pub struct RouteExecution {
+    /// Количество входного токена первого шага
+    pub amount_in: u64,
+    /// Фактически полученное количество целевого токена на последнем шаге
+    pub amount_out: u64,
+    /// Шаги маршрута в порядке выполнения
+    pub hops: Vec<RouteHop>,
+}
@@ -0,0 +1,594 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::cell::OnceCell;
+
+use crate::{
+    dex::{
+        get_token_balance,
+        math::{self, InitializedTick, TickRange, MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64},
+        types::{SwapMode, SwapParams},
+        verify_received_amount, DexInterface,
+    },
+    error::AutoBuyerError,
+    state::{constants, PoolConfig, PriceQ64, SwapCalculation, TradingPair},
+};
+
+/// Дискриминатор аккаунта Whirlpool
+const WHIRLPOOL_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9];
+
+/// Дискриминатор аккаунта TickArray
+const TICK_ARRAY_DISCRIMINATOR: [u8; 8] = [69, 97, 189, 190, 110, 7, 66, 187];
+
+/// Дискриминатор инструкции swap
+const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+
+/// Количество тиков в одном TickArray
+const TICK_ARRAY_SIZE: i32 = 88;
+
+/// Размер сериализованного тика
+const TICK_SIZE: usize = 113;
+
+/// Смещение первого тика в данных TickArray
+const TICKS_OFFSET: usize = 8 + 4;
+
+/// Смещение адреса пула в данных TickArray
+const TICK_ARRAY_WHIRLPOOL_OFFSET: usize = TICKS_OFFSET + TICK_ARRAY_SIZE as usize * TICK_SIZE;
+
+/// Количество аккаунтов, необходимых для обмена через Whirlpool
+const WHIRLPOOL_ACCOUNTS_LEN: usize = 14;
+
+/// Структура для работы с Orca Whirlpool
+///
+/// Аккаунты (после общих 0-4):
+/// 5. `[]` Программа Whirlpool
+/// 6. `[writable]` Whirlpool пул
+/// 7. `[writable]` Хранилище токена A
+/// 8. `[writable]` Хранилище токена B
+/// 9. `[]` Программа токенов
+/// 10. `[writable]` TickArray 0
+/// 11. `[writable]` TickArray 1
+/// 12. `[writable]` TickArray 2
+/// 13. `[writable]` Oracle
+pub struct Whirlpool {
+    /// Состояние пула, загруженное в рамках текущей инструкции
+    pool: OnceCell<(Pubkey, WhirlpoolState)>,
+}
+
+/// Состояние пула Whirlpool (без информации о наградах)
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct WhirlpoolState {
+    pub whirlpools_config: Pubkey,
+    pub whirlpool_bump: [u8; 1],
+    pub tick_spacing: u16,
+    pub fee_tier_index_seed: [u8; 2],
+    pub fee_rate: u16,
+    pub protocol_fee_rate: u16,
+    pub liquidity: u128,
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+    pub protocol_fee_owed_a: u64,
+    pub protocol_fee_owed_b: u64,
+    pub token_mint_a: Pubkey,
+    pub token_vault_a: Pubkey,
+    pub fee_growth_global_a: u128,
+    pub token_mint_b: Pubkey,
+    pub token_vault_b: Pubkey,
+    pub fee_growth_global_b: u128,
+}
+
+/// Инструкция обмена Whirlpool
+#[derive(BorshSerialize)]
+struct WhirlpoolSwapInstruction {
+    discriminator: [u8; 8],
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit: u128,
+    amount_specified_is_input: bool,
+    a_to_b: bool,
+}
+
+impl Whirlpool {
+    /// Создать новый экземпляр Whirlpool
+    pub fn new() -> Self {
+        Self {
+            pool: OnceCell::new(),
+        }
+    }
+
+    /// Загрузить состояние пула Whirlpool
+    fn load_whirlpool(
+        &self,
+        pool_account: &AccountInfo,
+    ) -> Result<&WhirlpoolState, AutoBuyerError> {
+        if let Some((pool_address, pool)) = self.pool.get() {
+            if pool_address != pool_account.key() {
+                return Err(AutoBuyerError::InvalidParameters);
+            }
+            return Ok(pool);
+        }
+
+        if pool_account.owner() != &constants::WHIRLPOOL_PROGRAM_ID {
+            return Err(AutoBuyerError::InvalidAccountOwner);
+        }
+
+        let data = pool_account
+            .try_borrow_data()
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        if data.len() < 8 || data[..8] != WHIRLPOOL_DISCRIMINATOR {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let pool = WhirlpoolState::deserialize(&mut &data[8..])
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        Ok(&self.pool.get_or_init(|| (*pool_account.key(), pool)).1)
+    }
+
+    /// Проверить, что переданные аккаунты соответствуют пулу
+    fn validate_pool_accounts(
+        &self,
+        pool: &WhirlpoolState,
+        accounts: &[AccountInfo],
+    ) -> Result<(), AutoBuyerError> {
+        if accounts.len() < WHIRLPOOL_ACCOUNTS_LEN {
+            msg!(
+                "Error: Insufficient accounts for Whirlpool. Expected {}, got {}",
+                WHIRLPOOL_ACCOUNTS_LEN,
+                accounts.len()
+            );
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if accounts[7].key() != &pool.token_vault_a || accounts[8].key() != &pool.token_vault_b {
+            msg!("Error: Pool vaults do not match Whirlpool");
+            return Err(AutoBuyerError::InvalidPoolVault);
+        }
+
+        if accounts[9].key() != &constants::TOKEN_PROGRAM_ID {
+            msg!("Error: Invalid token program");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if pool.fee_rate as u32 >= math::FEE_RATE_DENOMINATOR || pool.tick_spacing == 0 {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        Ok(())
+    }
+
+    /// Загрузить инициализированные тики из TickArray для обхода в направлении обмена
+    fn load_tick_range(
+        &self,
+        pool: &WhirlpoolState,
+        pool_address: &Pubkey,
+        tick_arrays: &[AccountInfo],
+        a_to_b: bool,
+    ) -> Result<TickRange, AutoBuyerError> {
+        // Загрузка уникальных TickArray пула
+        let mut arrays: Vec<(i32, Vec<InitializedTick>)> = Vec::with_capacity(tick_arrays.len());
+        for tick_array in tick_arrays {
+            if tick_array.owner() != &constants::WHIRLPOOL_PROGRAM_ID {
+                return Err(AutoBuyerError::InvalidAccountOwner);
+            }
+
+            let data = tick_array
+                .try_borrow_data()
+                .map_err(|_| AutoBuyerError::InvalidParameters)?;
+            let (start_tick_index, ticks) =
+                Self::parse_tick_array(&data, pool_address, pool.tick_spacing)?;
+            if !arrays.iter().any(|(start, _)| *start == start_tick_index) {
+                arrays.push((start_tick_index, ticks));
+            }
+        }
+
+        Self::chain_tick_arrays(&arrays, pool.tick_current_index, pool.tick_spacing, a_to_b)
+    }
+
+    /// Собрать тики непрерывной цепочки массивов, начиная с массива текущего тика
+    fn chain_tick_arrays(
+        arrays: &[(i32, Vec<InitializedTick>)],
+        tick_current_index: i32,
+        tick_spacing: u16,
+        a_to_b: bool,
+    ) -> Result<TickRange, AutoBuyerError> {
+        let ticks_per_array = TICK_ARRAY_SIZE * tick_spacing as i32;
+
+        let mut start = tick_current_index.div_euclid(ticks_per_array) * ticks_per_array;
+        let mut ticks = Vec::new();
+        let mut boundary_tick = if a_to_b {
+            start
+        } else {
+            start + ticks_per_array
+        };
+        let mut found_current = false;
+
+        while let Some((_, array_ticks)) = arrays.iter().find(|(s, _)| *s == start) {
+            found_current = true;
+            ticks.extend(array_ticks.iter().copied());
+
+            if a_to_b {
+                boundary_tick = start;
+                start -= ticks_per_array;
+            } else {
+                boundary_tick = start + ticks_per_array;
+                start += ticks_per_array;
+            }
+        }
+
+        if !found_current {
+            msg!("Error: Tick array for the current tick is missing");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if a_to_b {
+            ticks.retain(|tick| tick.index <= tick_current_index);
+            ticks.sort_unstable_by_key(|tick| core::cmp::Reverse(tick.index));
+        } else {
+            ticks.retain(|tick| tick.index > tick_current_index);
+            ticks.sort_unstable_by_key(|tick| tick.index);
+        }
+
+        Ok(TickRange {
+            ticks,
+            boundary_tick: boundary_tick.clamp(math::MIN_TICK, math::MAX_TICK),
+        })
+    }
+
+    /// Разобрать данные TickArray и вернуть индекс первого тика и инициализированные тики
+    fn parse_tick_array(
+        data: &[u8],
+        pool_address: &Pubkey,
+        tick_spacing: u16,
+    ) -> Result<(i32, Vec<InitializedTick>), AutoBuyerError> {
+        if data.len() < TICK_ARRAY_WHIRLPOOL_OFFSET + 32 || data[..8] != TICK_ARRAY_DISCRIMINATOR {
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        if &data[TICK_ARRAY_WHIRLPOOL_OFFSET..TICK_ARRAY_WHIRLPOOL_OFFSET + 32] != pool_address {
+            msg!("Error: Tick array belongs to another pool");
+            return Err(AutoBuyerError::InvalidParameters);
+        }
+
+        let start_tick_index = i32::from_le_bytes(data[8..12].try_into().unwrap());
+
+        let ticks = (0..TICK_ARRAY_SIZE as usize)
+            .filter_map(|i| {
+                let offset = TICKS_OFFSET + i * TICK_SIZE;
+                let initialized = data[offset] != 0;
+                initialized.then(|| InitializedTick {
+                    index: start_tick_index + i as i32 * tick_spacing as i32,
+                    liquidity_net: i128::from_le_bytes(
+                        data[offset + 1..offset + 17].try_into().unwrap(),
+                    ),
+                })
+            })
+            .collect();
+
+        Ok((start_tick_index, ticks))
+    }
+
+    /// Определить направление обмена: true, если входной токен - токен A пула
+    fn is_a_to_b(trading_pair: &TradingPair) -> bool {
+        trading_pair.pool_config.token_a_mint == trading_pair.quote_mint
+    }
+
+    /// Выполнить обмен через CPI
+    fn execute_whirlpool_swap(
+        &self,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        let a_to_b = Self::is_a_to_b(&swap_params.trading_pair);
+
+        let user_account = &accounts[0];
+        let (source_token_account, destination_token_account) =
+            swap_params.direction.user_token_accounts(accounts);
+        let (token_owner_account_a, token_owner_account_b) = if a_to_b {
+            (source_token_account, destination_token_account)
+        } else {
+            (destination_token_account, source_token_account)
+        };
+        let whirlpool_program = &accounts[5];
+        let whirlpool_account = &accounts[6];
+        let token_vault_a = &accounts[7];
+        let token_vault_b = &accounts[8];
+        let token_program = &accounts[9];
+        let tick_array_0 = &accounts[10];
+        let tick_array_1 = &accounts[11];
+        let tick_array_2 = &accounts[12];
+        let oracle = &accounts[13];
+
+        let instruction_data = borsh::to_vec(&WhirlpoolSwapInstruction {
+            discriminator: SWAP_DISCRIMINATOR,
+            amount: swap_params.amount_in,
+            other_amount_threshold: swap_params.min_amount_out,
+            sqrt_price_limit: if a_to_b {
+                MIN_SQRT_PRICE_X64
+            } else {
+                MAX_SQRT_PRICE_X64
+            },
+            amount_specified_is_input: true,
+            a_to_b,
+        })
+        .map_err(|_| AutoBuyerError::InvalidParameters)?;
+
+        let instruction = Instruction {
+            program_id: whirlpool_program.key(),
+            accounts: &[
+                AccountMeta::readonly(token_program.key()),
+                AccountMeta::readonly_signer(user_account.key()),
+                AccountMeta::writable(whirlpool_account.key()),
+                AccountMeta::writable(token_owner_account_a.key()),
+                AccountMeta::writable(token_vault_a.key()),
+                AccountMeta::writable(token_owner_account_b.key()),
+                AccountMeta::writable(token_vault_b.key()),
+                AccountMeta::writable(tick_array_0.key()),
+                AccountMeta::writable(tick_array_1.key()),
+                AccountMeta::writable(tick_array_2.key()),
+                AccountMeta::writable(oracle.key()),
+            ],
+            data: &instruction_data,
+        };
+
+        let account_infos = [
+            token_program,
+            user_account,
+            whirlpool_account,
+            token_owner_account_a,
+            token_vault_a,
+            token_owner_account_b,
+            token_vault_b,
+            tick_array_0,
+            tick_array_1,
+            tick_array_2,
+            oracle,
+        ];
+
+        let balance_before = get_token_balance(destination_token_account)?;
+
+        invoke(&instruction, &account_infos).map_err(|_| AutoBuyerError::CpiError)?;
+
+        verify_received_amount(
+            destination_token_account,
+            balance_before,
+            swap_params.min_amount_out,
+        )
+    }
+}
+
+impl DexInterface for Whirlpool {
+    fn accounts_len(&self) -> usize {
+        WHIRLPOOL_ACCOUNTS_LEN
+    }
+
+    fn find_trading_pair(
+        &self,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> Result<Option<TradingPair>, AutoBuyerError> {
+        if accounts.len() < 7 || accounts[5].key() != &constants::WHIRLPOOL_PROGRAM_ID {
+            return Ok(None);
+        }
+
+        let pool_account = &accounts[6];
+        let pool = match self.load_whirlpool(pool_account) {
+            Ok(pool) => pool,
+            Err(_) => return Ok(None),
+        };
+
+        let is_correct_pair = (pool.token_mint_a == *base_mint && pool.token_mint_b == *quote_mint)
+            || (pool.token_mint_a == *quote_mint && pool.token_mint_b == *base_mint);
+
+        if !is_correct_pair {
+            return Ok(None);
+        }
+
+        self.validate_pool_accounts(pool, accounts)?;
+
+        let pool_config = PoolConfig {
+            pool_address: *pool_account.key(),
+            token_a_account: pool.token_vault_a,
+            token_b_account: pool.token_vault_b,
+            token_a_mint: pool.token_mint_a,
+            token_b_mint: pool.token_mint_b,
+            // fee_rate хранится в сотых долях базисного пункта
+            fee_rate: pool.fee_rate / 100,
+        };
+
+        Ok(Some(TradingPair {
+            base_mint: *base_mint,
+            quote_mint: *quote_mint,
+            pool_config,
+        }))
+    }
+
+    fn calculate_swap(
+        &self,
+        trading_pair: &TradingPair,
+        amount_in: u64,
+        accounts: &[AccountInfo],
+    ) -> Result<SwapCalculation, AutoBuyerError> {
+        let pool_account = &accounts[6];
+        let pool = self.load_whirlpool(pool_account)?;
+        let a_to_b = Self::is_a_to_b(trading_pair);
+
+        let tick_range =
+            self.load_tick_range(pool, pool_account.key(), &accounts[10..13], a_to_b)?;
+
+        let (amount_out, fee_amount) = math::swap_exact_in(
+            pool.sqrt_price,
+            pool.liquidity,
+            pool.fee_rate as u32,
+            &tick_range,
+            amount_in,
+            a_to_b,
+        )?;
+
+        let price_per_unit = PriceQ64::from_ratio(amount_out, amount_in).unwrap_or_default();
+        let spot_price = math::spot_price_from_sqrt(pool.sqrt_price, a_to_b);
+
+        Ok(SwapCalculation {
+            amount_in,
+            amount_out,
+            fee_amount,
+            price_per_unit,
+            price_impact_bps: price_per_unit.impact_bps(spot_price),
+        })
+    }
+
+    fn execute_swap(
+        &self,
+        _program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        swap_params: &SwapParams,
+    ) -> Result<u64, AutoBuyerError> {
+        if swap_params.mode != SwapMode::ExactIn {
+            return Err(AutoBuyerError::SwapModeNotSupported);
+        }
+
+        msg!(
+            "Executing Whirlpool swap: {} -> {}",
+            swap_params.amount_in,
+            swap_params.calculation.amount_out
+        );
+
+        let amount_received = self.execute_whirlpool_swap(accounts, swap_params)?;
+
+        msg!(
+            "Whirlpool swap completed successfully, received {}",
+            amount_received
+        );
+        Ok(amount_received)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POOL: Pubkey = [7; 32];
+
+    /// Шаг тиков пула в тестах
+    const TICK_SPACING: u16 = 64;
+
+    /// Данные TickArray пула `POOL` с инициализированными тиками `(индекс, liquidity_net)`
+    fn tick_array(start_tick_index: i32, ticks: &[(i32, i128)]) -> Vec<u8> {
+        let mut data = vec![0u8; TICK_ARRAY_WHIRLPOOL_OFFSET + 32];
+        data[..8].copy_from_slice(&TICK_ARRAY_DISCRIMINATOR);
+        data[8..12].copy_from_slice(&start_tick_index.to_le_bytes());
+        for (index, liquidity_net) in ticks {
+            let position = ((index - start_tick_index) / TICK_SPACING as i32) as usize;
+            let offset = TICKS_OFFSET + position * TICK_SIZE;
+            data[offset] = 1;
+            data[offset + 1..offset + 17].copy_from_slice(&liquidity_net.to_le_bytes());
+        }
+        data[TICK_ARRAY_WHIRLPOOL_OFFSET..].copy_from_slice(&POOL);
+        data
+    }
+
+    /// Позиции [-640, 5760] с L = 4e8 и [0, 1280] с L = 1e9, разложенные по трем массивам
+    fn arrays() -> Vec<(i32, Vec<InitializedTick>)> {
+        [
+            tick_array(5632, &[(5760, -400_000_000)]),
+            tick_array(0, &[(0, 1_000_000_000), (1280, -1_000_000_000)]),
+            tick_array(-5632, &[(-640, 400_000_000)]),
+        ]
+        .iter()
+        .map(|data| Whirlpool::parse_tick_array(data, &POOL, TICK_SPACING).unwrap())
+        .collect()
+    }
+
+    fn indexes(range: &TickRange) -> Vec<i32> {
+        range.ticks.iter().map(|tick| tick.index).collect()
+    }
+
+    #[test]
+    fn parse_tick_array_reads_initialized_ticks() {
+        let data = tick_array(-5632, &[(-5632, 5), (-640, -7)]);
+        let (start, ticks) = Whirlpool::parse_tick_array(&data, &POOL, TICK_SPACING).unwrap();
+
+        assert_eq!(start, -5632);
+        assert_eq!(ticks.len(), 2);
+        assert_eq!((ticks[0].index, ticks[0].liquidity_net), (-5632, 5));
+        assert_eq!((ticks[1].index, ticks[1].liquidity_net), (-640, -7));
+    }
+
+    #[test]
+    fn parse_tick_array_rejects_foreign_data() {
+        let data = tick_array(0, &[]);
+        assert!(Whirlpool::parse_tick_array(&data, &[8; 32], TICK_SPACING).is_err());
+        assert!(Whirlpool::parse_tick_array(&data[..data.len() - 1], &POOL, TICK_SPACING).is_err());
+
+        let mut wrong_discriminator = data.clone();
+        wrong_discriminator[0] ^= 1;
+        assert!(Whirlpool::parse_tick_array(&wrong_discriminator, &POOL, TICK_SPACING).is_err());
+    }
+
+    #[test]
+    fn chain_tick_arrays_follows_swap_direction() {
+        let arrays = arrays();
+
+        let down = Whirlpool::chain_tick_arrays(&arrays, 100, TICK_SPACING, true).unwrap();
+        assert_eq!(indexes(&down), [0, -640]);
+        assert_eq!(down.boundary_tick, -5632);
+
+        let up = Whirlpool::chain_tick_arrays(&arrays, 100, TICK_SPACING, false).unwrap();
+        assert_eq!(indexes(&up), [1280, 5760]);
+        assert_eq!(up.boundary_tick, 11264);
+    }
+
+    #[test]
+    fn chain_tick_arrays_requires_current_array() {
+        let arrays = arrays();
+        assert!(Whirlpool::chain_tick_arrays(&arrays, 20_000, TICK_SPACING, true).is_err());
+
+        // Цепочка обрывается на пропущенном массиве
+        let gapped = [arrays[1].clone(), (11264, Vec::new())];
+        let up = Whirlpool::chain_tick_arrays(&gapped, 100, TICK_SPACING, false).unwrap();
+        assert_eq!(indexes(&up), [1280]);
+        assert_eq!(up.boundary_tick, 5632);
+    }
+
+    #[test]
+    fn quote_crosses_ticks_in_both_directions() {
+        let arrays = arrays();
+        let sqrt_price = math::sqrt_price_at_tick(100).unwrap();
+        let liquidity = 1_400_000_000;
+
+        // Цена растет через тик 1280, ликвидность падает до 4e8
+        let up = Whirlpool::chain_tick_arrays(&arrays, 100, TICK_SPACING, false).unwrap();
+        assert_eq!(
+            math::swap_exact_in(sqrt_price, liquidity, 3000, &up, 100_000_000, false).unwrap(),
+            (91891877, 300001)
+        );
+
+        // Цена падает через тик 0, ликвидность падает до 4e8
+        let down = Whirlpool::chain_tick_arrays(&arrays, 100, TICK_SPACING, true).unwrap();
+        assert_eq!(
+            math::swap_exact_in(sqrt_price, liquidity, 3000, &down, 10_000_000, true).unwrap(),
+            (9982842, 30001)
+        );
+    }
+
+    #[test]
+    fn quote_beyond_supplied_arrays_fails() {
+        let arrays = arrays();
+        let sqrt_price = math::sqrt_price_at_tick(100).unwrap();
+        let down = Whirlpool::chain_tick_arrays(&arrays, 100, TICK_SPACING, true).unwrap();
+
+        assert!(matches!(
+            math::swap_exact_in(sqrt_price, 1_400_000_000, 3000, &down, u64::MAX / 2, true),
+            Err(AutoBuyerError::InsufficientLiquidity)
+        ));
+    }
+}
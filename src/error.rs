@@ -43,6 +43,54 @@ pub enum AutoBuyerError {
     /// Токен не поддерживается
     #[error("Token not supported")]
     TokenNotSupported,
+
+    /// Режим обмена не поддерживается провайдером
+    #[error("Swap mode not supported")]
+    SwapModeNotSupported,
+
+    /// Фактически полученное количество меньше минимального
+    #[error("Received amount too low")]
+    ReceivedAmountTooLow,
+
+    /// Неверный минт токенового аккаунта
+    #[error("Invalid token mint")]
+    InvalidTokenMint,
+
+    /// Подписант не является владельцем или делегатом токенового аккаунта
+    #[error("Invalid token account authority")]
+    InvalidTokenAuthority,
+
+    /// Токеновый аккаунт заморожен
+    #[error("Account frozen")]
+    AccountFrozen,
+
+    /// Хранилище пула не соответствует AMM
+    #[error("Invalid pool vault")]
+    InvalidPoolVault,
+
+    /// Open orders не соответствуют AMM
+    #[error("Invalid open orders")]
+    InvalidOpenOrders,
+
+    /// Target orders не соответствуют AMM
+    #[error("Invalid target orders")]
+    InvalidTargetOrders,
+
+    /// Программа рынка не соответствует AMM
+    #[error("Invalid market program")]
+    InvalidMarketProgram,
+
+    /// Рынок не соответствует AMM
+    #[error("Invalid market")]
+    InvalidMarket,
+
+    /// Статус пула не допускает обмен
+    #[error("Pool not tradable")]
+    PoolNotTradable,
+
+    /// Время открытия пула еще не наступило
+    #[error("Pool not open")]
+    PoolNotOpen,
 }
 
 impl From<AutoBuyerError> for ProgramError {
@@ -65,6 +113,18 @@ impl From<ProgramError> for AutoBuyerError {
                 7 => AutoBuyerError::MathOverflow,
                 8 => AutoBuyerError::CpiError,
                 9 => AutoBuyerError::TokenNotSupported,
+                10 => AutoBuyerError::SwapModeNotSupported,
+                11 => AutoBuyerError::ReceivedAmountTooLow,
+                12 => AutoBuyerError::InvalidTokenMint,
+                13 => AutoBuyerError::InvalidTokenAuthority,
+                14 => AutoBuyerError::AccountFrozen,
+                15 => AutoBuyerError::InvalidPoolVault,
+                16 => AutoBuyerError::InvalidOpenOrders,
+                17 => AutoBuyerError::InvalidTargetOrders,
+                18 => AutoBuyerError::InvalidMarketProgram,
+                19 => AutoBuyerError::InvalidMarket,
+                20 => AutoBuyerError::PoolNotTradable,
+                21 => AutoBuyerError::PoolNotOpen,
                 _ => AutoBuyerError::InvalidInstruction,
             },
             _ => AutoBuyerError::InvalidInstruction,
@@ -1,6 +1,6 @@
 use crate::error::AutoBuyerError;
 use borsh::{BorshDeserialize, BorshSerialize};
-use pinocchio::program_error::ProgramError; // Removed unused Pubkey import
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
 
 /// Инструкции для автоматического покупателя токенов
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
@@ -13,8 +13,10 @@ pub enum AutoBuyerInstruction {
     /// 2. `[writable]` Аккаунт целевого токена пользователя (назначение)
     /// 3. `[]` Минт токена для покупки
     /// 4. `[]` Минт токена-котировки
-    /// 5. `[]` Raydium программа
-    /// 6. `[writable]` Raydium пул
+    /// 5. `[]` Программа DEX (определяет провайдера и раскладку аккаунтов ниже)
+    /// 6. `[writable]` Пул
+    ///
+    /// Raydium v4:
     /// 7. `[writable]` Raydium пул токен A аккаунт
     /// 8. `[writable]` Raydium пул токен B аккаунт
     /// 9. `[]` Программа токенов
@@ -30,12 +32,189 @@ pub enum AutoBuyerInstruction {
     /// 19. `[writable]` Serum coin vault
     /// 20. `[writable]` Serum pc vault
     /// 21. `[]` Serum vault signer
+    ///
+    /// Orca Whirlpool:
+    /// 7. `[writable]` Хранилище токена A
+    /// 8. `[writable]` Хранилище токена B
+    /// 9. `[]` Программа токенов
+    /// 10-12. `[writable]` TickArray в направлении обмена
+    /// 13. `[writable]` Oracle
+    ///
+    /// Raydium CPMM (пользовательские аккаунты могут принадлежать Token-2022):
+    /// 7. `[]` Authority пула
+    /// 8. `[]` AmmConfig
+    /// 9. `[writable]` Хранилище токена 0
+    /// 10. `[writable]` Хранилище токена 1
+    /// 11. `[]` Программа токена 0
+    /// 12. `[]` Программа токена 1
+    /// 13. `[writable]` Observation state
+    ///
+    /// Raydium CLMM (при сборке с feature `raydium-clmm`):
+    /// 7. `[]` AmmConfig
+    /// 8. `[writable]` Хранилище токена 0
+    /// 9. `[writable]` Хранилище токена 1
+    /// 10. `[writable]` Observation state
+    /// 11. `[]` Программа токенов
+    /// 12. `[]` TickArrayBitmapExtension
+    /// 13-15. `[writable]` TickArrayState в направлении обмена
+    ///
+    /// Meteora DLMM (пользовательские аккаунты могут принадлежать Token-2022):
+    /// 7. `[]` BinArrayBitmapExtension (или программа DLMM)
+    /// 8. `[writable]` Резерв токена X
+    /// 9. `[writable]` Резерв токена Y
+    /// 10. `[writable]` Oracle
+    /// 11. `[]` Программа токена X
+    /// 12. `[]` Программа токена Y
+    /// 13. `[]` Event authority
+    /// 14-16. `[writable]` BinArray в направлении обмена
+    ///
+    /// Meteora Dynamic AMM (резервы размещены в динамических хранилищах Meteora):
+    /// 7. `[writable]` Хранилище токена A
+    /// 8. `[writable]` Хранилище токена B
+    /// 9. `[writable]` Токеновый аккаунт хранилища A
+    /// 10. `[writable]` Токеновый аккаунт хранилища B
+    /// 11. `[writable]` LP минт хранилища A
+    /// 12. `[writable]` LP минт хранилища B
+    /// 13. `[writable]` LP аккаунт пула в хранилище A
+    /// 14. `[writable]` LP аккаунт пула в хранилище B
+    /// 15. `[writable]` Аккаунт комиссии протокола токена A
+    /// 16. `[writable]` Аккаунт комиссии протокола токена B
+    /// 17. `[]` Программа хранилищ Meteora
+    /// 18. `[]` Программа токенов
+    ///
+    /// Pump.fun (только покупка за SOL, оплата лампортами пользователя):
+    /// 7. `[writable]` Токеновый аккаунт кривой
+    /// 8. `[]` Global
+    /// 9. `[writable]` Получатель комиссии
+    /// 10. `[writable]` Хранилище создателя
+    /// 11. `[]` Системная программа
+    /// 12. `[]` Программа токенов
+    /// 13. `[]` Event authority
+    /// 14. `[writable]` Глобальный счетчик объема
+    /// 15. `[writable]` Счетчик объема пользователя
+    /// 16. `[]` Конфигурация комиссий
+    /// 17. `[]` Программа комиссий pump.fun
+    ///
+    /// PumpSwap (токены, завершившие кривую pump.fun; комиссии взимаются с токена-котировки):
+    /// 7. `[]` GlobalConfig
+    /// 8. `[writable]` Хранилище базового токена пула
+    /// 9. `[writable]` Хранилище токена-котировки пула
+    /// 10. `[]` Получатель комиссии протокола
+    /// 11. `[writable]` Токеновый аккаунт получателя комиссии протокола
+    /// 12. `[]` Программа базового токена
+    /// 13. `[]` Программа токена-котировки
+    /// 14. `[]` Системная программа
+    /// 15. `[]` Программа ассоциированных токеновых аккаунтов
+    /// 16. `[]` Event authority
+    /// 17. `[writable]` Токеновый аккаунт хранилища создателя монеты
+    /// 18. `[]` Authority хранилища создателя монеты
+    /// 19. `[writable]` Глобальный счетчик объема (только для buy)
+    /// 20. `[writable]` Счетчик объема пользователя (только для buy)
+    /// 21. `[]` Конфигурация комиссий
+    /// 22. `[]` Программа комиссий pump.fun
+    ///
+    /// OpenBook v2 (IOC-заявка тейкера, пользователь оплачивает штраф за неисполнение):
+    /// 7. `[]` Market authority
+    /// 8. `[writable]` Bids
+    /// 9. `[writable]` Asks
+    /// 10. `[writable]` Хранилище базового токена рынка
+    /// 11. `[writable]` Хранилище токена-котировки рынка
+    /// 12. `[writable]` Event heap
+    /// 13. `[]` Oracle A (или программа OpenBook)
+    /// 14. `[]` Oracle B (или программа OpenBook)
+    /// 15. `[]` Программа токенов
+    /// 16. `[]` Системная программа
+    ///
+    /// Phoenix (при сборке с feature `phoenix`, IOC-заявка с учетом комиссии тейкера):
+    /// 7. `[]` Log authority
+    /// 8. `[writable]` Хранилище базового токена рынка
+    /// 9. `[writable]` Хранилище токена-котировки рынка
+    /// 10. `[]` Программа токенов
+    ///
+    /// Saber (StableSwap для пар со связанной ценой):
+    /// 7. `[]` Authority пула
+    /// 8. `[writable]` Резерв токена A
+    /// 9. `[writable]` Резерв токена B
+    /// 10. `[writable]` Аккаунт административных комиссий токена A
+    /// 11. `[writable]` Аккаунт административных комиссий токена B
+    /// 12. `[]` Программа токенов
+    ///
+    /// После блока первого пула можно передать блоки других пулов-кандидатов в той же
+    /// раскладке, начиная с программы DEX. Обмен выполняется на пуле с лучшей котировкой,
+    /// выбранный пул и второй по котировке кандидат возвращаются в `BuyResult`.
     BuyToken {
         /// Сумма в токене-котировке для обмена
         amount_in: u64,
         /// Минимальное приемлемое количество выходного токена
         min_amount_out: u64,
     },
+
+    /// Продать целевой токен обратно за токен-котировку
+    ///
+    /// Аккаунты те же, что и у `BuyToken`, но обмен идет в обратную сторону:
+    /// 1. `[writable]` Аккаунт токена-котировки пользователя (назначение)
+    /// 2. `[writable]` Аккаунт целевого токена пользователя (источник)
+    SellToken {
+        /// Сумма в целевом токене для обмена
+        amount_in: u64,
+        /// Минимальное приемлемое количество токена-котировки
+        min_amount_out: u64,
+    },
+
+    /// Купить точное количество целевого токена (Raydium swap_base_out, CPMM swap_base_output)
+    ///
+    /// Аккаунты те же, что и у `BuyToken`.
+    BuyTokenExactOut {
+        /// Точное количество целевого токена для получения
+        amount_out: u64,
+        /// Максимальная приемлемая сумма в токене-котировке
+        max_amount_in: u64,
+    },
+
+    /// Рассчитать котировку покупки без выполнения обмена
+    ///
+    /// Аккаунты те же, что и у `BuyToken`, подпись пользователя не требуется.
+    /// `SwapCalculation` возвращается через return data.
+    QuoteBuy {
+        /// Сумма в токене-котировке для обмена
+        amount_in: u64,
+    },
+
+    /// Купить токен, разделив сумму между несколькими пулами
+    ///
+    /// Аккаунты те же, что и у `BuyToken`, с блоками нескольких пулов-кандидатов.
+    /// Сумма распределяется между пулами для максимального суммарного выхода, все части
+    /// исполняются в одной инструкции. `SplitBuyResult` возвращается через return data.
+    BuyTokenSplit {
+        /// Общая сумма в токене-котировке для обмена
+        amount_in: u64,
+        /// Минимальное приемлемое суммарное количество целевого токена
+        min_amount_out: u64,
+    },
+
+    /// Купить токен по многошаговому маршруту через промежуточные токены
+    ///
+    /// Например, USDC -> SOL -> целевой токен, если у целевого токена есть только пул с SOL.
+    /// Фактический выход каждого шага становится входом следующего.
+    ///
+    /// Аккаунты:
+    /// 0-4. Те же, что и у `BuyToken` (токен-котировка - вход первого шага)
+    ///
+    /// Для каждого промежуточного токена по порядку (`hops - 1` пар):
+    /// - `[writable]` Аккаунт промежуточного токена пользователя
+    /// - `[]` Минт промежуточного токена
+    ///
+    /// Затем блоки пулов шагов по порядку в раскладке `BuyToken`, начиная с программы DEX.
+    /// Пулы, оплачиваемые лампортами (pump.fun), допускаются только на первом шаге.
+    /// `MultiHopBuyResult` возвращается через return data.
+    BuyTokenMultiHop {
+        /// Количество шагов маршрута (от 2 до `MAX_ROUTE_HOPS`)
+        hops: u8,
+        /// Сумма в токене-котировке для обмена на первом шаге
+        amount_in: u64,
+        /// Минимальное приемлемое количество целевого токена на последнем шаге
+        min_amount_out: u64,
+    },
 }
 
 impl AutoBuyerInstruction {
@@ -51,6 +230,8 @@ impl AutoBuyerInstruction {
 }
 
 /// Данные результата покупки
+///
+/// Возвращаются вызывающей стороне через return data в сериализации Borsh.
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct BuyResult {
     /// Успешность транзакции
@@ -61,4 +242,53 @@ pub struct BuyResult {
     pub fee_paid: u64,
     /// Время выполнения транзакции
     pub timestamp: i64,
+    /// Пул, на котором выполнен обмен
+    pub venue: VenueQuote,
+    /// Второй по котировке пул-кандидат
+    pub runner_up: Option<VenueQuote>,
+}
+
+/// Котировка пула-кандидата
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct VenueQuote {
+    /// Программа DEX
+    pub dex_program: Pubkey,
+    /// Адрес пула
+    pub pool: Pubkey,
+    /// Котировка: количество входного токена
+    pub amount_in: u64,
+    /// Котировка: количество выходного токена за вычетом комиссий
+    pub amount_out: u64,
+}
+
+/// Данные результата покупки, разделенной между пулами
+///
+/// Возвращаются вызывающей стороне через return data в сериализации Borsh.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SplitBuyResult {
+    /// Успешность транзакции
+    pub success: bool,
+    /// Суммарное количество фактически полученного токена
+    pub amount_out: u64,
+    /// Суммарный размер уплаченной комиссии
+    pub fee_paid: u64,
+    /// Время выполнения транзакции
+    pub timestamp: i64,
+    /// Части обмена: выделенная пулу сумма и его котировка
+    pub legs: Vec<VenueQuote>,
+}
+
+/// Данные результата покупки по многошаговому маршруту
+///
+/// Возвращаются вызывающей стороне через return data в сериализации Borsh.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct MultiHopBuyResult {
+    /// Успешность транзакции
+    pub success: bool,
+    /// Количество фактически полученного целевого токена
+    pub amount_out: u64,
+    /// Время выполнения транзакции
+    pub timestamp: i64,
+    /// Шаги маршрута: пул, фактический вход шага и его котировка
+    pub hops: Vec<VenueQuote>,
 }
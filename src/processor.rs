@@ -1,9 +1,25 @@
-use pinocchio::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+use pinocchio::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    msg,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
+};
+use spl_token::{
+    solana_program::{program_option::COption, program_pack::Pack},
+    state::Account as TokenAccount,
+};
 
 use crate::{
-    dex::DexManager,
+    dex::{
+        token_2022::is_token_program,
+        types::{PoolSearchResult, RouteExecution, SplitExecution, SwapDirection, SwapExecution},
+        DexManager,
+    },
     error::AutoBuyerError,
-    instruction::{AutoBuyerInstruction, BuyResult},
+    instruction::{AutoBuyerInstruction, BuyResult, MultiHopBuyResult, SplitBuyResult, VenueQuote},
     state::constants,
 };
 
@@ -23,15 +39,67 @@ impl Processor {
                 min_amount_out,
             } => {
                 msg!("Processing BuyToken instruction");
-                Self::process_buy_token(program_id, accounts, amount_in, min_amount_out)
+                Self::process_swap(
+                    program_id,
+                    accounts,
+                    SwapDirection::Buy,
+                    amount_in,
+                    min_amount_out,
+                )
+            }
+            AutoBuyerInstruction::SellToken {
+                amount_in,
+                min_amount_out,
+            } => {
+                msg!("Processing SellToken instruction");
+                Self::process_swap(
+                    program_id,
+                    accounts,
+                    SwapDirection::Sell,
+                    amount_in,
+                    min_amount_out,
+                )
+            }
+            AutoBuyerInstruction::BuyTokenExactOut {
+                amount_out,
+                max_amount_in,
+            } => {
+                msg!("Processing BuyTokenExactOut instruction");
+                Self::process_buy_token_exact_out(program_id, accounts, amount_out, max_amount_in)
+            }
+            AutoBuyerInstruction::QuoteBuy { amount_in } => {
+                msg!("Processing QuoteBuy instruction");
+                Self::process_quote_buy(accounts, amount_in)
+            }
+            AutoBuyerInstruction::BuyTokenSplit {
+                amount_in,
+                min_amount_out,
+            } => {
+                msg!("Processing BuyTokenSplit instruction");
+                Self::process_buy_token_split(program_id, accounts, amount_in, min_amount_out)
+            }
+            AutoBuyerInstruction::BuyTokenMultiHop {
+                hops,
+                amount_in,
+                min_amount_out,
+            } => {
+                msg!("Processing BuyTokenMultiHop instruction");
+                Self::process_buy_token_multi_hop(
+                    program_id,
+                    accounts,
+                    hops,
+                    amount_in,
+                    min_amount_out,
+                )
             }
         }
     }
 
-    /// Обработать покупку токена
-    fn process_buy_token(
+    /// Обработать покупку или продажу токена
+    fn process_swap(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
+        direction: SwapDirection,
         amount_in: u64,
         min_amount_out: u64,
     ) -> ProgramResult {
@@ -51,24 +119,42 @@ impl Processor {
 
         // Извлечение аккаунтов
         let user_account = &accounts[0];
-        let source_token_account = &accounts[1];
-        let _destination_token_account = &accounts[2]; // Fixed: prefixed with underscore
+        let (source_token_account, _destination_token_account) =
+            direction.user_token_accounts(accounts);
         let target_mint = &accounts[3];
         let quote_mint = &accounts[4];
 
         // Fixed: Format Pubkey as debug instead of display
+        msg!("Direction: {:?}", direction);
         msg!("User: {:?}", user_account.key());
         msg!("Target mint: {:?}", target_mint.key());
         msg!("Quote mint: {:?}", quote_mint.key());
         msg!("Amount in: {}", amount_in);
         msg!("Min amount out: {}", min_amount_out);
 
-        // Проверка баланса пользователя
-        Self::check_user_balance(source_token_account, amount_in)?;
-
+        // Проверка баланса пользователя во входном токене
+        let input_mint = match direction {
+            SwapDirection::Buy => quote_mint,
+            SwapDirection::Sell => target_mint,
+        };
         // Создание менеджера DEX
         let dex_manager = DexManager::new();
 
+        // Покупка на пулах, оплачиваемых лампортами пользователя, не списывает аккаунт
+        // источника; продажа всегда списывает токены с него
+        let required_amount =
+            if direction == SwapDirection::Buy && dex_manager.funded_by_lamports(accounts)? {
+                0
+            } else {
+                amount_in
+            };
+        Self::check_user_balance(
+            source_token_account,
+            input_mint,
+            user_account,
+            required_amount,
+        )?;
+
         // Выполнение автоматического обмена
         let swap_result = dex_manager
             .execute_auto_swap(
@@ -76,6 +162,7 @@ impl Processor {
                 accounts,
                 target_mint.key(),
                 quote_mint.key(),
+                direction,
                 amount_in,
                 min_amount_out,
             )
@@ -83,30 +170,323 @@ impl Processor {
                 msg!("Swap failed: {:?}", e);
             })?;
 
-        // Создание результата без времени (упрощенная версия)
-        let result = BuyResult {
-            success: true,
-            amount_out: swap_result.amount_out,
-            fee_paid: swap_result.fee_amount,
-            timestamp: 0, // Упрощено для совместимости
-        };
+        // Создание и возврат результата
+        let result = Self::build_result(&swap_result)?;
+        Self::return_result(&result)?;
 
         // Логирование результата
         Self::log_transaction_result(&result, &swap_result);
 
+        match direction {
+            SwapDirection::Buy => msg!("Token purchase completed successfully"),
+            SwapDirection::Sell => msg!("Token sale completed successfully"),
+        }
+        Ok(())
+    }
+
+    /// Обработать покупку точного количества токена
+    fn process_buy_token_exact_out(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount_out: u64,
+        max_amount_in: u64,
+    ) -> ProgramResult {
+        // Валидация входных параметров
+        if amount_out == 0 {
+            msg!("Error: Amount out cannot be zero");
+            return Err(AutoBuyerError::InvalidParameters.into());
+        }
+
+        if max_amount_in == 0 {
+            msg!("Error: Maximum amount in cannot be zero");
+            return Err(AutoBuyerError::InvalidParameters.into());
+        }
+
+        // Валидация аккаунтов
+        Self::validate_accounts(accounts)?;
+
+        // Извлечение аккаунтов
+        let user_account = &accounts[0];
+        let source_token_account = &accounts[1];
+        let target_mint = &accounts[3];
+        let quote_mint = &accounts[4];
+
+        msg!("User: {:?}", user_account.key());
+        msg!("Target mint: {:?}", target_mint.key());
+        msg!("Quote mint: {:?}", quote_mint.key());
+        msg!("Amount out: {}", amount_out);
+        msg!("Max amount in: {}", max_amount_in);
+
+        // Проверка баланса пользователя по максимальной сумме
+        Self::check_user_balance(
+            source_token_account,
+            quote_mint,
+            user_account,
+            max_amount_in,
+        )?;
+
+        // Создание менеджера DEX
+        let dex_manager = DexManager::new();
+
+        // Выполнение обмена с фиксированным выходом
+        let swap_result = dex_manager
+            .execute_auto_swap_exact_out(
+                program_id,
+                accounts,
+                target_mint.key(),
+                quote_mint.key(),
+                amount_out,
+                max_amount_in,
+            )
+            .inspect_err(|&e| {
+                msg!("Swap failed: {:?}", e);
+            })?;
+
+        // Создание и возврат результата
+        let result = Self::build_result(&swap_result)?;
+        Self::return_result(&result)?;
+
+        // Логирование результата
+        msg!("Amount In: {}", swap_result.calculation.amount_in);
+        Self::log_transaction_result(&result, &swap_result);
+
         msg!("Token purchase completed successfully");
         Ok(())
     }
 
-    /// Валидация переданных аккаунтов
-    fn validate_accounts(accounts: &[AccountInfo]) -> Result<(), AutoBuyerError> {
-        if accounts.len() < 22 {
+    /// Обработать покупку, разделенную между несколькими пулами
+    fn process_buy_token_split(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> ProgramResult {
+        // Валидация входных параметров
+        if amount_in == 0 {
+            msg!("Error: Amount in cannot be zero");
+            return Err(AutoBuyerError::InvalidParameters.into());
+        }
+
+        if min_amount_out == 0 {
+            msg!("Error: Minimum amount out cannot be zero");
+            return Err(AutoBuyerError::InvalidParameters.into());
+        }
+
+        // Валидация аккаунтов
+        Self::validate_accounts(accounts)?;
+
+        // Извлечение аккаунтов
+        let user_account = &accounts[0];
+        let source_token_account = &accounts[1];
+        let target_mint = &accounts[3];
+        let quote_mint = &accounts[4];
+
+        msg!("User: {:?}", user_account.key());
+        msg!("Target mint: {:?}", target_mint.key());
+        msg!("Quote mint: {:?}", quote_mint.key());
+        msg!("Amount in: {}", amount_in);
+        msg!("Min amount out: {}", min_amount_out);
+
+        // Создание менеджера DEX
+        let dex_manager = DexManager::new();
+
+        // Проверка баланса пользователя
+        let required_amount = if dex_manager.funded_by_lamports(accounts)? {
+            0
+        } else {
+            amount_in
+        };
+        Self::check_user_balance(
+            source_token_account,
+            quote_mint,
+            user_account,
+            required_amount,
+        )?;
+
+        // Выполнение обмена, разделенного между пулами
+        let split_result = dex_manager
+            .execute_split_swap(
+                program_id,
+                accounts,
+                target_mint.key(),
+                quote_mint.key(),
+                amount_in,
+                min_amount_out,
+            )
+            .inspect_err(|&e| {
+                msg!("Split swap failed: {:?}", e);
+            })?;
+
+        // Создание и возврат результата
+        let result = Self::build_split_result(&split_result)?;
+        let data = borsh::to_vec(&result).map_err(|_| AutoBuyerError::InvalidParameters)?;
+        set_return_data(&data);
+
+        // Логирование результата
+        msg!("=== Transaction Result ===");
+        msg!("Success: {}", result.success);
+        msg!("Amount Out: {}", result.amount_out);
+        msg!("Fee Paid: {}", result.fee_paid);
+        msg!("Timestamp: {}", result.timestamp);
+        msg!(
+            "Price per Unit: {}",
+            split_result.calculation.price_per_unit
+        );
+        msg!(
+            "Price Impact: {} bps",
+            split_result.calculation.price_impact_bps
+        );
+        for leg in &split_result.legs {
             msg!(
-                "Error: Insufficient accounts provided. Expected 22, got {}",
-                accounts.len()
+                "Leg: {:?} {} -> {}",
+                leg.quote.trading_pair.pool_config.pool_address,
+                leg.amount_in,
+                leg.amount_received
             );
-            return Err(AutoBuyerError::InvalidParameters);
         }
+        msg!("========================");
+
+        msg!("Token purchase completed successfully");
+        Ok(())
+    }
+
+    /// Обработать покупку по многошаговому маршруту
+    fn process_buy_token_multi_hop(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        hops: u8,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> ProgramResult {
+        // Валидация входных параметров
+        if amount_in == 0 {
+            msg!("Error: Amount in cannot be zero");
+            return Err(AutoBuyerError::InvalidParameters.into());
+        }
+
+        if min_amount_out == 0 {
+            msg!("Error: Minimum amount out cannot be zero");
+            return Err(AutoBuyerError::InvalidParameters.into());
+        }
+
+        if !(2..=constants::MAX_ROUTE_HOPS).contains(&hops) {
+            msg!(
+                "Error: Route must have from 2 to {} hops, got {}",
+                constants::MAX_ROUTE_HOPS,
+                hops
+            );
+            return Err(AutoBuyerError::InvalidParameters.into());
+        }
+
+        // Валидация аккаунтов
+        Self::validate_accounts(accounts)?;
+        Self::validate_route_accounts(accounts, hops as usize)?;
+
+        // Извлечение аккаунтов
+        let user_account = &accounts[0];
+        let source_token_account = &accounts[1];
+        let target_mint = &accounts[3];
+        let quote_mint = &accounts[4];
+
+        msg!("User: {:?}", user_account.key());
+        msg!("Target mint: {:?}", target_mint.key());
+        msg!("Quote mint: {:?}", quote_mint.key());
+        msg!("Hops: {}", hops);
+        msg!("Amount in: {}", amount_in);
+        msg!("Min amount out: {}", min_amount_out);
+
+        // Создание менеджера DEX
+        let dex_manager = DexManager::new();
+
+        // Проверка баланса пользователя: первый шаг может оплачиваться лампортами
+        let required_amount = if dex_manager.route_funded_by_lamports(accounts, hops as usize)? {
+            0
+        } else {
+            amount_in
+        };
+        Self::check_user_balance(
+            source_token_account,
+            quote_mint,
+            user_account,
+            required_amount,
+        )?;
+
+        // Выполнение обмена по маршруту
+        let route_result = dex_manager
+            .execute_multi_hop_swap(
+                program_id,
+                accounts,
+                hops as usize,
+                amount_in,
+                min_amount_out,
+            )
+            .inspect_err(|&e| {
+                msg!("Multi-hop swap failed: {:?}", e);
+            })?;
+
+        // Создание и возврат результата
+        let result = Self::build_multi_hop_result(&route_result)?;
+        let data = borsh::to_vec(&result).map_err(|_| AutoBuyerError::InvalidParameters)?;
+        set_return_data(&data);
+
+        // Логирование результата
+        msg!("=== Transaction Result ===");
+        msg!("Success: {}", result.success);
+        msg!("Amount In: {}", route_result.amount_in);
+        msg!("Amount Out: {}", result.amount_out);
+        msg!("Timestamp: {}", result.timestamp);
+        for hop in &route_result.hops {
+            msg!(
+                "Hop: {:?} {} -> {}",
+                hop.quote.trading_pair.pool_config.pool_address,
+                hop.quote.calculation.amount_in,
+                hop.amount_received
+            );
+        }
+        msg!("========================");
+
+        msg!("Token purchase completed successfully");
+        Ok(())
+    }
+
+    /// Рассчитать котировку покупки и вернуть ее через return data
+    fn process_quote_buy(accounts: &[AccountInfo], amount_in: u64) -> ProgramResult {
+        if amount_in == 0 {
+            msg!("Error: Amount in cannot be zero");
+            return Err(AutoBuyerError::InvalidParameters.into());
+        }
+
+        // Валидация аккаунтов пула
+        Self::validate_pool_accounts(accounts)?;
+
+        let target_mint = &accounts[3];
+        let quote_mint = &accounts[4];
+
+        let dex_manager = DexManager::new();
+
+        let calculation = dex_manager
+            .quote_swap(accounts, target_mint.key(), quote_mint.key(), amount_in)
+            .inspect_err(|&e| {
+                msg!("Quote failed: {:?}", e);
+            })?;
+
+        let data = borsh::to_vec(&calculation).map_err(|_| AutoBuyerError::InvalidParameters)?;
+        set_return_data(&data);
+
+        msg!(
+            "Quote: {} -> {}",
+            calculation.amount_in,
+            calculation.amount_out
+        );
+        msg!("Fee: {}", calculation.fee_amount);
+        msg!("Price per Unit: {}", calculation.price_per_unit);
+        msg!("Price Impact: {} bps", calculation.price_impact_bps);
+        Ok(())
+    }
+
+    /// Валидация переданных аккаунтов
+    fn validate_accounts(accounts: &[AccountInfo]) -> Result<(), AutoBuyerError> {
+        Self::validate_pool_accounts(accounts)?;
 
         // Проверка, что пользователь подписал транзакцию
         if !accounts[0].is_signer() {
@@ -114,57 +494,210 @@ impl Processor {
             return Err(AutoBuyerError::InvalidParameters);
         }
 
-        // Проверка, что токеновые аккаунты принадлежат программе токенов
-        let token_program = &constants::TOKEN_PROGRAM_ID;
-
-        if accounts[1].owner() != token_program {
+        // Проверка, что токеновые аккаунты принадлежат программе токенов SPL или Token-2022
+        if !is_token_program(accounts[1].owner()) {
             msg!("Error: Source token account has invalid owner");
             return Err(AutoBuyerError::InvalidAccountOwner);
         }
 
-        if accounts[2].owner() != token_program {
+        if !is_token_program(accounts[2].owner()) {
             msg!("Error: Destination token account has invalid owner");
             return Err(AutoBuyerError::InvalidAccountOwner);
         }
 
-        // Проверка программы токенов
-        if accounts[9].key() != token_program {
-            msg!("Error: Invalid token program");
+        Ok(())
+    }
+
+    /// Валидация аккаунтов промежуточных токенов маршрута
+    fn validate_route_accounts(
+        accounts: &[AccountInfo],
+        hops: usize,
+    ) -> Result<(), AutoBuyerError> {
+        let intermediate_end = 5 + 2 * (hops - 1);
+        if accounts.len() <= intermediate_end {
+            msg!(
+                "Error: Insufficient accounts provided. Expected at least {}, got {}",
+                intermediate_end + 1,
+                accounts.len()
+            );
             return Err(AutoBuyerError::InvalidParameters);
         }
 
-        // Проверка Raydium программы
-        if accounts[5].key() != &constants::RAYDIUM_V4_PROGRAM_ID {
-            msg!("Error: Invalid Raydium program");
+        for index in (5..intermediate_end).step_by(2) {
+            let token_account = &accounts[index];
+            if !is_token_program(token_account.owner()) {
+                msg!("Error: Intermediate token account has invalid owner");
+                return Err(AutoBuyerError::InvalidAccountOwner);
+            }
+
+            // Промежуточный токен списывается следующим шагом от имени пользователя
+            Self::check_user_balance(token_account, &accounts[index + 1], &accounts[0], 0)?;
+        }
+
+        Ok(())
+    }
+
+    /// Валидация общих аккаунтов пула
+    ///
+    /// Раскладка аккаунтов начиная с программы DEX проверяется выбранным провайдером.
+    fn validate_pool_accounts(accounts: &[AccountInfo]) -> Result<(), AutoBuyerError> {
+        if accounts.len() < 7 {
+            msg!(
+                "Error: Insufficient accounts provided. Expected at least 7, got {}",
+                accounts.len()
+            );
             return Err(AutoBuyerError::InvalidParameters);
         }
 
         Ok(())
     }
 
-    /// Проверка баланса пользователя
+    /// Проверка аккаунта-источника и баланса пользователя
     fn check_user_balance(
-        _source_account: &AccountInfo,
+        source_account: &AccountInfo,
+        expected_mint: &AccountInfo,
+        authority: &AccountInfo,
         required_amount: u64,
     ) -> Result<(), AutoBuyerError> {
-        // Упрощенная проверка - предполагаем, что аккаунт валиден
-        // В реальной реализации здесь был бы анализ данных токенового аккаунта
+        let data = source_account
+            .try_borrow_data()
+            .map_err(|_| AutoBuyerError::InvalidParameters)?;
+        let token_account = TokenAccount::unpack(
+            data.get(..TokenAccount::LEN)
+                .ok_or(AutoBuyerError::InvalidParameters)?,
+        )
+        .map_err(|e| {
+            msg!("Token error: {:?}", e);
+            AutoBuyerError::InvalidParameters
+        })?;
+
+        if token_account.mint.to_bytes() != *expected_mint.key() {
+            msg!("Error: Source token account has invalid mint");
+            return Err(AutoBuyerError::InvalidTokenMint);
+        }
+
+        if token_account.is_frozen() {
+            msg!("Error: Source token account is frozen");
+            return Err(AutoBuyerError::AccountFrozen);
+        }
+
+        // Подписант должен быть владельцем или делегатом аккаунта
+        let available_amount = if token_account.owner.to_bytes() == *authority.key() {
+            token_account.amount
+        } else if token_account.delegate == COption::Some((*authority.key()).into()) {
+            token_account.amount.min(token_account.delegated_amount)
+        } else {
+            msg!("Error: Signer is neither owner nor delegate of source token account");
+            return Err(AutoBuyerError::InvalidTokenAuthority);
+        };
+
+        if available_amount < required_amount {
+            msg!(
+                "Error: Insufficient funds. Required: {}, available: {}",
+                required_amount,
+                available_amount
+            );
+            return Err(AutoBuyerError::InsufficientFunds);
+        }
+
         msg!("Balance check passed. Required: {}", required_amount);
         Ok(())
     }
 
+    /// Сформировать результат обмена с текущим временем кластера
+    fn build_result(swap_execution: &SwapExecution) -> Result<BuyResult, ProgramError> {
+        let clock = Clock::get()?;
+
+        Ok(BuyResult {
+            success: true,
+            amount_out: swap_execution.calculation.amount_out,
+            fee_paid: swap_execution.calculation.fee_amount,
+            timestamp: clock.unix_timestamp,
+            venue: Self::venue_quote(&swap_execution.selection.best),
+            runner_up: swap_execution
+                .selection
+                .runner_up
+                .as_ref()
+                .map(Self::venue_quote),
+        })
+    }
+
+    /// Котировка пула-кандидата для результата обмена
+    fn venue_quote(search_result: &PoolSearchResult) -> VenueQuote {
+        VenueQuote {
+            dex_program: search_result.dex_program,
+            pool: search_result.trading_pair.pool_config.pool_address,
+            amount_in: search_result.calculation.amount_in,
+            amount_out: search_result.calculation.amount_out,
+        }
+    }
+
+    /// Сформировать результат разделенной покупки с текущим временем кластера
+    fn build_split_result(
+        split_execution: &SplitExecution,
+    ) -> Result<SplitBuyResult, ProgramError> {
+        let clock = Clock::get()?;
+
+        Ok(SplitBuyResult {
+            success: true,
+            amount_out: split_execution.calculation.amount_out,
+            fee_paid: split_execution.calculation.fee_amount,
+            timestamp: clock.unix_timestamp,
+            legs: split_execution
+                .legs
+                .iter()
+                .map(|leg| VenueQuote {
+                    amount_in: leg.amount_in,
+                    ..Self::venue_quote(&leg.quote)
+                })
+                .collect(),
+        })
+    }
+
+    /// Сформировать результат покупки по маршруту с текущим временем кластера
+    fn build_multi_hop_result(
+        route_execution: &RouteExecution,
+    ) -> Result<MultiHopBuyResult, ProgramError> {
+        let clock = Clock::get()?;
+
+        Ok(MultiHopBuyResult {
+            success: true,
+            amount_out: route_execution.amount_out,
+            timestamp: clock.unix_timestamp,
+            hops: route_execution
+                .hops
+                .iter()
+                .map(|hop| Self::venue_quote(&hop.quote))
+                .collect(),
+        })
+    }
+
+    /// Вернуть результат вызывающей стороне через return data
+    fn return_result(result: &BuyResult) -> ProgramResult {
+        let data = borsh::to_vec(result).map_err(|_| AutoBuyerError::InvalidParameters)?;
+        set_return_data(&data);
+        Ok(())
+    }
+
     /// Логирование результата транзакции
-    fn log_transaction_result(
-        result: &BuyResult,
-        swap_calculation: &crate::state::SwapCalculation,
-    ) {
+    fn log_transaction_result(result: &BuyResult, swap_execution: &SwapExecution) {
+        let swap_calculation = &swap_execution.calculation;
+
         msg!("=== Transaction Result ===");
         msg!("Success: {}", result.success);
         msg!("Amount Out: {}", result.amount_out);
         msg!("Fee Paid: {}", result.fee_paid);
         msg!("Timestamp: {}", result.timestamp);
-        msg!("Price per Unit: {:.6}", swap_calculation.price_per_unit);
-        msg!("Slippage: {:.2}%", swap_calculation.slippage_percent);
+        msg!("Price per Unit: {}", swap_calculation.price_per_unit);
+        msg!("Price Impact: {} bps", swap_calculation.price_impact_bps);
+        msg!("Venue: {:?}", result.venue.pool);
+        if let Some(runner_up) = &result.runner_up {
+            msg!(
+                "Runner-up: {:?} (quoted {})",
+                runner_up.pool,
+                runner_up.amount_out
+            );
+        }
         msg!("========================");
     }
 }
@@ -1,3 +1,5 @@
+use core::fmt;
+
 use borsh::{BorshDeserialize, BorshSerialize};
 use pinocchio::pubkey::Pubkey;
 
@@ -29,8 +31,55 @@ pub struct TradingPair {
     pub pool_config: PoolConfig,
 }
 
+/// Цена в формате фиксированной точки Q64.64 (выходной токен за единицу входного)
+#[derive(
+    BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub struct PriceQ64(pub u128);
+
+impl PriceQ64 {
+    /// Количество дробных бит
+    pub const FRACTIONAL_BITS: u32 = 64;
+
+    /// Построить цену как отношение `numerator / denominator`
+    pub fn from_ratio(numerator: u64, denominator: u64) -> Option<Self> {
+        ((numerator as u128) << Self::FRACTIONAL_BITS)
+            .checked_div(denominator as u128)
+            .map(Self)
+    }
+
+    /// Отклонение от опорной цены в базисных пунктах (не более 100%)
+    pub fn impact_bps(&self, reference: PriceQ64) -> u16 {
+        if reference.0 == 0 {
+            return 0;
+        }
+
+        let diff = reference.0.abs_diff(self.0);
+        let bps = match diff.checked_mul(constants::BASIS_POINTS as u128) {
+            Some(scaled) => scaled / reference.0,
+            // Переполнение при опорной цене меньше 10000 означает отклонение больше 100%
+            None if reference.0 < constants::BASIS_POINTS as u128 => {
+                return constants::BASIS_POINTS;
+            }
+            None => diff / (reference.0 / constants::BASIS_POINTS as u128),
+        };
+
+        bps.min(constants::BASIS_POINTS as u128) as u16
+    }
+}
+
+impl fmt::Display for PriceQ64 {
+    /// Десятичное представление с шестью знаками после запятой
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let integer = self.0 >> Self::FRACTIONAL_BITS;
+        let fraction = self.0 & (u64::MAX as u128);
+        let micros = (fraction * 1_000_000) >> Self::FRACTIONAL_BITS;
+        write!(f, "{}.{:06}", integer, micros)
+    }
+}
+
 /// Результат расчета обмена
-#[derive(Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct SwapCalculation {
     /// Количество входного токена
     pub amount_in: u64,
@@ -39,9 +88,9 @@ pub struct SwapCalculation {
     /// Размер комиссии
     pub fee_amount: u64,
     /// Цена за единицу
-    pub price_per_unit: f64,
-    /// Проскальзывание в процентах
-    pub slippage_percent: f64,
+    pub price_per_unit: PriceQ64,
+    /// Влияние на цену в базисных пунктах
+    pub price_impact_bps: u16,
 }
 
 /// Константы программы
@@ -61,6 +110,78 @@ pub mod constants {
         0x8b, 0x5c,
     ];
 
+    /// Raydium CPMM (CP-Swap) программа ID
+    pub const RAYDIUM_CPMM_PROGRAM_ID: Pubkey = [
+        169, 42, 90, 139, 79, 41, 89, 82, 132, 37, 80, 170, 147, 253, 91, 149, 181, 172, 230, 168,
+        235, 146, 12, 147, 148, 46, 67, 105, 12, 32, 236, 115,
+    ];
+
+    /// Raydium CLMM программа ID
+    pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey = [
+        165, 213, 202, 158, 4, 207, 93, 181, 144, 183, 20, 186, 47, 227, 44, 177, 89, 19, 63, 193,
+        193, 146, 183, 34, 87, 253, 7, 211, 156, 176, 64, 30,
+    ];
+
+    /// Orca Whirlpool программа ID
+    pub const WHIRLPOOL_PROGRAM_ID: Pubkey = [
+        14, 3, 104, 95, 142, 144, 144, 83, 228, 88, 18, 28, 102, 245, 167, 106, 237, 199, 112, 106,
+        161, 28, 130, 248, 170, 149, 42, 143, 43, 120, 121, 169,
+    ];
+
+    /// Meteora DLMM программа ID
+    pub const METEORA_DLMM_PROGRAM_ID: Pubkey = [
+        4, 233, 225, 47, 188, 132, 232, 38, 201, 50, 204, 233, 226, 100, 12, 206, 21, 89, 12, 28,
+        98, 115, 176, 146, 87, 8, 224, 217, 220, 132, 196, 68,
+    ];
+
+    /// Meteora Dynamic AMM программа ID
+    pub const METEORA_DAMM_PROGRAM_ID: Pubkey = [
+        204, 248, 2, 212, 204, 204, 132, 215, 251, 33, 181, 247, 59, 73, 216, 26, 22, 197, 180,
+        200, 142, 227, 35, 148, 225, 201, 29, 53, 136, 204, 64, 128,
+    ];
+
+    /// Meteora Dynamic Vault программа ID
+    pub const METEORA_VAULT_PROGRAM_ID: Pubkey = [
+        15, 191, 232, 132, 109, 104, 92, 189, 198, 44, 202, 126, 4, 199, 232, 246, 141, 204, 49,
+        58, 179, 18, 119, 226, 224, 17, 42, 46, 192, 224, 82, 229,
+    ];
+
+    /// Pump.fun программа ID
+    pub const PUMP_FUN_PROGRAM_ID: Pubkey = [
+        1, 86, 224, 246, 147, 102, 90, 207, 68, 219, 21, 104, 191, 23, 91, 170, 81, 137, 203, 151,
+        245, 210, 255, 59, 101, 93, 43, 182, 253, 109, 24, 176,
+    ];
+
+    /// PumpSwap AMM программа ID
+    pub const PUMP_SWAP_PROGRAM_ID: Pubkey = [
+        12, 20, 222, 252, 130, 94, 198, 118, 148, 37, 8, 24, 187, 101, 64, 101, 244, 41, 141, 49,
+        86, 213, 113, 180, 212, 248, 9, 12, 24, 233, 168, 99,
+    ];
+
+    /// Программа комиссий pump.fun и PumpSwap ID
+    pub const PUMP_FEE_PROGRAM_ID: Pubkey = [
+        12, 53, 255, 169, 5, 90, 142, 86, 141, 168, 247, 188, 7, 86, 21, 39, 76, 241, 201, 44, 164,
+        31, 64, 0, 156, 81, 106, 164, 20, 194, 124, 112,
+    ];
+
+    /// OpenBook v2 программа ID
+    pub const OPENBOOK_V2_PROGRAM_ID: Pubkey = [
+        11, 254, 191, 189, 251, 171, 250, 208, 180, 101, 113, 191, 149, 140, 30, 184, 36, 120, 123,
+        176, 153, 75, 177, 132, 107, 41, 120, 7, 30, 23, 153, 190,
+    ];
+
+    /// Phoenix программа ID
+    pub const PHOENIX_PROGRAM_ID: Pubkey = [
+        5, 208, 234, 79, 51, 115, 112, 19, 165, 99, 224, 147, 72, 237, 182, 244, 89, 61, 145, 252,
+        118, 65, 249, 36, 124, 36, 65, 168, 66, 161, 187, 235,
+    ];
+
+    /// Saber StableSwap программа ID
+    pub const SABER_PROGRAM_ID: Pubkey = [
+        6, 132, 219, 73, 108, 250, 223, 231, 213, 161, 209, 140, 211, 155, 150, 205, 60, 136, 219,
+        100, 132, 193, 188, 117, 104, 24, 57, 17, 155, 106, 255, 182,
+    ];
+
     /// Serum программа ID
     pub const SERUM_PROGRAM_ID: Pubkey = [
         0x9, 0x71, 0x2, 0x4, 0xac, 0x5, 0x39, 0x36, 0x51, 0x2, 0x4, 0xac, 0x5, 0x39, 0x36, 0x51,
@@ -73,15 +194,33 @@ pub mod constants {
         0x2d, 0x1, 0x4, 0x7, 0x5, 0x4, 0x44, 0x4, 0x4, 0x4, 0x4, 0x4, 0x4, 0x4, 0x4, 0x4,
     ];
 
+    /// Системная программа
+    pub const SYSTEM_PROGRAM_ID: Pubkey = [0; 32];
+
     /// Программа токенов SPL
     pub const TOKEN_PROGRAM_ID: Pubkey = [
         6, 221, 246, 225, 215, 101, 161, 147, 217, 203, 225, 70, 206, 235, 121, 172, 28, 180, 133,
         237, 95, 91, 55, 145, 58, 140, 245, 133, 126, 255, 0, 169,
     ];
 
+    /// Программа токенов Token-2022
+    pub const TOKEN_2022_PROGRAM_ID: Pubkey = [
+        6, 221, 246, 225, 238, 117, 143, 222, 24, 66, 93, 188, 228, 108, 205, 218, 182, 26, 252,
+        77, 131, 185, 13, 39, 254, 189, 249, 40, 216, 161, 139, 252,
+    ];
+
+    /// Программа ассоциированных токеновых аккаунтов
+    pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = [
+        140, 151, 37, 143, 78, 36, 137, 241, 187, 61, 16, 41, 20, 142, 13, 131, 11, 90, 19, 153,
+        218, 255, 16, 132, 4, 142, 123, 216, 219, 233, 248, 89,
+    ];
+
     /// Максимальное проскальзывание (1%)
     pub const MAX_SLIPPAGE_BPS: u16 = 100;
 
     /// Базисные пункты (10000 = 100%)
     pub const BASIS_POINTS: u16 = 10000;
+
+    /// Максимальное количество обменов в многошаговом маршруте
+    pub const MAX_ROUTE_HOPS: u8 = 3;
 }